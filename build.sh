@@ -20,6 +20,12 @@ if ! pkg-config --exists gtk4; then
     exit 1
 fi
 
+# FileDialog and AlertDialog need GTK 4.10
+if ! pkg-config --atleast-version=4.10 gtk4; then
+    echo "GTK 4.10 or newer is required (found $(pkg-config --modversion gtk4))."
+    exit 1
+fi
+
 echo "Building application..."
 cargo build --release
 
@@ -0,0 +1,185 @@
+use gtk::prelude::*;
+use gtk::{gdk, gio};
+use std::path::Path;
+use std::process::Command;
+
+use crate::config;
+
+// Preferred applications (file manager, terminal, video player), resolved
+// through gio::AppInfo and the shared-mime-info database. Choices are stored
+// by desktop id in the [Applications] group; an empty id follows the system
+// default. Every "open" in the app goes through here and launches
+// asynchronously.
+
+const CONFIG_GROUP: &str = "Applications";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppRole {
+    FileManager,
+    Terminal,
+    VideoPlayer,
+}
+
+impl AppRole {
+    pub const ALL: [AppRole; 3] = [AppRole::FileManager, AppRole::Terminal, AppRole::VideoPlayer];
+
+    fn key(self) -> &'static str {
+        match self {
+            AppRole::FileManager => "file-manager",
+            AppRole::Terminal => "terminal",
+            AppRole::VideoPlayer => "video-player",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AppRole::FileManager => "File manager",
+            AppRole::Terminal => "Terminal",
+            AppRole::VideoPlayer => "Video player",
+        }
+    }
+
+    // Terminals have no mime type; they are found by desktop category
+    fn content_type(self) -> Option<&'static str> {
+        match self {
+            AppRole::FileManager => Some("inode/directory"),
+            AppRole::Terminal => None,
+            AppRole::VideoPlayer => Some("video/mp4"),
+        }
+    }
+}
+
+fn is_terminal(app_info: &gio::AppInfo) -> bool {
+    app_info
+        .id()
+        .and_then(|id| gio::DesktopAppInfo::new(&id))
+        .and_then(|desktop| desktop.categories())
+        .is_some_and(|categories| categories.split(';').any(|category| category == "TerminalEmulator"))
+}
+
+// Installed applications that can fill a role
+pub fn candidates(role: AppRole) -> Vec<gio::AppInfo> {
+    let apps = match role.content_type() {
+        Some(content_type) => gio::AppInfo::all_for_type(content_type),
+        None => gio::AppInfo::all().into_iter().filter(is_terminal).collect(),
+    };
+
+    let mut seen = Vec::new();
+    apps.into_iter()
+        .filter(|app_info| app_info.should_show())
+        .filter(|app_info| {
+            let id = app_info.id().map(|id| id.to_string()).unwrap_or_default();
+            let new = !seen.contains(&id);
+            seen.push(id);
+            new
+        })
+        .collect()
+}
+
+// Desktop id of the user's choice, "" for the system default
+pub fn preferred_id(role: AppRole) -> String {
+    config::get_string(CONFIG_GROUP, role.key(), "")
+}
+
+pub fn set_preferred_id(role: AppRole, id: &str) {
+    config::set_string(CONFIG_GROUP, role.key(), id);
+}
+
+// The application to use: the preferred one while it is installed, else the
+// system default
+pub fn resolve(role: AppRole) -> Option<gio::AppInfo> {
+    let id = preferred_id(role);
+    if !id.is_empty() {
+        match gio::DesktopAppInfo::new(&id) {
+            Some(desktop) => return Some(desktop.upcast()),
+            None => eprintln!("Preferred {} {} is not installed", role.label().to_lowercase(), id),
+        }
+    }
+
+    match role.content_type() {
+        Some(content_type) => gio::AppInfo::default_for_type(content_type, false),
+        None => candidates(role).into_iter().next(),
+    }
+}
+
+fn launch_context() -> Option<gdk::AppLaunchContext> {
+    gdk::Display::default().map(|display| display.app_launch_context())
+}
+
+fn show_error(message: &str) {
+    eprintln!("{}", message);
+
+    let parent = gio::Application::default()
+        .and_downcast::<gtk::Application>()
+        .and_then(|app| app.active_window());
+
+    gtk::AlertDialog::builder()
+        .message("Cannot open")
+        .detail(message)
+        .buttons(["OK"])
+        .modal(true)
+        .build()
+        .show(parent.as_ref());
+}
+
+fn launch(app_info: &gio::AppInfo, path: &Path) {
+    let uri = gio::File::for_path(path).uri();
+    let name = app_info.name();
+    let path = path.display().to_string();
+
+    app_info.launch_uris_async(&[&uri], launch_context().as_ref(), gio::Cancellable::NONE, move |result| {
+        if let Err(e) = result {
+            show_error(&format!("{} could not open {}: {}", name, path, e));
+        }
+    });
+}
+
+// Open a file or folder: folders in the file manager, videos in the video
+// player, anything else in the default application for its type
+pub fn open(path: &Path) {
+    if !path.exists() {
+        show_error(&format!("{} does not exist", path.display()));
+        return;
+    }
+
+    let role = if path.is_dir() {
+        Some(AppRole::FileManager)
+    } else {
+        let (content_type, _) = gio::content_type_guess(Some(path), &[]);
+        gio::content_type_is_a(&content_type, "video/*").then_some(AppRole::VideoPlayer)
+    };
+
+    if let Some(app_info) = role.and_then(resolve) {
+        launch(&app_info, path);
+        return;
+    }
+
+    match role {
+        // Without a file manager, a terminal in the folder still gets the user there
+        Some(AppRole::FileManager) => open_terminal(path),
+        _ => open_uri(&gio::File::for_path(path).uri()),
+    }
+}
+
+// Open a web link or other URI in its default handler
+pub fn open_uri(uri: &str) {
+    let owned_uri = uri.to_string();
+    gio::AppInfo::launch_default_for_uri_async(uri, launch_context().as_ref(), gio::Cancellable::NONE, move |result| {
+        if let Err(e) = result {
+            show_error(&format!("No application can open {}: {}", owned_uri, e));
+        }
+    });
+}
+
+// Terminals take no file argument, so start one inside the directory
+pub fn open_terminal(dir: &Path) {
+    let Some(app_info) = resolve(AppRole::Terminal) else {
+        show_error(&format!("No file manager or terminal is installed to open {}", dir.display()));
+        return;
+    };
+
+    // spawn() does not wait for the terminal to exit
+    if let Err(e) = Command::new(app_info.executable()).current_dir(dir).spawn() {
+        show_error(&format!("{} could not be started: {}", app_info.name(), e));
+    }
+}
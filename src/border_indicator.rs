@@ -0,0 +1,94 @@
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config;
+use crate::hyprland;
+use crate::recording;
+
+// Marks the desktop while recording by recolouring Hyprland's active border.
+// The original value is written to disk before overriding it, so a crash
+// never leaves the border red: the next start restores it.
+
+const BORDER_OPTION: &str = "general:col.active_border";
+pub const DEFAULT_COLOUR: &str = "rgba(ff3333ee)";
+
+pub fn is_enabled() -> bool {
+    config::get_bool("Recording", "border-indicator", false)
+}
+
+pub fn set_enabled(enabled: bool) {
+    config::set_bool("Recording", "border-indicator", enabled);
+}
+
+pub fn colour() -> String {
+    config::get_string("Recording", "border-indicator-colour", DEFAULT_COLOUR)
+}
+
+pub fn set_colour(colour: &str) {
+    config::set_string("Recording", "border-indicator-colour", colour);
+}
+
+fn saved_border_file() -> PathBuf {
+    config::data_dir().join("saved-active-border")
+}
+
+pub fn install() {
+    // A leftover saved value means we crashed (or were killed) mid-recording
+    if !recording::is_recording() {
+        restore();
+    }
+
+    recording::subscribe(|session| {
+        if session.is_some() {
+            if is_enabled() {
+                apply();
+            }
+        } else {
+            restore();
+        }
+    })
+    .keep();
+}
+
+fn apply() {
+    let path = saved_border_file();
+
+    // Save the user's value only once; a second apply must not save our own colour
+    if !path.exists() {
+        let Some(original) = hyprland::get_option(BORDER_OPTION)
+            .as_ref()
+            .and_then(hyprland::gradient_keyword_value)
+        else {
+            eprintln!("Could not read {}, not changing the border", BORDER_OPTION);
+            return;
+        };
+
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Err(e) = fs::write(&path, &original) {
+            eprintln!("Failed to save original border colour: {}", e);
+            return;
+        }
+    }
+
+    if let Err(e) = hyprland::keyword(BORDER_OPTION, &colour()) {
+        eprintln!("{}", e);
+    }
+}
+
+fn restore() {
+    let path = saved_border_file();
+    let Ok(original) = fs::read_to_string(&path) else {
+        return;
+    };
+
+    let original = original.trim();
+    match hyprland::keyword(BORDER_OPTION, original) {
+        Ok(()) => {
+            println!("Restored active border: {}", original);
+            let _ = fs::remove_file(&path);
+        }
+        Err(e) => eprintln!("Failed to restore active border: {}", e),
+    }
+}
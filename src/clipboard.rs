@@ -0,0 +1,45 @@
+use gtk::gio;
+use gtk::prelude::*;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+// Wayland clipboard through wl-copy, which keeps serving the selection after
+// ummit-settings exits
+
+fn wl_copy(mime_type: &str, data: &[u8]) -> Result<(), String> {
+    let mut child = Command::new("wl-copy")
+        .args(["--type", mime_type])
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run wl-copy: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(data)
+            .map_err(|e| format!("Failed to write to wl-copy: {}", e))?;
+    }
+
+    match child.wait() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(_) => Err("wl-copy failed".to_string()),
+        Err(e) => Err(format!("Failed to run wl-copy: {}", e)),
+    }
+}
+
+// The file itself, as a URI list that file managers, browsers and chat apps
+// paste as an attachment
+pub fn copy_file(path: &Path) -> Result<(), String> {
+    let uri = gio::File::for_path(path).uri();
+    wl_copy("text/uri-list", format!("{}\r\n", uri).as_bytes())
+}
+
+pub fn copy_text(text: &str) -> Result<(), String> {
+    wl_copy("text/plain;charset=utf-8", text.as_bytes())
+}
+
+// Image contents, for pasting screenshots into editors
+pub fn copy_image(path: &Path) -> Result<(), String> {
+    let data = std::fs::read(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    wl_copy("image/png", &data)
+}
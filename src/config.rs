@@ -0,0 +1,79 @@
+use gtk::glib;
+use std::fs;
+use std::path::PathBuf;
+
+// Persistent application settings, stored as a GKeyFile in
+// $XDG_CONFIG_HOME/ummit-settings/settings.ini
+
+pub fn config_dir() -> PathBuf {
+    glib::user_config_dir().join("ummit-settings")
+}
+
+// Application data such as saved state to restore after a crash
+pub fn data_dir() -> PathBuf {
+    glib::user_data_dir().join("ummit-settings")
+}
+
+fn settings_file() -> PathBuf {
+    config_dir().join("settings.ini")
+}
+
+// Load the settings file (an empty key file if it does not exist yet)
+pub fn load() -> glib::KeyFile {
+    let key_file = glib::KeyFile::new();
+    let path = settings_file();
+
+    if path.exists()
+        && let Err(e) = key_file.load_from_file(&path, glib::KeyFileFlags::KEEP_COMMENTS)
+    {
+        eprintln!("Failed to read settings from {}: {}", path.display(), e);
+    }
+
+    key_file
+}
+
+pub fn save(key_file: &glib::KeyFile) {
+    let path = settings_file();
+
+    if let Err(e) = fs::create_dir_all(config_dir()) {
+        eprintln!("Failed to create config directory: {}", e);
+        return;
+    }
+
+    if let Err(e) = key_file.save_to_file(&path) {
+        eprintln!("Failed to save settings to {}: {}", path.display(), e);
+    }
+}
+
+pub fn get_string(group: &str, key: &str, default: &str) -> String {
+    load()
+        .string(group, key)
+        .map(|value| value.to_string())
+        .unwrap_or_else(|_| default.to_string())
+}
+
+pub fn set_string(group: &str, key: &str, value: &str) {
+    let key_file = load();
+    key_file.set_string(group, key, value);
+    save(&key_file);
+}
+
+pub fn get_bool(group: &str, key: &str, default: bool) -> bool {
+    load().boolean(group, key).unwrap_or(default)
+}
+
+pub fn set_bool(group: &str, key: &str, value: bool) {
+    let key_file = load();
+    key_file.set_boolean(group, key, value);
+    save(&key_file);
+}
+
+pub fn get_int(group: &str, key: &str, default: i32) -> i32 {
+    load().integer(group, key).unwrap_or(default)
+}
+
+pub fn set_int(group: &str, key: &str, value: i32) {
+    let key_file = load();
+    key_file.set_integer(group, key, value);
+    save(&key_file);
+}
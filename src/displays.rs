@@ -0,0 +1,916 @@
+use gtk::prelude::*;
+use gtk::{cairo, glib, Box, Button, Label, Orientation};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use crate::hooks::{self, HookEvent};
+use crate::hyprland;
+use crate::json::JsonValue;
+use crate::revert;
+
+// Display configuration. Outputs are read from `hyprctl monitors all -j`,
+// arranged on a canvas and applied as `monitor=` rules through the
+// apply-with-revert flow, which persists confirmed rules in the managed
+// include file.
+
+const TRANSFORMS: [&str; 8] = [
+    "Normal",
+    "90°",
+    "180°",
+    "270°",
+    "Flipped",
+    "Flipped 90°",
+    "Flipped 180°",
+    "Flipped 270°",
+];
+
+// Edges closer than this (in logical pixels) snap together after a drag
+const SNAP_DISTANCE: f64 = 64.0;
+const CANVAS_MARGIN: f64 = 16.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mode {
+    pub width: i64,
+    pub height: i64,
+    pub refresh: f64,
+}
+
+impl Mode {
+    // "2560x1440@143.97Hz" as listed in availableModes
+    fn parse(text: &str) -> Option<Mode> {
+        let (size, refresh) = text.trim().split_once('@')?;
+        let (width, height) = size.split_once('x')?;
+        Some(Mode {
+            width: width.parse().ok()?,
+            height: height.parse().ok()?,
+            refresh: refresh.trim_end_matches("Hz").parse().ok()?,
+        })
+    }
+
+    fn resolution(&self) -> String {
+        format!("{}x{}", self.width, self.height)
+    }
+}
+
+// One output with the settings a `monitor=` rule can express
+#[derive(Debug, Clone, PartialEq)]
+pub struct Monitor {
+    pub name: String,
+    pub description: String,
+    pub enabled: bool,
+    pub mode: Mode,
+    pub x: i64,
+    pub y: i64,
+    pub scale: f64,
+    pub transform: i64,
+    pub mirror_of: Option<String>,
+    pub modes: Vec<Mode>,
+}
+
+impl Monitor {
+    fn from_json(value: &JsonValue) -> Option<Monitor> {
+        let modes = value
+            .get("availableModes")
+            .and_then(JsonValue::as_array)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|mode| mode.as_str().and_then(Mode::parse))
+            .collect();
+
+        Some(Monitor {
+            name: value.str_field("name")?.to_string(),
+            description: value.str_field("description").unwrap_or_default().to_string(),
+            enabled: value.bool_field("disabled") != Some(true),
+            mode: Mode {
+                width: value.i64_field("width")?,
+                height: value.i64_field("height")?,
+                refresh: value.f64_field("refreshRate").unwrap_or(60.0),
+            },
+            x: value.i64_field("x").unwrap_or(0),
+            y: value.i64_field("y").unwrap_or(0),
+            scale: value.f64_field("scale").unwrap_or(1.0),
+            transform: value.i64_field("transform").unwrap_or(0),
+            mirror_of: value
+                .str_field("mirrorOf")
+                .filter(|mirror| *mirror != "none")
+                .map(str::to_string),
+            modes,
+        })
+    }
+
+    // The `monitor=` rule value for this output
+    pub fn rule(&self) -> String {
+        if !self.enabled {
+            return format!("{},disable", self.name);
+        }
+
+        let scale = (self.scale * 100.0).round() / 100.0;
+        let mut rule = format!(
+            "{},{}x{}@{:.2},{}x{},{}",
+            self.name, self.mode.width, self.mode.height, self.mode.refresh, self.x, self.y, scale
+        );
+        if self.transform != 0 {
+            rule.push_str(&format!(",transform,{}", self.transform));
+        }
+        if let Some(mirror) = &self.mirror_of {
+            rule.push_str(&format!(",mirror,{}", mirror));
+        }
+        rule
+    }
+
+    // Size in the layout, after scaling and rotation
+    fn logical_size(&self) -> (f64, f64) {
+        let (width, height) = if self.transform % 2 == 1 {
+            (self.mode.height, self.mode.width)
+        } else {
+            (self.mode.width, self.mode.height)
+        };
+        (width as f64 / self.scale, height as f64 / self.scale)
+    }
+
+    // Shown on the canvas; mirrored outputs share another's space
+    fn is_placed(&self) -> bool {
+        self.enabled && self.mirror_of.is_none()
+    }
+}
+
+// Parse `hyprctl monitors all -j`
+pub fn parse_monitors(value: &JsonValue) -> Vec<Monitor> {
+    value
+        .as_array()
+        .unwrap_or_default()
+        .iter()
+        .filter_map(Monitor::from_json)
+        .collect()
+}
+
+pub fn read_monitors() -> Vec<Monitor> {
+    hyprland::query("monitors all")
+        .map(|value| parse_monitors(&value))
+        .unwrap_or_default()
+}
+
+// Rules for the outputs whose settings changed
+pub fn changed_rules(current: &[Monitor], edited: &[Monitor]) -> Vec<revert::Change> {
+    edited
+        .iter()
+        .filter_map(|monitor| {
+            let previous = current.iter().find(|existing| existing.name == monitor.name)?;
+            (previous.rule() != monitor.rule()).then(|| revert::Change {
+                option: "monitor".to_string(),
+                value: monitor.rule(),
+                previous: previous.rule(),
+            })
+        })
+        .collect()
+}
+
+// Align an output's edges with its neighbours, then move the layout so it
+// starts at 0x0
+fn snap(monitors: &mut [Monitor], index: usize) {
+    let (width, height) = monitors[index].logical_size();
+    let (x, y) = (monitors[index].x as f64, monitors[index].y as f64);
+
+    let mut best_x: Option<f64> = None;
+    let mut best_y: Option<f64> = None;
+    for (other_index, other) in monitors.iter().enumerate() {
+        if other_index == index || !other.is_placed() {
+            continue;
+        }
+        let (other_width, other_height) = other.logical_size();
+        let (other_x, other_y) = (other.x as f64, other.y as f64);
+
+        for candidate in [other_x + other_width, other_x - width, other_x, other_x + other_width - width] {
+            if (candidate - x).abs() < SNAP_DISTANCE && best_x.is_none_or(|best| (candidate - x).abs() < (best - x).abs()) {
+                best_x = Some(candidate);
+            }
+        }
+        for candidate in [other_y + other_height, other_y - height, other_y, other_y + other_height - height] {
+            if (candidate - y).abs() < SNAP_DISTANCE && best_y.is_none_or(|best| (candidate - y).abs() < (best - y).abs()) {
+                best_y = Some(candidate);
+            }
+        }
+    }
+
+    monitors[index].x = best_x.unwrap_or(x).round() as i64;
+    monitors[index].y = best_y.unwrap_or(y).round() as i64;
+
+    let placed = monitors.iter().filter(|monitor| monitor.is_placed());
+    let min_x = placed.clone().map(|monitor| monitor.x).min().unwrap_or(0);
+    let min_y = placed.map(|monitor| monitor.y).min().unwrap_or(0);
+    for monitor in monitors.iter_mut() {
+        monitor.x -= min_x;
+        monitor.y -= min_y;
+    }
+}
+
+// Mapping between layout and canvas coordinates
+#[derive(Debug, Clone, Copy)]
+struct Layout {
+    zoom: f64,
+    min_x: f64,
+    min_y: f64,
+    offset_x: f64,
+    offset_y: f64,
+}
+
+impl Layout {
+    fn fit(monitors: &[Monitor], width: f64, height: f64) -> Layout {
+        let placed: Vec<&Monitor> = monitors.iter().filter(|monitor| monitor.is_placed()).collect();
+        let min_x = placed.iter().map(|monitor| monitor.x as f64).fold(f64::INFINITY, f64::min);
+        let min_y = placed.iter().map(|monitor| monitor.y as f64).fold(f64::INFINITY, f64::min);
+        let max_x = placed
+            .iter()
+            .map(|monitor| monitor.x as f64 + monitor.logical_size().0)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let max_y = placed
+            .iter()
+            .map(|monitor| monitor.y as f64 + monitor.logical_size().1)
+            .fold(f64::NEG_INFINITY, f64::max);
+
+        if placed.is_empty() {
+            return Layout { zoom: 1.0, min_x: 0.0, min_y: 0.0, offset_x: CANVAS_MARGIN, offset_y: CANVAS_MARGIN };
+        }
+
+        // Leave room to drag outputs past the current edges
+        let (span_x, span_y) = ((max_x - min_x) * 1.5, (max_y - min_y) * 1.5);
+        let zoom = ((width - 2.0 * CANVAS_MARGIN) / span_x).min((height - 2.0 * CANVAS_MARGIN) / span_y);
+
+        Layout {
+            zoom,
+            min_x,
+            min_y,
+            offset_x: (width - (max_x - min_x) * zoom) / 2.0,
+            offset_y: (height - (max_y - min_y) * zoom) / 2.0,
+        }
+    }
+
+    fn rectangle(&self, monitor: &Monitor) -> (f64, f64, f64, f64) {
+        let (width, height) = monitor.logical_size();
+        (
+            self.offset_x + (monitor.x as f64 - self.min_x) * self.zoom,
+            self.offset_y + (monitor.y as f64 - self.min_y) * self.zoom,
+            width * self.zoom,
+            height * self.zoom,
+        )
+    }
+}
+
+struct DisplaysState {
+    // As reported by Hyprland
+    current: Vec<Monitor>,
+    // With the user's unapplied edits
+    edited: Vec<Monitor>,
+    selected: usize,
+    // Frozen while dragging so the canvas does not rescale under the pointer
+    drag: Option<(Layout, i64, i64)>,
+}
+
+fn draw(state: &DisplaysState, cr: &cairo::Context, width: f64, height: f64) {
+    let layout = state
+        .drag
+        .map(|(layout, _, _)| layout)
+        .unwrap_or_else(|| Layout::fit(&state.edited, width, height));
+
+    for (index, monitor) in state.edited.iter().enumerate() {
+        if !monitor.is_placed() {
+            continue;
+        }
+
+        let (x, y, w, h) = layout.rectangle(monitor);
+        if index == state.selected {
+            cr.set_source_rgba(0.21, 0.52, 0.89, 0.9);
+        } else {
+            cr.set_source_rgba(0.5, 0.5, 0.5, 0.6);
+        }
+        cr.rectangle(x + 1.0, y + 1.0, w - 2.0, h - 2.0);
+        let _ = cr.fill_preserve();
+        cr.set_source_rgba(1.0, 1.0, 1.0, 0.8);
+        cr.set_line_width(1.0);
+        let _ = cr.stroke();
+
+        cr.set_font_size(12.0);
+        let _ = cr.text_extents(&monitor.name).map(|extents| {
+            cr.move_to(x + (w - extents.width()) / 2.0, y + (h + extents.height()) / 2.0);
+        });
+        let _ = cr.show_text(&monitor.name);
+    }
+}
+
+fn setting_row<W: IsA<gtk::Widget>>(label_text: &str, widget: &W) -> Box {
+    let row = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(12)
+        .build();
+
+    let label = Label::builder()
+        .label(label_text)
+        .halign(gtk::Align::Start)
+        .hexpand(true)
+        .build();
+
+    row.append(&label);
+    row.append(widget);
+    row
+}
+
+fn set_strings(dropdown: &gtk::DropDown, strings: &[String]) {
+    let strings: Vec<&str> = strings.iter().map(String::as_str).collect();
+    dropdown.set_model(Some(&gtk::StringList::new(&strings)));
+}
+
+pub fn create_section() -> Box {
+    let section = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(8)
+        .build();
+
+    let monitors = read_monitors();
+    let state = Rc::new(RefCell::new(DisplaysState {
+        current: monitors.clone(),
+        edited: monitors,
+        selected: 0,
+        drag: None,
+    }));
+
+    let hint_label = Label::builder()
+        .label("Drag the displays to arrange them, then Apply. Changes revert after 15 seconds unless you keep them.")
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .css_classes(["dim-label"])
+        .build();
+    section.append(&hint_label);
+
+    let canvas = gtk::DrawingArea::builder()
+        .height_request(220)
+        .hexpand(true)
+        .build();
+    section.append(&canvas);
+
+    {
+        let state = state.clone();
+        canvas.set_draw_func(move |_, cr, width, height| {
+            draw(&state.borrow(), cr, width as f64, height as f64);
+        });
+    }
+
+    // Per-output controls
+    let output_dropdown = gtk::DropDown::from_strings(&[]);
+    let enabled_switch = gtk::Switch::builder().valign(gtk::Align::Center).build();
+    let resolution_dropdown = gtk::DropDown::from_strings(&[]);
+    let refresh_dropdown = gtk::DropDown::from_strings(&[]);
+    let scale_spin = gtk::SpinButton::with_range(0.25, 4.0, 0.05);
+    scale_spin.set_digits(2);
+    let transform_dropdown = gtk::DropDown::from_strings(&TRANSFORMS);
+    let mirror_dropdown = gtk::DropDown::from_strings(&[]);
+    let position_label = Label::builder().halign(gtk::Align::End).build();
+
+    section.append(&setting_row("Display", &output_dropdown));
+    section.append(&setting_row("Enabled", &enabled_switch));
+    section.append(&setting_row("Resolution", &resolution_dropdown));
+    section.append(&setting_row("Refresh rate", &refresh_dropdown));
+    section.append(&setting_row("Scale", &scale_spin));
+    section.append(&setting_row("Rotation", &transform_dropdown));
+    section.append(&setting_row("Mirror", &mirror_dropdown));
+    section.append(&setting_row("Position", &position_label));
+
+    let button_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .margin_top(8)
+        .build();
+
+    let apply_btn = Button::builder()
+        .label("Apply")
+        .css_classes(["suggested-action"])
+        .build();
+
+    let reset_btn = Button::builder()
+        .label("Reset")
+        .tooltip_text("Discard changes and re-read the displays")
+        .build();
+
+    button_box.append(&apply_btn);
+    button_box.append(&reset_btn);
+    section.append(&button_box);
+
+    let status_label = Label::builder()
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .css_classes(["dim-label"])
+        .build();
+    section.append(&status_label);
+
+    // Set while the controls are filled in from the state, so their
+    // handlers do not write back
+    let updating = Rc::new(Cell::new(false));
+
+    // Resolutions and rates offered for the selected output
+    let resolutions = |monitor: &Monitor| -> Vec<String> {
+        let mut resolutions: Vec<String> = Vec::new();
+        for mode in monitor.modes.iter().chain([&monitor.mode]) {
+            if !resolutions.contains(&mode.resolution()) {
+                resolutions.push(mode.resolution());
+            }
+        }
+        resolutions
+    };
+    let rates = |monitor: &Monitor| -> Vec<f64> {
+        let mut rates: Vec<f64> = monitor
+            .modes
+            .iter()
+            .filter(|mode| mode.width == monitor.mode.width && mode.height == monitor.mode.height)
+            .map(|mode| mode.refresh)
+            .collect();
+        if !rates.iter().any(|rate| (rate - monitor.mode.refresh).abs() < 0.01) {
+            rates.push(monitor.mode.refresh);
+        }
+        rates.sort_by(|a, b| b.total_cmp(a));
+        rates
+    };
+
+    let load_controls: Rc<dyn Fn()> = {
+        let state = state.clone();
+        let updating = updating.clone();
+        let output_dropdown = output_dropdown.clone();
+        let enabled_switch = enabled_switch.clone();
+        let resolution_dropdown = resolution_dropdown.clone();
+        let refresh_dropdown = refresh_dropdown.clone();
+        let scale_spin = scale_spin.clone();
+        let transform_dropdown = transform_dropdown.clone();
+        let mirror_dropdown = mirror_dropdown.clone();
+        let position_label = position_label.clone();
+        let apply_btn = apply_btn.clone();
+        let canvas = canvas.clone();
+        Rc::new(move || {
+            updating.set(true);
+            let state = state.borrow();
+
+            let names: Vec<String> = state.edited.iter().map(|monitor| monitor.name.clone()).collect();
+            set_strings(&output_dropdown, &names);
+            output_dropdown.set_selected(state.selected as u32);
+
+            let controls = [
+                enabled_switch.upcast_ref::<gtk::Widget>(),
+                resolution_dropdown.upcast_ref(),
+                refresh_dropdown.upcast_ref(),
+                scale_spin.upcast_ref(),
+                transform_dropdown.upcast_ref(),
+                mirror_dropdown.upcast_ref(),
+            ];
+
+            match state.edited.get(state.selected) {
+                Some(monitor) => {
+                    for control in controls {
+                        control.set_sensitive(true);
+                    }
+                    output_dropdown.set_tooltip_text(Some(&monitor.description));
+                    enabled_switch.set_active(monitor.enabled);
+
+                    let resolutions = resolutions(monitor);
+                    set_strings(&resolution_dropdown, &resolutions);
+                    let current = monitor.mode.resolution();
+                    resolution_dropdown.set_selected(resolutions.iter().position(|r| *r == current).unwrap_or(0) as u32);
+
+                    let rates = rates(monitor);
+                    let labels: Vec<String> = rates.iter().map(|rate| format!("{:.2} Hz", rate)).collect();
+                    set_strings(&refresh_dropdown, &labels);
+                    refresh_dropdown.set_selected(
+                        rates
+                            .iter()
+                            .position(|rate| (rate - monitor.mode.refresh).abs() < 0.01)
+                            .unwrap_or(0) as u32,
+                    );
+
+                    scale_spin.set_value(monitor.scale);
+                    transform_dropdown.set_selected(monitor.transform.clamp(0, 7) as u32);
+
+                    let mut mirrors = vec!["None".to_string()];
+                    mirrors.extend(names.iter().filter(|name| **name != monitor.name).cloned());
+                    set_strings(&mirror_dropdown, &mirrors);
+                    mirror_dropdown.set_selected(
+                        monitor
+                            .mirror_of
+                            .as_ref()
+                            .and_then(|mirror| mirrors.iter().position(|name| name == mirror))
+                            .unwrap_or(0) as u32,
+                    );
+
+                    position_label.set_text(&format!("{}, {}", monitor.x, monitor.y));
+                }
+                None => {
+                    for control in controls {
+                        control.set_sensitive(false);
+                    }
+                    position_label.set_text("No displays found (is Hyprland running?)");
+                }
+            }
+
+            apply_btn.set_sensitive(!changed_rules(&state.current, &state.edited).is_empty());
+            updating.set(false);
+            canvas.queue_draw();
+        })
+    };
+
+    // Change the selected output and refresh the controls
+    let edit = {
+        let state = state.clone();
+        let updating = updating.clone();
+        let load_controls = load_controls.clone();
+        move |change: &dyn Fn(&mut Monitor)| {
+            if updating.get() {
+                return;
+            }
+            {
+                let mut state = state.borrow_mut();
+                let selected = state.selected;
+                if let Some(monitor) = state.edited.get_mut(selected) {
+                    change(monitor);
+                }
+                snap(&mut state.edited, selected);
+            }
+            load_controls();
+        }
+    };
+
+    {
+        let state = state.clone();
+        let updating = updating.clone();
+        let load_controls = load_controls.clone();
+        output_dropdown.connect_selected_notify(move |dropdown| {
+            if updating.get() {
+                return;
+            }
+            state.borrow_mut().selected = dropdown.selected() as usize;
+            load_controls();
+        });
+    }
+
+    {
+        let edit = edit.clone();
+        enabled_switch.connect_active_notify(move |switch| {
+            let enabled = switch.is_active();
+            edit(&|monitor| monitor.enabled = enabled);
+        });
+    }
+
+    {
+        let edit = edit.clone();
+        resolution_dropdown.connect_selected_notify(move |dropdown| {
+            let Some(resolution) = dropdown
+                .selected_item()
+                .and_downcast::<gtk::StringObject>()
+                .map(|item| item.string().to_string())
+            else {
+                return;
+            };
+            edit(&|monitor| {
+                // Keep the refresh rate if the new resolution offers it, else take the highest
+                let best = monitor
+                    .modes
+                    .iter()
+                    .filter(|mode| mode.resolution() == resolution)
+                    .min_by(|a, b| {
+                        (a.refresh - monitor.mode.refresh)
+                            .abs()
+                            .total_cmp(&(b.refresh - monitor.mode.refresh).abs())
+                    })
+                    .copied();
+                if let Some(mode) = best {
+                    monitor.mode = mode;
+                }
+            });
+        });
+    }
+
+    {
+        let edit = edit.clone();
+        refresh_dropdown.connect_selected_notify(move |dropdown| {
+            let Some(refresh) = dropdown
+                .selected_item()
+                .and_downcast::<gtk::StringObject>()
+                .and_then(|item| item.string().trim_end_matches(" Hz").parse::<f64>().ok())
+            else {
+                return;
+            };
+            edit(&|monitor| monitor.mode.refresh = refresh);
+        });
+    }
+
+    {
+        let edit = edit.clone();
+        scale_spin.connect_value_changed(move |spin| {
+            let scale = spin.value();
+            edit(&|monitor| monitor.scale = scale);
+        });
+    }
+
+    {
+        let edit = edit.clone();
+        transform_dropdown.connect_selected_notify(move |dropdown| {
+            let transform = dropdown.selected() as i64;
+            edit(&|monitor| monitor.transform = transform);
+        });
+    }
+
+    {
+        let edit = edit.clone();
+        mirror_dropdown.connect_selected_notify(move |dropdown| {
+            let mirror = match dropdown.selected() {
+                0 => None,
+                _ => dropdown
+                    .selected_item()
+                    .and_downcast::<gtk::StringObject>()
+                    .map(|item| item.string().to_string()),
+            };
+            edit(&|monitor| monitor.mirror_of = mirror.clone());
+        });
+    }
+
+    // Click to select, drag to move
+    let drag = gtk::GestureDrag::new();
+    {
+        let state = state.clone();
+        let canvas = canvas.clone();
+        let load_controls = load_controls.clone();
+        drag.connect_drag_begin(move |_, x, y| {
+            {
+                let mut state = state.borrow_mut();
+                let layout = Layout::fit(&state.edited, canvas.width() as f64, canvas.height() as f64);
+                let hit = state.edited.iter().position(|monitor| {
+                    let (left, top, width, height) = layout.rectangle(monitor);
+                    monitor.is_placed() && x >= left && x <= left + width && y >= top && y <= top + height
+                });
+                let Some(index) = hit else {
+                    return;
+                };
+                state.selected = index;
+                state.drag = Some((layout, state.edited[index].x, state.edited[index].y));
+            }
+            load_controls();
+        });
+    }
+    {
+        let state = state.clone();
+        let canvas = canvas.clone();
+        drag.connect_drag_update(move |_, dx, dy| {
+            let mut state = state.borrow_mut();
+            let Some((layout, start_x, start_y)) = state.drag else {
+                return;
+            };
+            let selected = state.selected;
+            let monitor = &mut state.edited[selected];
+            monitor.x = start_x + (dx / layout.zoom).round() as i64;
+            monitor.y = start_y + (dy / layout.zoom).round() as i64;
+            drop(state);
+            canvas.queue_draw();
+        });
+    }
+    {
+        let state = state.clone();
+        let load_controls = load_controls.clone();
+        drag.connect_drag_end(move |_, _, _| {
+            {
+                let mut state = state.borrow_mut();
+                if state.drag.take().is_none() {
+                    return;
+                }
+                let selected = state.selected;
+                snap(&mut state.edited, selected);
+            }
+            load_controls();
+        });
+    }
+    canvas.add_controller(drag);
+
+    let reload: Rc<dyn Fn()> = {
+        let state = state.clone();
+        let load_controls = load_controls.clone();
+        Rc::new(move || {
+            {
+                let mut state = state.borrow_mut();
+                let monitors = read_monitors();
+                state.selected = state.selected.min(monitors.len().saturating_sub(1));
+                state.current = monitors.clone();
+                state.edited = monitors;
+            }
+            load_controls();
+        })
+    };
+
+    {
+        let reload = reload.clone();
+        let status_label = status_label.clone();
+        reset_btn.connect_clicked(move |_| {
+            status_label.set_text("");
+            reload();
+        });
+    }
+
+    {
+        let state = state.clone();
+        let reload = reload.clone();
+        let status_label = status_label.clone();
+        apply_btn.connect_clicked(move |button| {
+            let changes = {
+                let state = state.borrow();
+                changed_rules(&state.current, &state.edited)
+            };
+            if changes.is_empty() {
+                return;
+            }
+
+            // Never switch off every output
+            if !state.borrow().edited.iter().any(|monitor| monitor.enabled) {
+                status_label.set_text("At least one display must stay enabled");
+                return;
+            }
+
+            let parent = button
+                .root()
+                .and_then(|root| root.downcast::<gtk::Window>().ok());
+            let rules: Vec<String> = changes.iter().map(|change| change.value.clone()).collect();
+            let reload = reload.clone();
+            let status_label = status_label.clone();
+            revert::apply_with_confirmation(parent.as_ref(), changes, move |kept| {
+                if kept {
+                    status_label.set_text("Display settings saved");
+                    for rule in &rules {
+                        hooks::run(
+                            HookEvent::SettingsApplied,
+                            &[("UMMIT_SETTING", "monitor".to_string()), ("UMMIT_VALUE", rule.clone())],
+                        );
+                    }
+                } else {
+                    status_label.set_text("Display settings reverted");
+                }
+
+                // Give Hyprland a moment to reconfigure the outputs
+                let reload = reload.clone();
+                glib::timeout_add_local_once(std::time::Duration::from_millis(500), move || reload());
+            });
+        });
+    }
+
+    load_controls();
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json;
+
+    // Layout: a 1.5x laptop panel, a portrait monitor to its right, a
+    // projector mirroring the panel and a disabled TV
+    fn sample_monitors() -> Vec<Monitor> {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/displays/monitors-all.json");
+        let text = std::fs::read_to_string(path).unwrap();
+        parse_monitors(&json::parse(&text).unwrap())
+    }
+
+    fn by_name<'a>(monitors: &'a [Monitor], name: &str) -> &'a Monitor {
+        monitors.iter().find(|monitor| monitor.name == name).unwrap()
+    }
+
+    #[test]
+    fn parses_monitors_all() {
+        let monitors = sample_monitors();
+        let names: Vec<&str> = monitors.iter().map(|monitor| monitor.name.as_str()).collect();
+        assert_eq!(names, ["eDP-1", "DP-1", "DP-2", "HDMI-A-1"]);
+
+        let panel = by_name(&monitors, "eDP-1");
+        assert_eq!(panel.mode, Mode { width: 2880, height: 1800, refresh: 120.0 });
+        assert_eq!(panel.scale, 1.5);
+        assert_eq!(panel.mirror_of, None);
+        assert_eq!(panel.modes.len(), 3);
+        assert_eq!(panel.logical_size(), (1920.0, 1200.0));
+
+        let portrait = by_name(&monitors, "DP-1");
+        assert_eq!((portrait.x, portrait.y, portrait.transform), (1920, 0, 1));
+        assert_eq!(portrait.modes[0], Mode { width: 2560, height: 1440, refresh: 143.97 });
+        assert_eq!(portrait.logical_size(), (1440.0, 2560.0));
+        assert!(portrait.is_placed());
+
+        let projector = by_name(&monitors, "DP-2");
+        assert!(projector.enabled);
+        assert_eq!(projector.mirror_of.as_deref(), Some("eDP-1"));
+        assert!(!projector.is_placed());
+
+        let tv = by_name(&monitors, "HDMI-A-1");
+        assert!(!tv.enabled);
+        assert!(!tv.is_placed());
+        assert_eq!(tv.description, "LG Electronics LG TV SSCR2 0x01010101");
+    }
+
+    #[test]
+    fn skips_entries_without_name_or_size() {
+        let value = json::parse(r#"[{"name": "DP-3"}, {"width": 1920, "height": 1080}, 7]"#).unwrap();
+        assert!(parse_monitors(&value).is_empty());
+        assert!(parse_monitors(&json::parse("{}").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn builds_monitor_rules() {
+        let monitors = sample_monitors();
+        assert_eq!(by_name(&monitors, "eDP-1").rule(), "eDP-1,2880x1800@120.00,0x0,1.5");
+        assert_eq!(by_name(&monitors, "DP-1").rule(), "DP-1,2560x1440@143.97,1920x0,1,transform,1");
+        assert_eq!(by_name(&monitors, "DP-2").rule(), "DP-2,1920x1080@60.00,0x0,1,mirror,eDP-1");
+        assert_eq!(by_name(&monitors, "HDMI-A-1").rule(), "HDMI-A-1,disable");
+
+        let mut monitor = by_name(&monitors, "eDP-1").clone();
+        monitor.scale = 4.0 / 3.0;
+        assert_eq!(monitor.rule(), "eDP-1,2880x1800@120.00,0x0,1.33");
+    }
+
+    #[test]
+    fn builds_rules_for_each_transform() {
+        let mut monitor = sample_monitors().remove(1);
+        assert_eq!(TRANSFORMS.len(), 8);
+        for transform in 0..TRANSFORMS.len() as i64 {
+            monitor.transform = transform;
+            let expected = match transform {
+                0 => "DP-1,2560x1440@143.97,1920x0,1".to_string(),
+                _ => format!("DP-1,2560x1440@143.97,1920x0,1,transform,{}", transform),
+            };
+            assert_eq!(monitor.rule(), expected);
+
+            // Odd transforms rotate by 90° and swap the sides
+            let size = if transform % 2 == 1 { (1440.0, 2560.0) } else { (2560.0, 1440.0) };
+            assert_eq!(monitor.logical_size(), size, "transform {}", transform);
+        }
+    }
+
+    #[test]
+    fn reports_only_changed_rules() {
+        let current = sample_monitors();
+        assert!(changed_rules(&current, &current).is_empty());
+
+        let mut edited = current.clone();
+        edited[3].enabled = true;
+        edited[1].scale = 1.25;
+        let changes = changed_rules(&current, &edited);
+        assert_eq!(
+            changes,
+            [
+                revert::Change {
+                    option: "monitor".to_string(),
+                    value: "DP-1,2560x1440@143.97,1920x0,1.25,transform,1".to_string(),
+                    previous: "DP-1,2560x1440@143.97,1920x0,1,transform,1".to_string(),
+                },
+                revert::Change {
+                    option: "monitor".to_string(),
+                    value: "HDMI-A-1,3840x2160@60.00,0x0,1".to_string(),
+                    previous: "HDMI-A-1,disable".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn snaps_to_nearby_edges() {
+        let mut monitors = sample_monitors();
+
+        // Dropped a little right of and above the panel's top-right corner
+        monitors[1].x = 1950;
+        monitors[1].y = -40;
+        snap(&mut monitors, 1);
+        assert_eq!((monitors[1].x, monitors[1].y), (1920, 0));
+        assert_eq!((monitors[0].x, monitors[0].y), (0, 0));
+
+        // Bottom edges line up when close
+        monitors[1].x = -1400;
+        monitors[1].y = -1330;
+        snap(&mut monitors, 1);
+        assert_eq!((monitors[0].x, monitors[0].y), (1440, 1360));
+        assert_eq!((monitors[1].x, monitors[1].y), (0, 0));
+    }
+
+    #[test]
+    fn ignores_mirrored_and_disabled_outputs_when_snapping() {
+        let mut monitors = sample_monitors();
+
+        // The disabled TV's corner is within snapping distance, but it is
+        // not on the canvas and neither is the projector
+        monitors[1].x = 3800;
+        monitors[1].y = 2150;
+        snap(&mut monitors, 1);
+        assert_eq!((monitors[1].x, monitors[1].y), (3800, 2150));
+    }
+
+    #[test]
+    fn normalises_the_layout_to_the_origin() {
+        let mut monitors = sample_monitors();
+
+        // Far to the left and below: no snapping, but the layout shifts so
+        // the leftmost and topmost placed outputs start at 0
+        monitors[1].x = -3000;
+        monitors[1].y = 500;
+        snap(&mut monitors, 1);
+        assert_eq!((monitors[1].x, monitors[1].y), (0, 500));
+        assert_eq!((monitors[0].x, monitors[0].y), (3000, 0));
+
+        // Unplaced outputs move along, keeping the mirror on its source
+        assert_eq!((monitors[2].x, monitors[2].y), (3000, 0));
+    }
+}
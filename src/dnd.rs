@@ -0,0 +1,226 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::config;
+use crate::recording;
+
+// Automatic do-not-disturb while recording. The previous daemon state is
+// written to disk before switching, so it can be restored after a crash.
+
+pub trait DndBackend {
+    fn name(&self) -> &'static str;
+
+    // Whether this notification daemon is running
+    fn is_available(&self) -> bool;
+
+    // Current state, in a form `restore` understands
+    fn save_state(&self) -> Result<String, String>;
+
+    fn enable(&self) -> Result<(), String>;
+
+    fn restore(&self, saved: &str) -> Result<(), String>;
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(format!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+fn is_running(process: &str) -> bool {
+    run("pidof", &[process])
+        .map(|pids| !pids.is_empty())
+        .unwrap_or(false)
+}
+
+// mako: needs a [mode=do-not-disturb] section (e.g. invisible=1) in its config
+struct Mako;
+
+impl DndBackend for Mako {
+    fn name(&self) -> &'static str {
+        "mako"
+    }
+
+    fn is_available(&self) -> bool {
+        is_running("mako")
+    }
+
+    fn save_state(&self) -> Result<String, String> {
+        // One active mode per line
+        run("makoctl", &["mode"]).map(|modes| modes.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+
+    fn enable(&self) -> Result<(), String> {
+        run("makoctl", &["mode", "-a", "do-not-disturb"]).map(|_| ())
+    }
+
+    fn restore(&self, saved: &str) -> Result<(), String> {
+        let mut args = vec!["mode", "-s"];
+        args.extend(saved.split_whitespace());
+        if args.len() == 2 {
+            args.push("default");
+        }
+        run("makoctl", &args).map(|_| ())
+    }
+}
+
+struct Dunst;
+
+impl DndBackend for Dunst {
+    fn name(&self) -> &'static str {
+        "dunst"
+    }
+
+    fn is_available(&self) -> bool {
+        is_running("dunst")
+    }
+
+    fn save_state(&self) -> Result<String, String> {
+        run("dunstctl", &["is-paused"])
+    }
+
+    fn enable(&self) -> Result<(), String> {
+        run("dunstctl", &["set-paused", "true"]).map(|_| ())
+    }
+
+    fn restore(&self, saved: &str) -> Result<(), String> {
+        let paused = if saved.trim() == "true" { "true" } else { "false" };
+        run("dunstctl", &["set-paused", paused]).map(|_| ())
+    }
+}
+
+struct SwayNc;
+
+impl DndBackend for SwayNc {
+    fn name(&self) -> &'static str {
+        "swaync"
+    }
+
+    fn is_available(&self) -> bool {
+        is_running("swaync")
+    }
+
+    fn save_state(&self) -> Result<String, String> {
+        run("swaync-client", &["--get-dnd", "--skip-wait"])
+    }
+
+    fn enable(&self) -> Result<(), String> {
+        run("swaync-client", &["--dnd-on", "--skip-wait"]).map(|_| ())
+    }
+
+    fn restore(&self, saved: &str) -> Result<(), String> {
+        let flag = if saved.trim() == "true" { "--dnd-on" } else { "--dnd-off" };
+        run("swaync-client", &[flag, "--skip-wait"]).map(|_| ())
+    }
+}
+
+fn backends() -> Vec<Box<dyn DndBackend>> {
+    vec![Box::new(Mako), Box::new(Dunst), Box::new(SwayNc)]
+}
+
+// The running notification daemon, if we know how to talk to it
+pub fn detect_backend() -> Option<Box<dyn DndBackend>> {
+    backends().into_iter().find(|backend| backend.is_available())
+}
+
+fn backend_by_name(name: &str) -> Option<Box<dyn DndBackend>> {
+    backends().into_iter().find(|backend| backend.name() == name)
+}
+
+pub fn is_enabled() -> bool {
+    config::get_bool("Recording", "auto-dnd", false)
+}
+
+pub fn set_enabled(enabled: bool) {
+    config::set_bool("Recording", "auto-dnd", enabled);
+}
+
+fn saved_state_file() -> PathBuf {
+    config::data_dir().join("saved-dnd-state")
+}
+
+pub fn install() {
+    // Restore a state left behind by a crash
+    if !recording::is_recording() {
+        restore();
+    }
+
+    recording::subscribe(|session| {
+        if session.is_some() {
+            if is_enabled() {
+                enable();
+            }
+        } else {
+            restore();
+        }
+    })
+    .keep();
+}
+
+fn enable() {
+    let Some(backend) = detect_backend() else {
+        println!("Do not disturb: no supported notification daemon running");
+        return;
+    };
+
+    let path = saved_state_file();
+    if !path.exists() {
+        let state = match backend.save_state() {
+            Ok(state) => state,
+            Err(e) => {
+                eprintln!("Do not disturb: {}", e);
+                return;
+            }
+        };
+
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        if let Err(e) = fs::write(&path, format!("{}\n{}\n", backend.name(), state)) {
+            eprintln!("Failed to save notification daemon state: {}", e);
+            return;
+        }
+    }
+
+    match backend.enable() {
+        Ok(()) => println!("Do not disturb enabled via {}", backend.name()),
+        Err(e) => eprintln!("Do not disturb: {}", e),
+    }
+}
+
+fn restore() {
+    let path = saved_state_file();
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return;
+    };
+
+    let mut lines = contents.lines();
+    let name = lines.next().unwrap_or_default();
+    let state = lines.next().unwrap_or_default();
+
+    let Some(backend) = backend_by_name(name) else {
+        let _ = fs::remove_file(&path);
+        return;
+    };
+
+    match backend.restore(state) {
+        Ok(()) => {
+            println!("Restored {} notification state", backend.name());
+            let _ = fs::remove_file(&path);
+        }
+        Err(e) => eprintln!("Failed to restore notification state: {}", e),
+    }
+}
@@ -0,0 +1,292 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+use crate::json;
+use crate::libraries;
+use crate::recorder;
+use crate::recording::RecordingSession;
+
+// Append-only history of recording sessions, stored as JSON lines in the
+// data directory. A session is written when it starts and again when it
+// ends, so a start without an end means the app or recorder crashed.
+// Files moved between libraries are found again by inode and logged as a
+// "moved" event.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Recording,
+    Completed,
+    Failed,
+    Crashed,
+}
+
+impl Outcome {
+    pub fn label(self) -> &'static str {
+        match self {
+            Outcome::Recording => "Recording",
+            Outcome::Completed => "Completed",
+            Outcome::Failed => "Failed",
+            Outcome::Crashed => "Crashed",
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Outcome::Recording => "recording",
+            Outcome::Completed => "completed",
+            Outcome::Failed => "failed",
+            Outcome::Crashed => "crashed",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Outcome> {
+        [Outcome::Recording, Outcome::Completed, Outcome::Failed, Outcome::Crashed]
+            .into_iter()
+            .find(|outcome| outcome.key() == key)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    pub started: i64,
+    pub ended: Option<i64>,
+    pub preset: String,
+    pub audio_source: String,
+    pub region: String,
+    pub output: String,
+    pub final_path: Option<String>,
+    // Size when the session ended
+    pub size: Option<u64>,
+    pub outcome: Outcome,
+    // Deleted from within the app
+    pub removed: bool,
+    id: String,
+    pid: i64,
+    process: String,
+    // Inode of the final file, used to find it after a move
+    inode: Option<u64>,
+}
+
+impl HistoryEntry {
+    pub fn path(&self) -> &str {
+        self.final_path.as_deref().unwrap_or(&self.output)
+    }
+}
+
+pub fn history_file() -> PathBuf {
+    config::data_dir().join("history.jsonl")
+}
+
+fn session_id(session: &RecordingSession) -> String {
+    format!("{}-{}", session.started_unix(), session.pid)
+}
+
+enum Field {
+    Str(String),
+    Int(i64),
+}
+
+fn append(fields: &[(&str, Field)]) {
+    let line = fields
+        .iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Field::Str(value) => json::escape_string(value),
+                Field::Int(value) => value.to_string(),
+            };
+            format!("{}: {}", json::escape_string(key), value)
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let path = history_file();
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+
+    let written = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(format!("{{{}}}\n", line).as_bytes()));
+
+    if let Err(e) = written {
+        eprintln!("Failed to write recording history {}: {}", path.display(), e);
+    }
+}
+
+// Name of a running process, used to tell a live recorder from a reused pid
+fn process_name(pid: i64) -> Option<String> {
+    fs::read_to_string(format!("/proc/{}/comm", pid))
+        .ok()
+        .map(|name| name.trim().to_string())
+}
+
+pub fn record_started(session: &RecordingSession, preset: &str, audio_source: &str, region: &str) {
+    append(&[
+        ("event", Field::Str("started".to_string())),
+        ("id", Field::Str(session_id(session))),
+        ("pid", Field::Int(session.pid as i64)),
+        ("process", Field::Str(process_name(session.pid as i64).unwrap_or_default())),
+        ("time", Field::Int(session.started_unix())),
+        ("preset", Field::Str(preset.to_string())),
+        ("audio", Field::Str(audio_source.to_string())),
+        ("region", Field::Str(region.to_string())),
+        ("output", Field::Str(session.file_path.clone())),
+    ]);
+}
+
+// Blocking (reads the final file size); call it once the recorder has exited
+pub fn record_finished(session: &RecordingSession, ended: i64, outcome: Outcome, final_path: &Path) {
+    let metadata = fs::metadata(final_path).ok();
+    let size = metadata.as_ref().map(|metadata| metadata.len() as i64).unwrap_or(-1);
+    let inode = metadata.as_ref().map(|metadata| metadata.ino() as i64).unwrap_or(-1);
+
+    append(&[
+        ("event", Field::Str("finished".to_string())),
+        ("id", Field::Str(session_id(session))),
+        ("time", Field::Int(ended)),
+        ("outcome", Field::Str(outcome.key().to_string())),
+        ("path", Field::Str(final_path.to_string_lossy().to_string())),
+        ("size", Field::Int(size)),
+        ("inode", Field::Int(inode)),
+    ]);
+}
+
+// A recording now lives at a different path
+fn record_moved(from: &str, to: &Path) {
+    append(&[
+        ("event", Field::Str("moved".to_string())),
+        ("time", Field::Int(chrono::Local::now().timestamp())),
+        ("from", Field::Str(from.to_string())),
+        ("path", Field::Str(to.to_string_lossy().to_string())),
+    ]);
+}
+
+// A recording was deleted or trashed from within the app
+pub fn record_removed(path: &Path) {
+    append(&[
+        ("event", Field::Str("removed".to_string())),
+        ("time", Field::Int(chrono::Local::now().timestamp())),
+        ("path", Field::Str(path.to_string_lossy().to_string())),
+    ]);
+}
+
+// All sessions, newest first
+pub fn load() -> Vec<HistoryEntry> {
+    let Ok(contents) = fs::read_to_string(history_file()) else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<HistoryEntry> = Vec::new();
+
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+        let record = match json::parse(line) {
+            Ok(record) => record,
+            Err(e) => {
+                eprintln!("Skipping invalid history line: {}", e);
+                continue;
+            }
+        };
+        let str_field = |key: &str| record.str_field(key).unwrap_or_default().to_string();
+
+        match record.str_field("event") {
+            Some("started") => entries.push(HistoryEntry {
+                id: str_field("id"),
+                pid: record.i64_field("pid").unwrap_or(0),
+                process: str_field("process"),
+                started: record.i64_field("time").unwrap_or(0),
+                ended: None,
+                preset: str_field("preset"),
+                audio_source: str_field("audio"),
+                region: str_field("region"),
+                output: str_field("output"),
+                final_path: None,
+                size: None,
+                outcome: Outcome::Crashed,
+                removed: false,
+                inode: None,
+            }),
+            Some("finished") => {
+                let id = str_field("id");
+                if let Some(entry) = entries.iter_mut().rev().find(|entry| entry.id == id) {
+                    entry.ended = record.i64_field("time");
+                    entry.outcome = record
+                        .str_field("outcome")
+                        .and_then(Outcome::from_key)
+                        .unwrap_or(Outcome::Completed);
+                    entry.final_path = record.str_field("path").map(str::to_string);
+                    entry.size = record.i64_field("size").filter(|size| *size >= 0).map(|size| size as u64);
+                    entry.inode = record.i64_field("inode").filter(|inode| *inode >= 0).map(|inode| inode as u64);
+                }
+            }
+            Some("moved") => {
+                let from = str_field("from");
+                for entry in entries.iter_mut().filter(|entry| entry.path() == from) {
+                    entry.final_path = Some(str_field("path"));
+                }
+            }
+            Some("removed") => {
+                let path = str_field("path");
+                for entry in entries.iter_mut().filter(|entry| entry.path() == path) {
+                    entry.removed = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Sessions that never finished are either still running or crashed
+    for entry in entries.iter_mut().filter(|entry| entry.ended.is_none()) {
+        if entry.pid > 0 && process_name(entry.pid).is_some_and(|name| name == entry.process) {
+            entry.outcome = Outcome::Recording;
+        }
+    }
+
+    entries.reverse();
+    entries
+}
+
+// Look for recordings that are missing from their logged path in the
+// library directories. A match on inode (or, for sessions logged before
+// inodes were, on file name and size) is recorded as a move.
+pub fn locate_moved(entries: &mut [HistoryEntry]) {
+    let mut candidates: Option<Vec<(PathBuf, fs::Metadata)>> = None;
+
+    for entry in entries.iter_mut() {
+        if entry.removed || entry.ended.is_none() || Path::new(entry.path()).exists() {
+            continue;
+        }
+
+        // Only list the libraries when something is actually missing
+        let candidates = candidates.get_or_insert_with(library_files);
+        let name = Path::new(entry.path()).file_name().map(|name| name.to_os_string());
+
+        let found = candidates.iter().find(|(path, metadata)| match entry.inode {
+            Some(inode) => metadata.ino() == inode && Some(metadata.len()) == entry.size,
+            None => path.file_name().map(|name| name.to_os_string()) == name && Some(metadata.len()) == entry.size,
+        });
+
+        if let Some((path, _)) = found {
+            record_moved(entry.path(), path);
+            entry.final_path = Some(path.to_string_lossy().to_string());
+        }
+    }
+}
+
+fn library_files() -> Vec<(PathBuf, fs::Metadata)> {
+    libraries::all()
+        .iter()
+        .filter_map(|library| fs::read_dir(&library.path).ok())
+        .flat_map(|entries| entries.filter_map(|entry| entry.ok()))
+        .map(|entry| entry.path())
+        .filter(|path| recorder::is_video_file(path))
+        .filter_map(|path| {
+            let metadata = fs::metadata(&path).ok()?;
+            Some((path, metadata))
+        })
+        .collect()
+}
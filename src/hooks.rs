@@ -0,0 +1,222 @@
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Write};
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::config;
+
+// User hook commands run on recording and settings events. Event data is
+// passed through UMMIT_* environment variables; output goes to hooks.log.
+
+const CONFIG_GROUP: &str = "Hooks";
+const DEFAULT_TIMEOUT_SECS: i32 = 30;
+// How long a timed-out hook gets to exit after SIGTERM before SIGKILL
+const KILL_GRACE: Duration = Duration::from_secs(2);
+// How long to wait for output once the hook has exited; background
+// processes it left behind may hold the pipes open indefinitely
+const OUTPUT_GRACE: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    RecordingStarted,
+    RecordingStopped,
+    RecordingFailed,
+    FileSaved,
+    SettingsApplied,
+}
+
+impl HookEvent {
+    pub const ALL: [HookEvent; 5] = [
+        HookEvent::RecordingStarted,
+        HookEvent::RecordingStopped,
+        HookEvent::RecordingFailed,
+        HookEvent::FileSaved,
+        HookEvent::SettingsApplied,
+    ];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            HookEvent::RecordingStarted => "recording-started",
+            HookEvent::RecordingStopped => "recording-stopped",
+            HookEvent::RecordingFailed => "recording-failed",
+            HookEvent::FileSaved => "file-saved",
+            HookEvent::SettingsApplied => "settings-applied",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HookEvent::RecordingStarted => "Recording started",
+            HookEvent::RecordingStopped => "Recording stopped",
+            HookEvent::RecordingFailed => "Recording failed",
+            HookEvent::FileSaved => "File saved",
+            HookEvent::SettingsApplied => "Settings applied",
+        }
+    }
+}
+
+pub fn command(event: HookEvent) -> String {
+    config::get_string(CONFIG_GROUP, event.key(), "")
+}
+
+pub fn set_command(event: HookEvent, command: &str) {
+    config::set_string(CONFIG_GROUP, event.key(), command);
+}
+
+pub fn timeout_secs() -> i32 {
+    config::get_int(CONFIG_GROUP, "timeout-secs", DEFAULT_TIMEOUT_SECS)
+}
+
+pub fn set_timeout_secs(seconds: i32) {
+    config::set_int(CONFIG_GROUP, "timeout-secs", seconds);
+}
+
+pub fn log_file() -> PathBuf {
+    config::data_dir().join("hooks.log")
+}
+
+// Run the hook for an event (if configured) in the background
+pub fn run(event: HookEvent, env: &[(&str, String)]) {
+    let command = command(event);
+    if command.trim().is_empty() {
+        return;
+    }
+
+    let timeout = Duration::from_secs(timeout_secs().max(1) as u64);
+    let mut env: Vec<(String, String)> = env
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.clone()))
+        .collect();
+    env.push(("UMMIT_EVENT".to_string(), event.key().to_string()));
+
+    thread::spawn(move || execute(event, &command, &env, timeout));
+}
+
+fn execute(event: HookEvent, command: &str, env: &[(String, String)], timeout: Duration) {
+    println!("Running {} hook: {}", event.key(), command);
+
+    let child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .envs(env.iter().map(|(key, value)| (key.as_str(), value.as_str())))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // Own process group, so a timeout can stop everything the hook started
+        .process_group(0)
+        .spawn();
+
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            write_log(event, command, &format!("failed to start: {}", e), "");
+            return;
+        }
+    };
+
+    // Drain output on separate threads so a chatty hook cannot block on a full pipe
+    let stdout_reader = child.stdout.take().map(spawn_reader);
+    let stderr_reader = child.stderr.take().map(spawn_reader);
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break format!("exited with {}", status),
+            Ok(None) if started.elapsed() >= timeout => {
+                kill_group(&mut child);
+                break format!("killed after {}s timeout", timeout.as_secs());
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(100)),
+            Err(e) => break format!("wait failed: {}", e),
+        }
+    };
+
+    // Readers still blocked after the grace period are left to finish on their
+    // own; whatever they have read so far is logged
+    let readers: Vec<OutputReader> = [stdout_reader, stderr_reader].into_iter().flatten().collect();
+    let waited = Instant::now();
+    while waited.elapsed() < OUTPUT_GRACE && !readers.iter().all(|(handle, _)| handle.is_finished()) {
+        thread::sleep(Duration::from_millis(20));
+    }
+
+    let mut output = String::new();
+    for (_, buffer) in &readers {
+        output.push_str(&String::from_utf8_lossy(&buffer.lock().unwrap()));
+    }
+
+    write_log(event, command, &status, &output);
+}
+
+type OutputReader = (thread::JoinHandle<()>, Arc<Mutex<Vec<u8>>>);
+
+// Collect a pipe into a shared buffer as it arrives
+fn spawn_reader<R: Read + Send + 'static>(mut pipe: R) -> OutputReader {
+    let buffer = Arc::new(Mutex::new(Vec::new()));
+    let shared = buffer.clone();
+    let handle = thread::spawn(move || {
+        let mut chunk = [0u8; 4096];
+        while let Ok(read) = pipe.read(&mut chunk) {
+            if read == 0 {
+                break;
+            }
+            shared.lock().unwrap().extend_from_slice(&chunk[..read]);
+        }
+    });
+    (handle, buffer)
+}
+
+// Stop the hook's whole process group: SIGTERM first, SIGKILL if it lingers
+fn kill_group(child: &mut std::process::Child) {
+    let group = format!("-{}", child.id());
+    let _ = Command::new("kill").arg("-TERM").arg("--").arg(&group).stderr(Stdio::null()).status();
+
+    let started = Instant::now();
+    while started.elapsed() < KILL_GRACE {
+        if let Ok(Some(_)) = child.try_wait() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    // Children of the hook may outlive the shell, so signal the group regardless
+    let _ = Command::new("kill").arg("-KILL").arg("--").arg(&group).stderr(Stdio::null()).status();
+    let _ = child.wait();
+}
+
+fn write_log(event: HookEvent, command: &str, status: &str, output: &str) {
+    println!("{} hook {}", event.key(), status);
+
+    let path = log_file();
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+
+    let entry = format!(
+        "[{}] {} hook `{}` {}\n{}",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+        event.key(),
+        command,
+        status,
+        indent_output(output)
+    );
+
+    if let Err(e) = append(&path, &entry) {
+        eprintln!("Failed to write hook log {}: {}", path.display(), e);
+    }
+}
+
+fn append(path: &Path, entry: &str) -> std::io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(entry.as_bytes())
+}
+
+fn indent_output(output: &str) -> String {
+    output
+        .lines()
+        .map(|line| format!("    {}\n", line))
+        .collect()
+}
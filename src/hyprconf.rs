@@ -0,0 +1,726 @@
+use gtk::glib;
+use std::collections::BTreeMap;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+// Parser and writer for hyprland.conf. Every line keeps its original text,
+// so a document is written back byte for byte except for the values that
+// were edited. `source =` includes are followed, and every value records
+// the file and line it was defined on.
+
+// Guard against include cycles and runaway globs
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LineKind {
+    // Blank lines, comments and anything we do not understand
+    Other,
+    // `decoration {`
+    SectionStart(String),
+    SectionEnd,
+    // `key = value # comment`; the range is the value's bytes in the line
+    Assignment { key: String, value: Range<usize> },
+}
+
+#[derive(Debug, Clone)]
+struct Line {
+    raw: String,
+    kind: LineKind,
+}
+
+// Byte offset of the first unescaped `#` (`##` is a literal `#`)
+fn comment_start(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'#' {
+            if bytes.get(i + 1) == Some(&b'#') {
+                i += 2;
+                continue;
+            }
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+fn parse_line(raw: &str) -> LineKind {
+    let content = raw.trim_end_matches(['\n', '\r']);
+    let code = &content[..comment_start(content).unwrap_or(content.len())];
+    let trimmed = code.trim();
+
+    if trimmed.is_empty() {
+        return LineKind::Other;
+    }
+    if trimmed == "}" {
+        return LineKind::SectionEnd;
+    }
+    if let Some(name) = trimmed.strip_suffix('{') {
+        let name = name.trim();
+        if !name.is_empty() && !name.contains('=') {
+            return LineKind::SectionStart(name.to_string());
+        }
+    }
+
+    let Some(equals) = code.find('=') else {
+        return LineKind::Other;
+    };
+    let key = code[..equals].trim();
+    if key.is_empty() {
+        return LineKind::Other;
+    }
+
+    let after = &code[equals + 1..];
+    let start = equals + 1 + (after.len() - after.trim_start().len());
+    let end = (equals + 1 + after.trim_end().len()).max(start);
+
+    LineKind::Assignment {
+        key: key.to_string(),
+        value: start..end,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Document {
+    pub path: PathBuf,
+    lines: Vec<Line>,
+    modified: bool,
+}
+
+impl Document {
+    pub fn parse(path: &Path, text: &str) -> Self {
+        let lines = text
+            .split_inclusive('\n')
+            .map(|raw| Line {
+                raw: raw.to_string(),
+                kind: parse_line(raw),
+            })
+            .collect();
+
+        Document {
+            path: path.to_path_buf(),
+            lines,
+            modified: false,
+        }
+    }
+
+    // The document as it would be written
+    pub fn text(&self) -> String {
+        self.lines.iter().map(|line| line.raw.as_str()).collect()
+    }
+
+    fn value(&self, index: usize) -> Option<String> {
+        match &self.lines[index].kind {
+            LineKind::Assignment { value, .. } => Some(self.lines[index].raw[value.clone()].replace("##", "#")),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub file: PathBuf,
+    // 1-based, as in editors
+    pub line: usize,
+}
+
+// One `key = value` with its section path, e.g. "decoration:blur:size"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub value: String,
+    pub location: Location,
+    document: usize,
+    index: usize,
+}
+
+impl Entry {
+    pub fn is_variable(&self) -> bool {
+        self.name.starts_with('$')
+    }
+}
+
+// hyprland.conf and everything it sources, with entries in the order
+// Hyprland evaluates them (later definitions win)
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    documents: Vec<Document>,
+    entries: Vec<Entry>,
+    // What `~` in a `source =` path stands for
+    home: PathBuf,
+}
+
+pub fn default_path() -> PathBuf {
+    glib::user_config_dir().join("hypr").join("hyprland.conf")
+}
+
+// `*` and `?` wildcards within one path component
+fn wildcard_match(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            wildcard_match(&pattern[1..], name) || (!name.is_empty() && wildcard_match(pattern, &name[1..]))
+        }
+        (Some(b'?'), Some(_)) => wildcard_match(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => wildcard_match(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        Self::load_with_home(path, glib::home_dir())
+    }
+
+    fn load_with_home(path: &Path, home: PathBuf) -> Result<Self, String> {
+        let mut config = Config {
+            home,
+            ..Config::default()
+        };
+        config.load_file(path, 0)?;
+        Ok(config)
+    }
+
+    pub fn load_default() -> Result<Self, String> {
+        Self::load(&default_path())
+    }
+
+    fn load_file(&mut self, path: &Path, depth: usize) -> Result<(), String> {
+        if depth > MAX_INCLUDE_DEPTH {
+            return Err(format!("Includes nested too deeply at {}", path.display()));
+        }
+        if self.documents.iter().any(|document| document.path == path) {
+            return Err(format!("{} is sourced more than once", path.display()));
+        }
+
+        let text = fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        let document_index = self.documents.len();
+        self.documents.push(Document::parse(path, &text));
+
+        let kinds: Vec<LineKind> = self.documents[document_index]
+            .lines
+            .iter()
+            .map(|line| line.kind.clone())
+            .collect();
+        let mut sections: Vec<String> = Vec::new();
+
+        for (index, kind) in kinds.into_iter().enumerate() {
+            match kind {
+                LineKind::SectionStart(name) => sections.push(name),
+                LineKind::SectionEnd => {
+                    sections.pop();
+                }
+                LineKind::Assignment { key, .. } => {
+                    let value = self.documents[document_index].value(index).unwrap_or_default();
+                    let name = sections
+                        .iter()
+                        .map(String::as_str)
+                        .chain([key.as_str()])
+                        .collect::<Vec<_>>()
+                        .join(":");
+
+                    self.entries.push(Entry {
+                        name: name.clone(),
+                        value: value.clone(),
+                        location: Location {
+                            file: path.to_path_buf(),
+                            line: index + 1,
+                        },
+                        document: document_index,
+                        index,
+                    });
+
+                    // Sourced files are evaluated in place
+                    if name == "source" {
+                        for include in self.resolve_include(&value, path) {
+                            if let Err(e) = self.load_file(&include, depth + 1) {
+                                eprintln!("Skipping include: {}", e);
+                            }
+                        }
+                    }
+                }
+                LineKind::Other => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    // Expand ~, variables and a wildcard file name, relative to the including file
+    fn resolve_include(&self, value: &str, from: &Path) -> Vec<PathBuf> {
+        let value = self.expand_variables(value);
+        let path = match value.strip_prefix("~/") {
+            Some(rest) => self.home.join(rest),
+            None => from.parent().unwrap_or(Path::new("/")).join(value.trim_start_matches("./")),
+        };
+
+        let Some(pattern) = path.file_name().map(|name| name.to_string_lossy().to_string()) else {
+            return Vec::new();
+        };
+        if !pattern.contains(['*', '?']) {
+            return vec![path];
+        }
+
+        let dir = path.parent().unwrap_or(Path::new("/"));
+        let mut matches: Vec<PathBuf> = fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| wildcard_match(pattern.as_bytes(), entry.file_name().to_string_lossy().as_bytes()))
+                    .map(|entry| entry.path())
+                    .collect()
+            })
+            .unwrap_or_default();
+        matches.sort();
+        matches
+    }
+
+    // Is this file part of the config, directly or through includes?
+    pub fn includes(&self, path: &Path) -> bool {
+        let Ok(path) = fs::canonicalize(path) else {
+            return false;
+        };
+        self.documents
+            .iter()
+            .any(|document| fs::canonicalize(&document.path).is_ok_and(|loaded| loaded == path))
+    }
+
+    // Every entry, in evaluation order
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    // Every definition of a repeatable keyword such as `monitor` or `bind`
+    pub fn all(&self, name: &str) -> Vec<&Entry> {
+        self.entries.iter().filter(|entry| entry.name == name).collect()
+    }
+
+    // The definition that takes effect
+    pub fn get(&self, name: &str) -> Option<&Entry> {
+        self.entries.iter().rev().find(|entry| entry.name == name)
+    }
+
+    // `$name` variables, last definition wins
+    pub fn variables(&self) -> BTreeMap<String, String> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.is_variable())
+            .map(|entry| (entry.name[1..].to_string(), entry.value.clone()))
+            .collect()
+    }
+
+    // Substitute `$name` references; unknown names are left as written
+    pub fn expand_variables(&self, value: &str) -> String {
+        let variables = self.variables();
+        let mut expanded = String::with_capacity(value.len());
+        let mut chars = value.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if c != '$' {
+                expanded.push(c);
+                continue;
+            }
+
+            let mut name = String::new();
+            while let Some(&next) = chars.peek() {
+                if next.is_ascii_alphanumeric() || next == '_' {
+                    name.push(next);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+
+            match variables.get(&name) {
+                Some(value) => expanded.push_str(value),
+                None => {
+                    expanded.push('$');
+                    expanded.push_str(&name);
+                }
+            }
+        }
+
+        expanded
+    }
+
+    // Replace the value of one entry, keeping the key, spacing and comment
+    pub fn set_value(&mut self, entry: &Entry, value: &str) {
+        let document = &mut self.documents[entry.document];
+        let line = &mut document.lines[entry.index];
+        let LineKind::Assignment { value: range, .. } = &mut line.kind else {
+            return;
+        };
+
+        let escaped = value.replace('#', "##");
+        line.raw.replace_range(range.clone(), &escaped);
+        *range = range.start..range.start + escaped.len();
+        document.modified = true;
+
+        for existing in self.entries.iter_mut() {
+            if existing.document == entry.document && existing.index == entry.index {
+                existing.value = value.to_string();
+            }
+        }
+    }
+
+    // Change the key of one entry (e.g. `bind` to `binde`), keeping the rest
+    // of the line
+    pub fn set_key(&mut self, entry: &Entry, key: &str) {
+        let document = &mut self.documents[entry.document];
+        let line = &mut document.lines[entry.index];
+        let LineKind::Assignment { key: old_key, value } = &mut line.kind else {
+            return;
+        };
+
+        let start = line.raw.len() - line.raw.trim_start().len();
+        if !line.raw[start..].starts_with(old_key.as_str()) {
+            return;
+        }
+        line.raw.replace_range(start..start + old_key.len(), key);
+        let shift = key.len() as isize - old_key.len() as isize;
+        *value = (value.start as isize + shift) as usize..(value.end as isize + shift) as usize;
+        *old_key = key.to_string();
+        document.modified = true;
+
+        for existing in self.entries.iter_mut() {
+            if existing.document == entry.document && existing.index == entry.index {
+                existing.name = match existing.name.rsplit_once(':') {
+                    Some((sections, _)) => format!("{}:{}", sections, key),
+                    None => key.to_string(),
+                };
+            }
+        }
+    }
+
+    // Delete the line an entry is defined on
+    pub fn remove(&mut self, entry: &Entry) {
+        let document = &mut self.documents[entry.document];
+        if !matches!(document.lines[entry.index].kind, LineKind::Assignment { .. }) {
+            return;
+        }
+        document.lines.remove(entry.index);
+        document.modified = true;
+
+        self.entries
+            .retain(|existing| existing.document != entry.document || existing.index != entry.index);
+        for existing in self.entries.iter_mut() {
+            if existing.document == entry.document && existing.index > entry.index {
+                existing.index -= 1;
+                existing.location.line -= 1;
+            }
+        }
+    }
+
+    // Add `name = value` at the end of the main file, outside any section
+    pub fn append(&mut self, name: &str, value: &str) {
+        let Some(document) = self.documents.first_mut() else {
+            return;
+        };
+
+        if let Some(last) = document.lines.last_mut()
+            && !last.raw.ends_with('\n')
+        {
+            last.raw.push('\n');
+        }
+
+        let raw = format!("{} = {}\n", name, value.replace('#', "##"));
+        let kind = parse_line(&raw);
+        document.lines.push(Line { raw, kind });
+        document.modified = true;
+
+        let index = document.lines.len() - 1;
+        self.entries.push(Entry {
+            name: name.to_string(),
+            value: value.to_string(),
+            location: Location {
+                file: document.path.clone(),
+                line: index + 1,
+            },
+            document: 0,
+            index,
+        });
+    }
+
+    // Write the documents that changed
+    pub fn save(&mut self) -> Result<(), String> {
+        for document in self.documents.iter_mut().filter(|document| document.modified) {
+            write_atomically(&document.path, &document.text())?;
+            document.modified = false;
+        }
+        Ok(())
+    }
+}
+
+// Write next to the target and rename over it, so readers (and Hyprland)
+// never see a half-written file. Symlinks are written through.
+pub fn write_atomically(path: &Path, contents: &str) -> Result<(), String> {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{}.tmp", file_name));
+
+    fs::write(&tmp, contents).map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    // Keep the mode of the file being replaced
+    if let Ok(metadata) = fs::metadata(&path) {
+        let _ = fs::set_permissions(&tmp, metadata.permissions());
+    }
+    fs::rename(&tmp, &path).map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/hyprconf")
+            .join(name)
+    }
+
+    fn load_fixture() -> Config {
+        Config::load_with_home(&fixture("hyprland.conf"), fixture("home")).unwrap()
+    }
+
+    fn main_text(config: &Config) -> String {
+        config.documents[0].text()
+    }
+
+    // Lines that differ between two texts, as (1-based line, after)
+    fn changed_lines(before: &str, after: &str) -> Vec<(usize, String)> {
+        let before: Vec<&str> = before.split_inclusive('\n').collect();
+        let after: Vec<&str> = after.split_inclusive('\n').collect();
+        assert_eq!(before.len(), after.len());
+        before
+            .iter()
+            .zip(&after)
+            .enumerate()
+            .filter(|(_, (before, after))| before != after)
+            .map(|(index, (_, after))| (index + 1, after.to_string()))
+            .collect()
+    }
+
+    fn entry(config: &Config, name: &str) -> Entry {
+        config.get(name).unwrap_or_else(|| panic!("{} not found", name)).clone()
+    }
+
+    #[test]
+    fn round_trips_byte_for_byte() {
+        let config = load_fixture();
+        for document in &config.documents {
+            assert_eq!(document.text(), fs::read_to_string(&document.path).unwrap());
+        }
+
+        let odd = "a = 1\r\n\r\n  # indented comment\n}\nb=2 ## not a comment # comment";
+        assert_eq!(Document::parse(Path::new("odd.conf"), odd).text(), odd);
+    }
+
+    #[test]
+    fn names_nested_sections() {
+        let config = load_fixture();
+        assert_eq!(entry(&config, "general:gaps_in").value, "5");
+        assert_eq!(entry(&config, "decoration:active_opacity").value, "0.95");
+        assert_eq!(entry(&config, "decoration:blur:size").value, "8");
+        assert_eq!(entry(&config, "decoration:shadow:enabled").value, "false");
+        assert_eq!(entry(&config, "animations:enabled").value, "yes");
+        assert_eq!(entry(&config, "input:kb_layout").value, "us,de");
+        assert!(config.get("blur:size").is_none());
+    }
+
+    #[test]
+    fn unescapes_doubled_hashes() {
+        let config = load_fixture();
+        assert_eq!(entry(&config, "$accent").value, "#89b4fa");
+        assert_eq!(
+            entry(&config, "windowrulev2").value,
+            "bordercolor rgb(ff0000), title:^(Alert #1)$"
+        );
+        assert_eq!(config.all("monitor")[1].value, "HDMI-A-1, preferred, auto, 1");
+    }
+
+    #[test]
+    fn set_value_changes_only_its_line() {
+        let mut config = load_fixture();
+        let before = main_text(&config);
+
+        // The main file's definition; cycle-b.conf overrides it later
+        config.set_value(&config.all("decoration:rounding")[0].clone(), "12");
+        assert_eq!(changed_lines(&before, &main_text(&config)), [(15, "    rounding = 12   # corners\n".to_string())]);
+
+        let accent = entry(&config, "$accent");
+        config.set_value(&accent, "#f38ba8");
+        assert_eq!(entry(&config, "$accent").value, "#f38ba8");
+        assert_eq!(
+            changed_lines(&before, &main_text(&config))[1],
+            (30, "$accent = ##f38ba8\n".to_string())
+        );
+
+        // The edited text parses back to the same values
+        let reparsed = Document::parse(Path::new("hyprland.conf"), &main_text(&config));
+        assert_eq!(reparsed.value(14).as_deref(), Some("12"));
+        assert_eq!(reparsed.value(29).as_deref(), Some("#f38ba8"));
+    }
+
+    #[test]
+    fn set_key_changes_only_its_line() {
+        let mut config = load_fixture();
+        let before = main_text(&config);
+        let bind = config.all("bind")[0].clone();
+
+        config.set_key(&bind, "binde");
+        assert_eq!(
+            changed_lines(&before, &main_text(&config)),
+            [(33, "binde = $mainMod, Q, exec, kitty\n".to_string())]
+        );
+        assert_eq!(config.all("binde").len(), 1);
+        assert_eq!(config.all("binde")[0].value, "$mainMod, Q, exec, kitty");
+
+        // The value range moved with the key
+        config.set_value(&config.all("binde")[0].clone(), "$mainMod, W, exec, kitty");
+        assert_eq!(
+            changed_lines(&before, &main_text(&config)),
+            [(33, "binde = $mainMod, W, exec, kitty\n".to_string())]
+        );
+
+        // Keys inside sections keep their section prefix
+        config.set_key(&entry(&config, "decoration:blur:passes"), "new_optimizations");
+        assert_eq!(entry(&config, "decoration:blur:new_optimizations").value, "2");
+    }
+
+    #[test]
+    fn remove_drops_only_its_line() {
+        let mut config = load_fixture();
+        let before = main_text(&config);
+        let exit_bind = config.all("bind")[1].clone();
+        assert_eq!(exit_bind.location.line, 34);
+
+        config.remove(&exit_bind);
+        let mut expected: Vec<&str> = before.split_inclusive('\n').collect();
+        expected.remove(33);
+        assert_eq!(main_text(&config), expected.concat());
+
+        // Later entries in the same file move up, other files do not
+        assert_eq!(entry(&config, "animations:enabled").location.line, 42);
+        assert_eq!(entry(&config, "input:follow_mouse").location.line, 3);
+        assert_eq!(config.all("bind").len(), 2);
+    }
+
+    #[test]
+    fn records_locations() {
+        let config = load_fixture();
+        let main = fixture("hyprland.conf");
+
+        let monitors: Vec<&Location> = config.all("monitor").iter().map(|entry| &entry.location).collect();
+        assert_eq!(
+            monitors,
+            [
+                &Location { file: main.clone(), line: 6 },
+                &Location { file: main.clone(), line: 7 },
+            ]
+        );
+        assert_eq!(entry(&config, "decoration:blur:passes").location, Location { file: main, line: 21 });
+        assert_eq!(
+            entry(&config, "input:kb_layout").location,
+            Location { file: fixture("conf.d/10-input.conf"), line: 2 }
+        );
+    }
+
+    #[test]
+    fn follows_wildcard_variable_and_home_includes() {
+        let config = load_fixture();
+        let files: Vec<PathBuf> = config.documents.iter().map(|document| document.path.clone()).collect();
+        assert_eq!(
+            files,
+            [
+                fixture("hyprland.conf"),
+                fixture("conf.d/10-input.conf"),
+                fixture("conf.d/20-binds.conf"),
+                fixture("colors/theme.conf"),
+                fixture("home/home.conf"),
+                fixture("cycle-a.conf"),
+                fixture("cycle-b.conf"),
+            ]
+        );
+
+        // Included files are evaluated in place, so the theme overrides
+        // the main file's earlier border
+        let border = entry(&config, "general:col.active_border");
+        assert_eq!(border.value, "rgba(ff0000ff)");
+        assert_eq!(border.location.file, fixture("colors/theme.conf"));
+
+        assert_eq!(config.variables().get("terminal").map(String::as_str), Some("foot"));
+        assert_eq!(
+            config.expand_variables(&config.all("bind")[2].value),
+            "SUPER, Return, exec, foot"
+        );
+        assert_eq!(config.expand_variables("$unknown $mainMod"), "$unknown SUPER");
+        assert!(config.includes(&fixture("conf.d/20-binds.conf")));
+        assert!(!config.includes(&fixture("conf.d/readme.txt")));
+    }
+
+    #[test]
+    fn stops_include_cycles() {
+        let config = load_fixture();
+
+        // cycle-b sources cycle-a and hyprland.conf again; both are skipped
+        for name in ["hyprland.conf", "cycle-a.conf"] {
+            let loaded = config.documents.iter().filter(|document| document.path == fixture(name)).count();
+            assert_eq!(loaded, 1, "{} loaded {} times", name, loaded);
+        }
+        assert_eq!(entry(&config, "misc:disable_hyprland_logo").value, "true");
+
+        // Entries after the cycle are still read, in order
+        let rounding = entry(&config, "decoration:rounding");
+        assert_eq!(rounding.value, "4");
+        assert_eq!(rounding.location, Location { file: fixture("cycle-b.conf"), line: 3 });
+        assert_eq!(config.entries().last().unwrap().name, "animations:enabled");
+    }
+
+    #[test]
+    fn saves_only_changed_documents() {
+        let dir = std::env::temp_dir().join(format!("ummit-hyprconf-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let main = dir.join("hyprland.conf");
+        let included = dir.join("included.conf");
+        fs::write(&main, "source = ./included.conf\ngaps_in = 5").unwrap();
+        fs::write(&included, "gaps_out = 10\n").unwrap();
+
+        let mut config = Config::load_with_home(&main, dir.clone()).unwrap();
+        config.set_value(&entry(&config, "gaps_in"), "6");
+        config.append("border_size", "2 # px");
+        fs::remove_file(&included).unwrap();
+        config.save().unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&main).unwrap(),
+            "source = ./included.conf\ngaps_in = 6\nborder_size = 2 ## px\n"
+        );
+        // The untouched include was not written back
+        assert!(!included.exists());
+
+        let reloaded = Config::load_with_home(&main, dir).unwrap();
+        assert_eq!(entry(&reloaded, "border_size").value, "2 # px");
+        assert_eq!(entry(&reloaded, "border_size").location.line, 3);
+    }
+
+    #[test]
+    fn write_atomically_keeps_file_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("ummit-hyprconf-mode-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("hyprland.conf");
+        fs::write(&path, "gaps_in = 5\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+        write_atomically(&path, "gaps_in = 6\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "gaps_in = 6\n");
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+    }
+}
@@ -0,0 +1,292 @@
+use std::process::Command;
+
+use crate::json::{self, JsonValue};
+
+// Thin wrapper around hyprctl for reading and changing Hyprland options
+
+// Read an option with `hyprctl getoption -j`
+pub fn get_option(name: &str) -> Option<JsonValue> {
+    let output = Command::new("hyprctl")
+        .arg("getoption")
+        .arg("-j")
+        .arg(name)
+        .output()
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    match json::parse(&String::from_utf8_lossy(&output.stdout)) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            eprintln!("Failed to parse hyprctl getoption {}: {}", name, e);
+            None
+        }
+    }
+}
+
+// Apply an option live with `hyprctl keyword`
+pub fn keyword(name: &str, value: &str) -> Result<(), String> {
+    println!("hyprctl keyword {} {}", name, value);
+
+    let output = Command::new("hyprctl")
+        .arg("keyword")
+        .arg(name)
+        .arg(value)
+        .output()
+        .map_err(|e| format!("Failed to run hyprctl: {}", e))?;
+
+    let reply = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() && (reply.is_empty() || reply == "ok") {
+        Ok(())
+    } else {
+        Err(format!("hyprctl keyword {} failed: {}", name, reply))
+    }
+}
+
+// Re-read the config files, e.g. after editing binds in them
+pub fn reload() -> Result<(), String> {
+    let output = Command::new("hyprctl")
+        .arg("reload")
+        .output()
+        .map_err(|e| format!("Failed to run hyprctl: {}", e))?;
+
+    let reply = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() && (reply.is_empty() || reply == "ok") {
+        Ok(())
+    } else {
+        Err(format!("hyprctl reload failed: {}", reply))
+    }
+}
+
+// Options behind the System page controls
+pub const ANIMATIONS_ENABLED: &str = "animations:enabled";
+pub const ACTIVE_OPACITY: &str = "decoration:active_opacity";
+pub const INACTIVE_OPACITY: &str = "decoration:inactive_opacity";
+
+// Boolean options are reported as ints
+pub fn get_bool(name: &str) -> Option<bool> {
+    get_option(name)?.i64_field("int").map(|value| value != 0)
+}
+
+pub fn get_float(name: &str) -> Option<f64> {
+    get_option(name)?.f64_field("float")
+}
+
+// Current value of any option, in the syntax `keyword` accepts
+pub fn get_keyword_value(name: &str) -> Option<String> {
+    let option = get_option(name)?;
+
+    if let Some(value) = option.f64_field("float") {
+        return Some(format!("{:.2}", value));
+    }
+    if let Some(value) = option.str_field("str") {
+        return Some(value.to_string());
+    }
+    if let Some(vec2) = option.get("vec2").and_then(JsonValue::as_array) {
+        let parts: Option<Vec<String>> = vec2.iter().map(|part| part.as_f64().map(|part| part.to_string())).collect();
+        return parts.map(|parts| parts.join(" "));
+    }
+    if option.get("custom").is_some() || option.get("gradient").is_some() {
+        return gradient_keyword_value(&option);
+    }
+    option.i64_field("int").map(|value| value.to_string())
+}
+
+// Convert a colour/gradient option as reported by getoption into keyword syntax
+pub fn gradient_keyword_value(option: &JsonValue) -> Option<String> {
+    if let Some(gradient) = option.str_field("custom").or_else(|| option.str_field("gradient")) {
+        // "ee33ccff 00ff99ee 45deg" -> "0xee33ccff 0x00ff99ee 45deg"
+        let parts: Vec<String> = gradient
+            .split_whitespace()
+            .map(|part| {
+                if part.ends_with("deg") {
+                    part.to_string()
+                } else {
+                    format!("0x{}", part)
+                }
+            })
+            .collect();
+        return (!parts.is_empty()).then(|| parts.join(" "));
+    }
+
+    option
+        .i64_field("int")
+        .map(|colour| format!("0x{:08x}", colour as u32))
+}
+
+// Run a hyprctl query such as `clients` or `monitors` with JSON output
+pub fn query(command: &str) -> Option<JsonValue> {
+    let output = Command::new("hyprctl").arg("-j").arg(command).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    json::parse(&String::from_utf8_lossy(&output.stdout))
+        .map_err(|e| eprintln!("Failed to parse hyprctl {}: {}", command, e))
+        .ok()
+}
+
+// Geometries ("x,y wxh", slurp format) of the windows on the workspaces
+// currently shown on any monitor
+pub fn visible_window_geometries() -> Vec<String> {
+    let active_workspaces: Vec<i64> = query("monitors")
+        .as_ref()
+        .and_then(JsonValue::as_array)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|monitor| monitor.get("activeWorkspace")?.i64_field("id"))
+        .collect();
+
+    let pair = |value: Option<&JsonValue>| -> Option<(i64, i64)> {
+        match value?.as_array()? {
+            [a, b] => Some((a.as_i64()?, b.as_i64()?)),
+            _ => None,
+        }
+    };
+
+    query("clients")
+        .as_ref()
+        .and_then(JsonValue::as_array)
+        .unwrap_or_default()
+        .iter()
+        .filter(|client| client.bool_field("hidden") != Some(true))
+        .filter(|client| {
+            client
+                .get("workspace")
+                .and_then(|workspace| workspace.i64_field("id"))
+                .is_some_and(|id| active_workspaces.contains(&id))
+        })
+        .filter_map(|client| {
+            let (x, y) = pair(client.get("at"))?;
+            let (width, height) = pair(client.get("size"))?;
+            Some(format!("{},{} {}x{}", x, y, width, height))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+    use std::sync::{Mutex, MutexGuard, OnceLock};
+
+    // Answers like Hyprland does and logs each call's arguments, one per line
+    // separated by '|', next to itself
+    const STUB: &str = r#"#!/bin/sh
+for arg in "$@"; do printf '%s|' "$arg"; done >> "$(dirname "$0")/calls"
+echo >> "$(dirname "$0")/calls"
+case "$1 $2 $3" in
+    "getoption -j animations:enabled") echo '{"option": "animations:enabled", "int": 1, "set": true}' ;;
+    "getoption -j decoration:active_opacity") echo '{"option": "decoration:active_opacity", "float": 0.900000, "set": true}' ;;
+    "getoption -j decoration:inactive_opacity") echo '{"option": "decoration:inactive_opacity", "float": 1.000000, "set": false}' ;;
+    "getoption -j general:col.active_border") echo '{"option": "general:col.active_border", "gradient": "ee33ccff 00ff99ee 45deg", "set": true}' ;;
+    "getoption -j general:col.inactive_border") echo '{"option": "general:col.inactive_border", "int": 4288585374, "set": false}' ;;
+    getoption*) echo 'no such option' ;;
+    keyword*)
+        if [ "$2" = "bad:option" ]; then echo 'invalid field'; else echo 'ok'; fi ;;
+    *) exit 1 ;;
+esac
+"#;
+
+    static STUB_DIR: OnceLock<PathBuf> = OnceLock::new();
+    static LOCK: Mutex<()> = Mutex::new(());
+
+    // Put the stub first on PATH (once) and start a fresh call log. The
+    // guard keeps tests from interleaving their calls.
+    fn stub_hyprctl() -> MutexGuard<'static, ()> {
+        let guard = LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let dir = STUB_DIR.get_or_init(|| {
+            let dir = std::env::temp_dir().join(format!("ummit-hyprctl-{}", std::process::id()));
+            fs::create_dir_all(&dir).unwrap();
+            let script = dir.join("hyprctl");
+            fs::write(&script, STUB).unwrap();
+            fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+
+            let path = format!("{}:{}", dir.display(), std::env::var("PATH").unwrap_or_default());
+            // SAFETY: set once before any test in this module runs hyprctl
+            unsafe { std::env::set_var("PATH", path) };
+            dir
+        });
+        let _ = fs::remove_file(dir.join("calls"));
+        guard
+    }
+
+    fn calls() -> Vec<String> {
+        fs::read_to_string(STUB_DIR.get().unwrap().join("calls"))
+            .unwrap_or_default()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn reads_options_with_getoption_json() {
+        let _guard = stub_hyprctl();
+
+        assert_eq!(get_bool(ANIMATIONS_ENABLED), Some(true));
+        assert_eq!(get_float(ACTIVE_OPACITY), Some(0.9));
+        assert_eq!(get_float(INACTIVE_OPACITY), Some(1.0));
+        assert_eq!(
+            calls(),
+            [
+                "getoption|-j|animations:enabled|",
+                "getoption|-j|decoration:active_opacity|",
+                "getoption|-j|decoration:inactive_opacity|",
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_option_reads_as_none() {
+        let _guard = stub_hyprctl();
+
+        assert_eq!(get_option("misc:nope"), None);
+        assert_eq!(get_bool("misc:nope"), None);
+        assert_eq!(get_keyword_value("misc:nope"), None);
+    }
+
+    #[test]
+    fn keyword_values_round_trip() {
+        let _guard = stub_hyprctl();
+
+        assert_eq!(get_keyword_value(ANIMATIONS_ENABLED).as_deref(), Some("1"));
+        assert_eq!(get_keyword_value(ACTIVE_OPACITY).as_deref(), Some("0.90"));
+        assert_eq!(
+            get_keyword_value("general:col.active_border").as_deref(),
+            Some("0xee33ccff 0x00ff99ee 45deg")
+        );
+        // Plain colours come back as ints, which keyword also takes
+        assert_eq!(get_keyword_value("general:col.inactive_border").as_deref(), Some("4288585374"));
+        let colour = get_option("general:col.inactive_border").unwrap();
+        assert_eq!(gradient_keyword_value(&colour).as_deref(), Some("0xff9e9e9e"));
+    }
+
+    #[test]
+    fn sets_system_page_options_with_exact_arguments() {
+        let _guard = stub_hyprctl();
+
+        keyword(ANIMATIONS_ENABLED, "0").unwrap();
+        keyword(ACTIVE_OPACITY, "0.85").unwrap();
+        keyword(INACTIVE_OPACITY, "0.70").unwrap();
+        assert_eq!(
+            calls(),
+            [
+                "keyword|animations:enabled|0|",
+                "keyword|decoration:active_opacity|0.85|",
+                "keyword|decoration:inactive_opacity|0.70|",
+            ]
+        );
+    }
+
+    #[test]
+    fn keyword_reports_hyprland_errors() {
+        let _guard = stub_hyprctl();
+
+        let error = keyword("bad:option", "1").unwrap_err();
+        assert!(error.contains("invalid field"), "{}", error);
+    }
+}
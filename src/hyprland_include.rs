@@ -0,0 +1,116 @@
+use gtk::glib;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::hyprconf;
+use crate::hyprland;
+
+// Settings applied live with `hyprctl keyword` are also written to
+// ~/.config/hypr/ummit-settings.conf, which hyprland.conf sources, so they
+// survive a restart. The app owns that file completely; the only change to
+// the user's own config is the single `source =` line, appended at the end so
+// values applied from the app take effect over the user's earlier ones.
+
+const FILE_NAME: &str = "ummit-settings.conf";
+const HEADER: &str = "# Managed by ummit-settings: this file is rewritten whenever a setting is\n\
+                      # applied. It is sourced at the end of hyprland.conf, so these values win\n\
+                      # over anything set earlier there; to override one, set it again below the\n\
+                      # source line.\n";
+
+fn hypr_dir() -> PathBuf {
+    glib::user_config_dir().join("hypr")
+}
+
+pub fn managed_file() -> PathBuf {
+    hypr_dir().join(FILE_NAME)
+}
+
+// The source value as written into hyprland.conf, with ~ where possible
+fn source_value() -> String {
+    let managed = managed_file();
+    match managed.strip_prefix(glib::home_dir()) {
+        Ok(relative) => format!("~/{}", relative.display()),
+        Err(_) => managed.display().to_string(),
+    }
+}
+
+// Keywords like `monitor` are repeated, one rule per output; the others
+// are plain options with a single value
+fn same_target(option: &str, existing: &str, value: &str) -> bool {
+    match option {
+        "monitor" => existing.split(',').next().map(str::trim) == value.split(',').next().map(str::trim),
+        _ => true,
+    }
+}
+
+// The managed file, created with its header the first time
+fn load_managed() -> Result<hyprconf::Config, String> {
+    let managed = managed_file();
+    if !managed.exists() {
+        fs::create_dir_all(hypr_dir()).map_err(|e| format!("Failed to create {}: {}", hypr_dir().display(), e))?;
+        hyprconf::write_atomically(&managed, HEADER)?;
+    }
+    hyprconf::Config::load(&managed)
+}
+
+fn save(option: &str, value: &str) -> Result<(), String> {
+    let mut config = load_managed()?;
+    let existing = config
+        .all(option)
+        .into_iter()
+        .rev()
+        .find(|entry| same_target(option, &entry.value, value))
+        .cloned();
+    match existing {
+        Some(entry) => config.set_value(&entry, value),
+        None => config.append(option, value),
+    }
+    config.save()
+}
+
+// Add the source line to hyprland.conf once, after backing it up
+pub fn ensure_sourced() -> Result<(), String> {
+    let mut config = hyprconf::Config::load(&hyprconf::default_path())?;
+    if config.includes(&managed_file()) {
+        return Ok(());
+    }
+
+    // Dotfile managers often symlink hyprland.conf; back up the real file
+    let config_path = fs::canonicalize(hyprconf::default_path()).unwrap_or_else(|_| hyprconf::default_path());
+    let file_name = config_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let backup = config_path.with_file_name(format!(
+        "{}.{}.bak",
+        file_name,
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    fs::copy(&config_path, &backup).map_err(|e| format!("Failed to back up {}: {}", config_path.display(), e))?;
+    println!("Backed up {} to {}", config_path.display(), backup.display());
+
+    // Appended last, so these values win over earlier ones
+    config.append("source", &source_value());
+    config.save()
+}
+
+// Keep an option (already applied live) for the next Hyprland start
+pub fn persist(option: &str, value: &str) -> Result<(), String> {
+    save(option, value)?;
+    ensure_sourced()
+}
+
+// Add another line for a repeatable keyword such as `bind`, leaving the
+// existing ones alone
+pub fn add(keyword: &str, value: &str) -> Result<(), String> {
+    let mut config = load_managed()?;
+    config.append(keyword, value);
+    config.save()?;
+    ensure_sourced()
+}
+
+// Apply an option live and keep it for the next Hyprland start
+pub fn apply(option: &str, value: &str) -> Result<(), String> {
+    hyprland::keyword(option, value)?;
+    persist(option, value)
+}
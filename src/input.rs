@@ -0,0 +1,865 @@
+use gtk::prelude::*;
+use gtk::{glib, Box, Button, Label, Orientation};
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::fs;
+use std::rc::Rc;
+
+use crate::hooks::{self, HookEvent};
+use crate::hyprconf;
+use crate::hyprland;
+use crate::hyprland_include;
+use crate::revert;
+
+// Keyboard, mouse and touchpad settings from the `input { }` category, plus
+// per-device overrides (`device[name]:option`). Edits are collected and
+// applied together through the apply-with-revert flow, since a wrong layout
+// or sensitivity can make it hard to click "Revert" otherwise.
+
+// xkeyboard-config's list of layouts, variants and options
+const RULES_FILES: [&str; 2] = [
+    "/usr/share/X11/xkb/rules/evdev.lst",
+    "/usr/local/share/X11/xkb/rules/evdev.lst",
+];
+
+const KB_LAYOUT: &str = "input:kb_layout";
+const KB_VARIANT: &str = "input:kb_variant";
+const KB_OPTIONS: &str = "input:kb_options";
+const REPEAT_RATE: &str = "input:repeat_rate";
+const REPEAT_DELAY: &str = "input:repeat_delay";
+const SENSITIVITY: &str = "input:sensitivity";
+const ACCEL_PROFILE: &str = "input:accel_profile";
+const NATURAL_SCROLL: &str = "input:natural_scroll";
+const TOUCHPAD_NATURAL_SCROLL: &str = "input:touchpad:natural_scroll";
+const TAP_TO_CLICK: &str = "input:touchpad:tap-to-click";
+const DISABLE_WHILE_TYPING: &str = "input:touchpad:disable_while_typing";
+
+// Used when kb_layout is unset, as in Hyprland
+const DEFAULT_LAYOUT: &str = "us";
+
+const ACCEL_PROFILES: [(&str, &str); 3] = [("", "Default"), ("flat", "Flat"), ("adaptive", "Adaptive")];
+
+#[derive(Debug, Clone, Default)]
+pub struct OptionGroup {
+    pub name: String,
+    pub description: String,
+    pub options: Vec<(String, String)>,
+}
+
+// (code, description) pairs from evdev.lst
+#[derive(Debug, Clone, Default)]
+pub struct XkbRules {
+    pub layouts: Vec<(String, String)>,
+    // Keyed by layout
+    pub variants: BTreeMap<String, Vec<(String, String)>>,
+    pub option_groups: Vec<OptionGroup>,
+}
+
+// Parse the `! layout`, `! variant` and `! option` sections of evdev.lst
+pub fn parse_rules(text: &str) -> XkbRules {
+    let mut rules = XkbRules::default();
+    let mut section = "";
+
+    for line in text.lines() {
+        if let Some(name) = line.strip_prefix('!') {
+            section = match name.trim() {
+                "layout" => "layout",
+                "variant" => "variant",
+                "option" => "option",
+                _ => "",
+            };
+            continue;
+        }
+
+        let line = line.trim();
+        let Some((code, description)) = line.split_once(char::is_whitespace) else {
+            continue;
+        };
+        let (code, description) = (code.to_string(), description.trim().to_string());
+
+        match section {
+            "layout" => rules.layouts.push((code, description)),
+            // "intl            us: English (US, intl., with dead keys)"
+            "variant" => {
+                if let Some((layout, description)) = description.split_once(": ") {
+                    rules
+                        .variants
+                        .entry(layout.to_string())
+                        .or_default()
+                        .push((code, description.to_string()));
+                }
+            }
+            // Group headers have no colon, their options follow them
+            "option" => {
+                if !code.contains(':') {
+                    rules.option_groups.push(OptionGroup {
+                        name: code,
+                        description,
+                        options: Vec::new(),
+                    });
+                } else if let Some(group) = rules.option_groups.last_mut() {
+                    group.options.push((code, description));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    rules
+}
+
+pub fn load_rules() -> XkbRules {
+    RULES_FILES
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .map(|text| parse_rules(&text))
+        .unwrap_or_else(|| {
+            eprintln!("xkb rules not found, layout lists will be empty");
+            XkbRules::default()
+        })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceKind {
+    Pointer { touchpad: bool },
+    Keyboard,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Device {
+    pub name: String,
+    pub kind: DeviceKind,
+}
+
+// Mice and keyboards from `hyprctl devices -j`
+pub fn read_devices() -> Vec<Device> {
+    let Some(devices) = hyprland::query("devices") else {
+        return Vec::new();
+    };
+
+    let names = |key: &str| -> Vec<String> {
+        devices
+            .get(key)
+            .and_then(|list| list.as_array())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|device| device.str_field("name").map(str::to_string))
+            .collect()
+    };
+
+    let pointers = names("mice").into_iter().map(|name| Device {
+        kind: DeviceKind::Pointer {
+            touchpad: name.contains("touchpad"),
+        },
+        name,
+    });
+    let keyboards = names("keyboards").into_iter().map(|name| Device {
+        name,
+        kind: DeviceKind::Keyboard,
+    });
+
+    pointers.chain(keyboards).collect()
+}
+
+pub fn device_option(device: &str, key: &str) -> String {
+    format!("device[{}]:{}", device, key)
+}
+
+// The global option a device setting falls back to when not overridden
+fn global_option(key: &str, touchpad: bool) -> String {
+    match key {
+        "tap-to-click" | "disable_while_typing" => format!("input:touchpad:{}", key),
+        "natural_scroll" if touchpad => TOUCHPAD_NATURAL_SCROLL.to_string(),
+        _ => format!("input:{}", key),
+    }
+}
+
+// Device options cannot always be read back from Hyprland; use what we
+// persisted, then the global value the device inherits
+fn device_value(device: &str, key: &str, touchpad: bool) -> String {
+    let option = device_option(device, key);
+    hyprland::get_keyword_value(&option)
+        .or_else(|| {
+            let managed = hyprconf::Config::load(&hyprland_include::managed_file()).ok()?;
+            managed.get(&option).map(|entry| entry.value.clone())
+        })
+        .or_else(|| hyprland::get_keyword_value(&global_option(key, touchpad)))
+        .unwrap_or_default()
+}
+
+// Current values and unapplied edits, keyed by option
+struct Editor {
+    current: RefCell<BTreeMap<String, String>>,
+    pending: RefCell<BTreeMap<String, String>>,
+    on_change: std::boxed::Box<dyn Fn(bool)>,
+}
+
+impl Editor {
+    fn value(&self, option: &str) -> String {
+        if let Some(value) = self.pending.borrow().get(option) {
+            return value.clone();
+        }
+        self.current
+            .borrow_mut()
+            .entry(option.to_string())
+            .or_insert_with(|| hyprland::get_keyword_value(option).unwrap_or_default())
+            .clone()
+    }
+
+    // Record a value read some other way than `getoption`
+    fn preload(&self, option: &str, value: String) {
+        self.current.borrow_mut().entry(option.to_string()).or_insert(value);
+    }
+
+    fn set(&self, option: &str, value: &str) {
+        let unchanged = self.current.borrow().get(option).is_some_and(|current| current == value);
+        {
+            let mut pending = self.pending.borrow_mut();
+            if unchanged {
+                pending.remove(option);
+            } else {
+                pending.insert(option.to_string(), value.to_string());
+            }
+        }
+        (self.on_change)(!self.pending.borrow().is_empty());
+    }
+
+    fn changes(&self) -> Vec<revert::Change> {
+        let current = self.current.borrow();
+        self.pending
+            .borrow()
+            .iter()
+            .map(|(option, value)| revert::Change {
+                option: option.clone(),
+                value: value.clone(),
+                previous: current.get(option).cloned().unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    fn clear(&self) {
+        self.current.borrow_mut().clear();
+        self.pending.borrow_mut().clear();
+        (self.on_change)(false);
+    }
+}
+
+fn setting_row<W: IsA<gtk::Widget>>(label_text: &str, widget: &W) -> Box {
+    let row = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(12)
+        .build();
+
+    let label = Label::builder()
+        .label(label_text)
+        .halign(gtk::Align::Start)
+        .hexpand(true)
+        .build();
+
+    row.append(&label);
+    row.append(widget);
+    row
+}
+
+fn section_title(text: &str) -> Label {
+    Label::builder()
+        .label(format!("<span weight='bold'>{}</span>", text))
+        .use_markup(true)
+        .halign(gtk::Align::Start)
+        .margin_top(12)
+        .build()
+}
+
+// Long lists such as layouts can be filtered by typing
+fn searchable_dropdown(labels: &[String]) -> gtk::DropDown {
+    let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+    let dropdown = gtk::DropDown::from_strings(&labels);
+    dropdown.set_expression(Some(gtk::PropertyExpression::new(
+        gtk::StringObject::static_type(),
+        None::<gtk::Expression>,
+        "string",
+    )));
+    dropdown.set_enable_search(true);
+    dropdown
+}
+
+fn switch_row(editor: &Rc<Editor>, label: &str, option: &str) -> Box {
+    let switch = gtk::Switch::builder().valign(gtk::Align::Center).build();
+    switch.set_active(editor.value(option) != "0" && !editor.value(option).is_empty());
+
+    let editor = editor.clone();
+    let option = option.to_string();
+    switch.connect_active_notify(move |switch| {
+        editor.set(&option, if switch.is_active() { "1" } else { "0" });
+    });
+
+    setting_row(label, &switch)
+}
+
+fn spin_row(editor: &Rc<Editor>, label: &str, option: &str, range: (f64, f64, f64), digits: u32) -> Box {
+    let (min, max, step) = range;
+    let spin = gtk::SpinButton::with_range(min, max, step);
+    spin.set_digits(digits);
+    if let Ok(value) = editor.value(option).parse() {
+        spin.set_value(value);
+    }
+
+    let editor = editor.clone();
+    let option = option.to_string();
+    spin.connect_value_changed(move |spin| {
+        let value = if digits == 0 {
+            (spin.value() as i64).to_string()
+        } else {
+            format!("{:.2}", spin.value())
+        };
+        editor.set(&option, &value);
+    });
+
+    setting_row(label, &spin)
+}
+
+fn accel_row(editor: &Rc<Editor>, option: &str) -> Box {
+    let mut values: Vec<String> = ACCEL_PROFILES.iter().map(|(value, _)| value.to_string()).collect();
+    let mut labels: Vec<String> = ACCEL_PROFILES.iter().map(|(_, label)| label.to_string()).collect();
+
+    // Keep a custom profile selectable rather than showing it as Default
+    let current = editor.value(option);
+    if !values.contains(&current) {
+        values.push(current.clone());
+        labels.push(current.clone());
+    }
+
+    let dropdown = searchable_dropdown(&labels);
+    dropdown.set_enable_search(false);
+    dropdown.set_selected(values.iter().position(|value| *value == current).unwrap_or(0) as u32);
+
+    let editor = editor.clone();
+    let option = option.to_string();
+    dropdown.connect_selected_notify(move |dropdown| {
+        if let Some(value) = values.get(dropdown.selected() as usize) {
+            editor.set(&option, value);
+        }
+    });
+
+    setting_row("Acceleration Profile", &dropdown)
+}
+
+type LayoutModel = Rc<RefCell<Vec<(String, String)>>>;
+type CommitLayouts = Rc<dyn Fn(&[(String, String)])>;
+
+// One row per layout with its variant; kb_layout and kb_variant are
+// parallel comma-separated lists
+fn fill_layout_rows(container: &Box, model: &LayoutModel, rules: &Rc<XkbRules>, commit: &CommitLayouts) {
+    while let Some(child) = container.first_child() {
+        container.remove(&child);
+    }
+
+    // Rebuild after the signal that asked for it has returned
+    let refill = {
+        let container = container.clone();
+        let model = model.clone();
+        let rules = rules.clone();
+        let commit = commit.clone();
+        move || {
+            let (container, model, rules, commit) = (container.clone(), model.clone(), rules.clone(), commit.clone());
+            glib::idle_add_local_once(move || fill_layout_rows(&container, &model, &rules, &commit));
+        }
+    };
+
+    let rows = model.borrow().clone();
+    for (index, (layout, variant)) in rows.iter().enumerate() {
+        let row = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .build();
+
+        let mut layout_codes: Vec<String> = rules.layouts.iter().map(|(code, _)| code.clone()).collect();
+        let mut layout_labels: Vec<String> = rules
+            .layouts
+            .iter()
+            .map(|(code, description)| format!("{} ({})", description, code))
+            .collect();
+        if !layout_codes.contains(layout) {
+            layout_codes.push(layout.clone());
+            layout_labels.push(layout.clone());
+        }
+
+        let layout_dropdown = searchable_dropdown(&layout_labels);
+        layout_dropdown.set_hexpand(true);
+        layout_dropdown.set_selected(layout_codes.iter().position(|code| code == layout).unwrap_or(0) as u32);
+
+        let known_variants = rules.variants.get(layout).cloned().unwrap_or_default();
+        let mut variant_codes = vec![String::new()];
+        let mut variant_labels = vec!["Default".to_string()];
+        variant_codes.extend(known_variants.iter().map(|(code, _)| code.clone()));
+        variant_labels.extend(known_variants.iter().map(|(_, description)| description.clone()));
+        if !variant_codes.contains(variant) {
+            variant_codes.push(variant.clone());
+            variant_labels.push(variant.clone());
+        }
+
+        let variant_dropdown = searchable_dropdown(&variant_labels);
+        variant_dropdown.set_hexpand(true);
+        variant_dropdown.set_selected(variant_codes.iter().position(|code| code == variant).unwrap_or(0) as u32);
+
+        let remove_btn = Button::builder()
+            .icon_name("list-remove-symbolic")
+            .tooltip_text("Remove layout")
+            .sensitive(rows.len() > 1)
+            .build();
+
+        {
+            let model = model.clone();
+            let commit = commit.clone();
+            let refill = refill.clone();
+            layout_dropdown.connect_selected_notify(move |dropdown| {
+                let Some(code) = layout_codes.get(dropdown.selected() as usize) else {
+                    return;
+                };
+                // Variants belong to a layout, so start over with the default
+                model.borrow_mut()[index] = (code.clone(), String::new());
+                commit(&model.borrow());
+                refill();
+            });
+        }
+        {
+            let model = model.clone();
+            let commit = commit.clone();
+            variant_dropdown.connect_selected_notify(move |dropdown| {
+                let Some(code) = variant_codes.get(dropdown.selected() as usize) else {
+                    return;
+                };
+                model.borrow_mut()[index].1 = code.clone();
+                commit(&model.borrow());
+            });
+        }
+        {
+            let model = model.clone();
+            let commit = commit.clone();
+            let refill = refill.clone();
+            remove_btn.connect_clicked(move |_| {
+                model.borrow_mut().remove(index);
+                commit(&model.borrow());
+                refill();
+            });
+        }
+
+        row.append(&layout_dropdown);
+        row.append(&variant_dropdown);
+        row.append(&remove_btn);
+        container.append(&row);
+    }
+
+    let add_btn = Button::builder()
+        .label("Add Layout")
+        .halign(gtk::Align::Start)
+        .tooltip_text("Switch between layouts with a grp: option below")
+        .build();
+    {
+        let model = model.clone();
+        let commit = commit.clone();
+        add_btn.connect_clicked(move |_| {
+            model.borrow_mut().push((DEFAULT_LAYOUT.to_string(), String::new()));
+            commit(&model.borrow());
+            refill();
+        });
+    }
+    container.append(&add_btn);
+}
+
+fn layouts_editor(editor: &Rc<Editor>, rules: &Rc<XkbRules>, layout_option: &str, variant_option: &str) -> Box {
+    let container = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(6)
+        .build();
+
+    let layouts = editor.value(layout_option);
+    let variants = editor.value(variant_option);
+    let mut variants = variants.split(',').map(|variant| variant.trim().to_string());
+    let rows: Vec<(String, String)> = if layouts.trim().is_empty() {
+        vec![(DEFAULT_LAYOUT.to_string(), variants.next().unwrap_or_default())]
+    } else {
+        layouts
+            .split(',')
+            .map(|layout| (layout.trim().to_string(), variants.next().unwrap_or_default()))
+            .collect()
+    };
+
+    let model: LayoutModel = Rc::new(RefCell::new(rows));
+    let commit: CommitLayouts = {
+        let editor = editor.clone();
+        let (layout_option, variant_option) = (layout_option.to_string(), variant_option.to_string());
+        Rc::new(move |rows| {
+            let layouts: Vec<&str> = rows.iter().map(|(layout, _)| layout.as_str()).collect();
+            let variants: Vec<&str> = rows.iter().map(|(_, variant)| variant.as_str()).collect();
+            editor.set(&layout_option, &layouts.join(","));
+            // All-default variants are written as an empty value, as usual
+            if variants.iter().all(|variant| variant.is_empty()) {
+                editor.set(&variant_option, "");
+            } else {
+                editor.set(&variant_option, &variants.join(","));
+            }
+        })
+    };
+
+    fill_layout_rows(&container, &model, rules, &commit);
+    container
+}
+
+fn split_options(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|option| !option.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// kb_options as editable text, with a checklist of the known options
+fn options_row(editor: &Rc<Editor>, rules: &XkbRules, option: &str) -> Box {
+    let entry = gtk::Entry::builder()
+        .text(editor.value(option))
+        .placeholder_text("e.g. grp:alt_shift_toggle,caps:escape")
+        .hexpand(true)
+        .build();
+
+    let list = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(2)
+        .margin_top(8)
+        .margin_bottom(8)
+        .margin_start(8)
+        .margin_end(8)
+        .build();
+
+    let syncing = Rc::new(Cell::new(false));
+    let checks: Rc<RefCell<Vec<(String, gtk::CheckButton)>>> = Rc::new(RefCell::new(Vec::new()));
+
+    for group in &rules.option_groups {
+        let group_label = Label::builder()
+            .label(format!("<span weight='bold'>{}</span>", glib::markup_escape_text(&group.description)))
+            .use_markup(true)
+            .tooltip_text(group.name.as_str())
+            .halign(gtk::Align::Start)
+            .margin_top(8)
+            .build();
+        list.append(&group_label);
+
+        for (name, description) in &group.options {
+            let check = gtk::CheckButton::builder()
+                .label(description.as_str())
+                .tooltip_text(name.as_str())
+                .build();
+
+            {
+                let entry = entry.clone();
+                let syncing = syncing.clone();
+                let name = name.clone();
+                check.connect_toggled(move |check| {
+                    if syncing.get() {
+                        return;
+                    }
+                    let mut options = split_options(&entry.text());
+                    options.retain(|option| *option != name);
+                    if check.is_active() {
+                        options.push(name.clone());
+                    }
+                    entry.set_text(&options.join(","));
+                });
+            }
+
+            list.append(&check);
+            checks.borrow_mut().push((name.clone(), check));
+        }
+    }
+
+    let scrolled = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .min_content_width(420)
+        .min_content_height(360)
+        .child(&list)
+        .build();
+
+    let popover = gtk::Popover::builder().child(&scrolled).build();
+    {
+        let entry = entry.clone();
+        let checks = checks.clone();
+        popover.connect_show(move |_| {
+            let options = split_options(&entry.text());
+            syncing.set(true);
+            for (name, check) in checks.borrow().iter() {
+                check.set_active(options.contains(name));
+            }
+            syncing.set(false);
+        });
+    }
+
+    let choose_btn = gtk::MenuButton::builder()
+        .label("Choose…")
+        .popover(&popover)
+        .sensitive(!rules.option_groups.is_empty())
+        .build();
+
+    {
+        let editor = editor.clone();
+        let option = option.to_string();
+        entry.connect_changed(move |entry| {
+            editor.set(&option, &split_options(&entry.text()).join(","));
+        });
+    }
+
+    let controls = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .build();
+    controls.append(&entry);
+    controls.append(&choose_btn);
+
+    let row = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(4)
+        .build();
+    row.append(&Label::builder().label("Keyboard Options").halign(gtk::Align::Start).build());
+    row.append(&controls);
+    row
+}
+
+fn fill_device_controls(container: &Box, editor: &Rc<Editor>, rules: &Rc<XkbRules>, device: &Device) {
+    while let Some(child) = container.first_child() {
+        container.remove(&child);
+    }
+
+    let option = |key: &str| device_option(&device.name, key);
+    match device.kind {
+        DeviceKind::Pointer { touchpad } => {
+            let mut keys = vec!["sensitivity", "accel_profile", "natural_scroll"];
+            if touchpad {
+                keys.extend(["tap-to-click", "disable_while_typing"]);
+            }
+            for key in keys {
+                editor.preload(&option(key), device_value(&device.name, key, touchpad));
+            }
+
+            container.append(&spin_row(editor, "Sensitivity", &option("sensitivity"), (-1.0, 1.0, 0.05), 2));
+            container.append(&accel_row(editor, &option("accel_profile")));
+            container.append(&switch_row(editor, "Natural Scrolling", &option("natural_scroll")));
+            if touchpad {
+                container.append(&switch_row(editor, "Tap to Click", &option("tap-to-click")));
+                container.append(&switch_row(editor, "Disable While Typing", &option("disable_while_typing")));
+            }
+        }
+        DeviceKind::Keyboard => {
+            for key in ["kb_layout", "kb_variant", "kb_options"] {
+                editor.preload(&option(key), device_value(&device.name, key, false));
+            }
+
+            container.append(&Label::builder().label("Layouts").halign(gtk::Align::Start).build());
+            container.append(&layouts_editor(editor, rules, &option("kb_layout"), &option("kb_variant")));
+            container.append(&options_row(editor, rules, &option("kb_options")));
+        }
+    }
+}
+
+fn create_device_section(editor: &Rc<Editor>, rules: &Rc<XkbRules>) -> gtk::Expander {
+    let expander = gtk::Expander::builder()
+        .label("Per-Device Overrides")
+        .margin_top(8)
+        .build();
+
+    let content = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(8)
+        .margin_top(8)
+        .build();
+
+    let devices = read_devices();
+    if devices.is_empty() {
+        content.append(
+            &Label::builder()
+                .label("No devices found (is Hyprland running?)")
+                .halign(gtk::Align::Start)
+                .css_classes(["dim-label"])
+                .build(),
+        );
+        expander.set_child(Some(&content));
+        return expander;
+    }
+
+    let labels: Vec<String> = devices
+        .iter()
+        .map(|device| match device.kind {
+            DeviceKind::Pointer { touchpad: true } => format!("{} (touchpad)", device.name),
+            DeviceKind::Pointer { touchpad: false } => format!("{} (mouse)", device.name),
+            DeviceKind::Keyboard => format!("{} (keyboard)", device.name),
+        })
+        .collect();
+    let device_dropdown = searchable_dropdown(&labels);
+    device_dropdown.set_hexpand(true);
+    content.append(&setting_row("Device", &device_dropdown));
+
+    let hint = Label::builder()
+        .label("Settings shown here apply to this device only and override the ones above.")
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .css_classes(["dim-label"])
+        .build();
+    content.append(&hint);
+
+    let device_box = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(8)
+        .build();
+    content.append(&device_box);
+
+    fill_device_controls(&device_box, editor, rules, &devices[0]);
+    {
+        let editor = editor.clone();
+        let rules = rules.clone();
+        device_dropdown.connect_selected_notify(move |dropdown| {
+            if let Some(device) = devices.get(dropdown.selected() as usize) {
+                fill_device_controls(&device_box, &editor, &rules, device);
+            }
+        });
+    }
+
+    expander.set_child(Some(&content));
+    expander
+}
+
+fn build_controls(editor: &Rc<Editor>, rules: &Rc<XkbRules>) -> Box {
+    let controls = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(8)
+        .build();
+
+    controls.append(&section_title("Keyboard"));
+    controls.append(&layouts_editor(editor, rules, KB_LAYOUT, KB_VARIANT));
+    controls.append(&options_row(editor, rules, KB_OPTIONS));
+    controls.append(&spin_row(editor, "Repeat Rate (per second)", REPEAT_RATE, (1.0, 200.0, 1.0), 0));
+    controls.append(&spin_row(editor, "Repeat Delay (ms)", REPEAT_DELAY, (100.0, 2000.0, 50.0), 0));
+
+    controls.append(&section_title("Mouse"));
+    controls.append(&spin_row(editor, "Sensitivity", SENSITIVITY, (-1.0, 1.0, 0.05), 2));
+    controls.append(&accel_row(editor, ACCEL_PROFILE));
+    controls.append(&switch_row(editor, "Natural Scrolling", NATURAL_SCROLL));
+
+    controls.append(&section_title("Touchpad"));
+    controls.append(&switch_row(editor, "Natural Scrolling", TOUCHPAD_NATURAL_SCROLL));
+    controls.append(&switch_row(editor, "Tap to Click", TAP_TO_CLICK));
+    controls.append(&switch_row(editor, "Disable While Typing", DISABLE_WHILE_TYPING));
+
+    controls.append(&create_device_section(editor, rules));
+    controls
+}
+
+pub fn create_section() -> Box {
+    let section = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(8)
+        .build();
+
+    let rules = Rc::new(load_rules());
+
+    let controls_holder = Box::builder()
+        .orientation(Orientation::Vertical)
+        .build();
+    section.append(&controls_holder);
+
+    let button_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .margin_top(8)
+        .build();
+
+    let apply_btn = Button::builder()
+        .label("Apply")
+        .css_classes(["suggested-action"])
+        .sensitive(false)
+        .build();
+
+    let reset_btn = Button::builder()
+        .label("Reset")
+        .tooltip_text("Discard changes and re-read the current settings")
+        .build();
+
+    button_box.append(&apply_btn);
+    button_box.append(&reset_btn);
+    section.append(&button_box);
+
+    let status_label = Label::builder()
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .css_classes(["dim-label"])
+        .build();
+    section.append(&status_label);
+
+    let editor = {
+        let apply_btn = apply_btn.clone();
+        Rc::new(Editor {
+            current: RefCell::new(BTreeMap::new()),
+            pending: RefCell::new(BTreeMap::new()),
+            on_change: std::boxed::Box::new(move |has_changes| apply_btn.set_sensitive(has_changes)),
+        })
+    };
+
+    // Re-read everything and rebuild the controls from it
+    let reload: Rc<dyn Fn()> = {
+        let editor = editor.clone();
+        let rules = rules.clone();
+        let controls_holder = controls_holder.clone();
+        Rc::new(move || {
+            editor.clear();
+            while let Some(child) = controls_holder.first_child() {
+                controls_holder.remove(&child);
+            }
+            controls_holder.append(&build_controls(&editor, &rules));
+        })
+    };
+
+    {
+        let reload = reload.clone();
+        let status_label = status_label.clone();
+        reset_btn.connect_clicked(move |_| {
+            status_label.set_text("");
+            reload();
+        });
+    }
+
+    {
+        let editor = editor.clone();
+        let reload = reload.clone();
+        let status_label = status_label.clone();
+        apply_btn.connect_clicked(move |button| {
+            let changes = editor.changes();
+            if changes.is_empty() {
+                return;
+            }
+
+            let parent = button
+                .root()
+                .and_then(|root| root.downcast::<gtk::Window>().ok());
+            let applied: Vec<(String, String)> = changes
+                .iter()
+                .map(|change| (change.option.clone(), change.value.clone()))
+                .collect();
+            let reload = reload.clone();
+            let status_label = status_label.clone();
+            revert::apply_with_confirmation(parent.as_ref(), changes, move |kept| {
+                if kept {
+                    status_label.set_text("Input settings saved");
+                    for (option, value) in &applied {
+                        hooks::run(
+                            HookEvent::SettingsApplied,
+                            &[("UMMIT_SETTING", option.clone()), ("UMMIT_VALUE", value.clone())],
+                        );
+                    }
+                } else {
+                    status_label.set_text("Input settings reverted");
+                }
+                reload();
+            });
+        });
+    }
+
+    reload();
+    section
+}
@@ -0,0 +1,412 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+// Minimal JSON reader/writer for hyprctl -j output and Waybar lines
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(BTreeMap<String, JsonValue>),
+}
+
+impl JsonValue {
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JsonValue::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[JsonValue]> {
+        match self {
+            JsonValue::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_f64().map(|value| value as i64)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JsonValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    // Convenience accessors for object fields
+    pub fn str_field(&self, key: &str) -> Option<&str> {
+        self.get(key).and_then(JsonValue::as_str)
+    }
+
+    pub fn i64_field(&self, key: &str) -> Option<i64> {
+        self.get(key).and_then(JsonValue::as_i64)
+    }
+
+    pub fn f64_field(&self, key: &str) -> Option<f64> {
+        self.get(key).and_then(JsonValue::as_f64)
+    }
+
+    pub fn bool_field(&self, key: &str) -> Option<bool> {
+        self.get(key).and_then(JsonValue::as_bool)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.position)
+    }
+}
+
+pub fn parse(input: &str) -> Result<JsonValue, ParseError> {
+    let mut parser = Parser { input: input.as_bytes(), position: 0 };
+    let value = parser.parse_value()?;
+
+    parser.skip_whitespace();
+    if parser.position != parser.input.len() {
+        return Err(parser.error("Trailing characters"));
+    }
+    Ok(value)
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> ParseError {
+        ParseError {
+            position: self.position,
+            message: message.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    fn expect_literal(&mut self, literal: &str, value: JsonValue) -> Result<JsonValue, ParseError> {
+        if self.input[self.position..].starts_with(literal.as_bytes()) {
+            self.position += literal.len();
+            Ok(value)
+        } else {
+            Err(self.error("Invalid literal"))
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue, ParseError> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(b'{') => self.parse_object(),
+            Some(b'[') => self.parse_array(),
+            Some(b'"') => self.parse_string().map(JsonValue::String),
+            Some(b't') => self.expect_literal("true", JsonValue::Bool(true)),
+            Some(b'f') => self.expect_literal("false", JsonValue::Bool(false)),
+            Some(b'n') => self.expect_literal("null", JsonValue::Null),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(_) => Err(self.error("Unexpected character")),
+            None => Err(self.error("Unexpected end of input")),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<JsonValue, ParseError> {
+        let mut map = BTreeMap::new();
+        self.position += 1;
+
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(JsonValue::Object(map));
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error("Expected object key"));
+            }
+            let key = self.parse_string()?;
+
+            self.skip_whitespace();
+            if self.peek() != Some(b':') {
+                return Err(self.error("Expected ':'"));
+            }
+            self.position += 1;
+
+            let value = self.parse_value()?;
+            map.insert(key, value);
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(JsonValue::Object(map));
+                }
+                _ => return Err(self.error("Expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<JsonValue, ParseError> {
+        let mut values = Vec::new();
+        self.position += 1;
+
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(JsonValue::Array(values));
+        }
+
+        loop {
+            values.push(self.parse_value()?);
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(JsonValue::Array(values));
+                }
+                _ => return Err(self.error("Expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, ParseError> {
+        let mut bytes = Vec::new();
+        self.position += 1;
+
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.position += 1;
+                    return String::from_utf8(bytes).map_err(|_| self.error("Invalid UTF-8 in string"));
+                }
+                Some(b'\\') => {
+                    self.position += 1;
+                    let escaped = self.peek().ok_or_else(|| self.error("Unterminated escape"))?;
+                    self.position += 1;
+
+                    let c = match escaped {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.parse_unicode_escape()?,
+                        _ => return Err(self.error("Invalid escape")),
+                    };
+
+                    let mut buffer = [0u8; 4];
+                    bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+                }
+                Some(byte) => {
+                    bytes.push(byte);
+                    self.position += 1;
+                }
+                None => return Err(self.error("Unterminated string")),
+            }
+        }
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, ParseError> {
+        let digits = self
+            .input
+            .get(self.position..self.position + 4)
+            .and_then(|digits| std::str::from_utf8(digits).ok())
+            .ok_or_else(|| self.error("Truncated unicode escape"))?;
+        let value = u32::from_str_radix(digits, 16).map_err(|_| self.error("Invalid unicode escape"))?;
+        self.position += 4;
+        Ok(value)
+    }
+
+    fn parse_unicode_escape(&mut self) -> Result<char, ParseError> {
+        let high = self.parse_hex4()?;
+
+        // Surrogate pair
+        if (0xD800..0xDC00).contains(&high) && self.input[self.position..].starts_with(b"\\u") {
+            self.position += 2;
+            let low = self.parse_hex4()?;
+            let code = 0x10000 + ((high - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+            return char::from_u32(code).ok_or_else(|| self.error("Invalid surrogate pair"));
+        }
+
+        Ok(char::from_u32(high).unwrap_or('\u{FFFD}'))
+    }
+
+    fn parse_number(&mut self) -> Result<JsonValue, ParseError> {
+        let start = self.position;
+
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.peek() {
+            self.position += 1;
+        }
+
+        std::str::from_utf8(&self.input[start..self.position])
+            .ok()
+            .and_then(|number| number.parse::<f64>().ok())
+            .map(JsonValue::Number)
+            .ok_or_else(|| self.error("Invalid number"))
+    }
+}
+
+// Quote and escape a string for JSON output
+pub fn escape_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(pairs: &[(&str, JsonValue)]) -> JsonValue {
+        JsonValue::Object(pairs.iter().map(|(key, value)| (key.to_string(), value.clone())).collect())
+    }
+
+    #[test]
+    fn parses_string_escapes() {
+        let value = parse(r#""quote \" backslash \\ slash \/ \b\f\n\r\t""#).unwrap();
+        assert_eq!(value.as_str(), Some("quote \" backslash \\ slash / \u{8}\u{c}\n\r\t"));
+    }
+
+    #[test]
+    fn parses_unicode_escapes_and_surrogate_pairs() {
+        assert_eq!(parse(r#""\u00e9\u4e2d""#).unwrap().as_str(), Some("é中"));
+        assert_eq!(parse(r#""\ud83d\ude00""#).unwrap().as_str(), Some("😀"));
+        // A lone high surrogate cannot be represented and is replaced
+        assert_eq!(parse(r#""\ud83d""#).unwrap().as_str(), Some("\u{FFFD}"));
+        // Raw UTF-8 passes through untouched
+        assert_eq!(parse("\"DP-1 • 4K\"").unwrap().as_str(), Some("DP-1 • 4K"));
+    }
+
+    #[test]
+    fn parses_numbers() {
+        assert_eq!(parse("0").unwrap().as_f64(), Some(0.0));
+        assert_eq!(parse("-12").unwrap().as_i64(), Some(-12));
+        assert_eq!(parse("143.97").unwrap().as_f64(), Some(143.97));
+        assert_eq!(parse("1e3").unwrap().as_f64(), Some(1000.0));
+        assert_eq!(parse("-2.5E-2").unwrap().as_f64(), Some(-0.025));
+        assert_eq!(parse("4278190335").unwrap().as_i64(), Some(4278190335));
+    }
+
+    #[test]
+    fn parses_nested_values() {
+        let value = parse(
+            r#" { "monitors": [ { "name": "DP-1", "scale": 1.5, "disabled": false, "modes": ["1920x1080@60.00Hz"] } ],
+                  "empty": {}, "none": [], "missing": null } "#,
+        )
+        .unwrap();
+
+        let monitor = &value.get("monitors").and_then(JsonValue::as_array).unwrap()[0];
+        assert_eq!(monitor.str_field("name"), Some("DP-1"));
+        assert_eq!(monitor.f64_field("scale"), Some(1.5));
+        assert_eq!(monitor.bool_field("disabled"), Some(false));
+        assert_eq!(
+            monitor.get("modes"),
+            Some(&JsonValue::Array(vec![JsonValue::String("1920x1080@60.00Hz".to_string())]))
+        );
+        assert_eq!(value.get("empty"), Some(&object(&[])));
+        assert_eq!(value.get("none"), Some(&JsonValue::Array(Vec::new())));
+        assert_eq!(value.get("missing"), Some(&JsonValue::Null));
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        for input in [
+            "",
+            "   ",
+            "{",
+            "[1, 2",
+            "[1 2]",
+            "{\"a\" 1}",
+            "{\"a\": 1,}",
+            "{1: 2}",
+            "\"unterminated",
+            "\"bad escape \\q\"",
+            "\"truncated \\u12",
+            "\"not hex \\uzzzz\"",
+            "-",
+            "1e",
+            "1.2.3",
+            "tru",
+            "nul",
+            "{} trailing",
+            "@",
+        ] {
+            assert!(parse(input).is_err(), "{:?} should not parse", input);
+        }
+    }
+
+    #[test]
+    fn truncated_input_never_panics() {
+        let document = r#"{"name": "DP-1", "x": -1920, "scale": 1.25, "tags": ["\u00e9", "\ud83d\ude00", true, null]}"#;
+        assert!(parse(document).is_ok());
+        for end in 0..document.len() {
+            assert!(parse(&document[..end]).is_err(), "prefix of {} bytes should not parse", end);
+        }
+    }
+
+    #[test]
+    fn errors_report_position() {
+        let error = parse("[1, x]").unwrap_err();
+        assert_eq!(error.position, 4);
+    }
+
+    #[test]
+    fn escapes_strings_for_output() {
+        let escaped = escape_string("a \"b\" \\ \n\u{1}");
+        assert_eq!(escaped, r#""a \"b\" \\ \n\u0001""#);
+        assert_eq!(parse(&escaped).unwrap().as_str(), Some("a \"b\" \\ \n\u{1}"));
+    }
+}
@@ -0,0 +1,894 @@
+use gtk::prelude::*;
+use gtk::{gdk, gio, glib, Box, Button, Label, Orientation};
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::rc::{Rc, Weak};
+
+use crate::hooks::{self, HookEvent};
+use crate::hyprconf;
+use crate::hyprland;
+use crate::hyprland_include;
+
+// Keybindings from hyprland.conf and everything it sources. Binds are edited
+// in the file that defines them through the comment-preserving config
+// writer; new ones are added to the managed include file, so the user's own
+// files only change where they already define a bind. Hyprland is reloaded
+// after every change.
+
+// Offered in the type picker; other flag combinations are kept as found
+const BIND_KINDS: [(&str, &str); 8] = [
+    ("bind", "Normal"),
+    ("binde", "Repeat while held"),
+    ("bindl", "Also when locked"),
+    ("bindel", "Repeat, also when locked"),
+    ("bindr", "On release"),
+    ("bindn", "Non-consuming"),
+    ("bindm", "Mouse (move/resize)"),
+    ("bindd", "With description"),
+];
+
+// Valid flags after "bind", as in `bindel`
+const BIND_FLAGS: &str = "lrenmtisdpocgu";
+
+// Modifier masks as Hyprland (and xkb) number them
+const MODIFIERS: [(&str, u8); 8] = [
+    ("SHIFT", 1 << 0),
+    ("CAPS", 1 << 1),
+    ("CTRL", 1 << 2),
+    ("ALT", 1 << 3),
+    ("MOD2", 1 << 4),
+    ("MOD3", 1 << 5),
+    ("SUPER", 1 << 6),
+    ("MOD5", 1 << 7),
+];
+
+fn modifier_mask(name: &str) -> u8 {
+    let canonical = match name.to_ascii_uppercase().as_str() {
+        "CONTROL" => "CTRL".to_string(),
+        "MOD1" => "ALT".to_string(),
+        "WIN" | "LOGO" | "MOD4" | "META" => "SUPER".to_string(),
+        other => other.to_string(),
+    };
+    MODIFIERS
+        .iter()
+        .find(|(modifier, _)| *modifier == canonical)
+        .map(|(_, mask)| *mask)
+        .unwrap_or(0)
+}
+
+// "SUPER_SHIFT", "SUPER + SHIFT" and "SHIFT SUPER" are all the same
+pub fn parse_modifiers(text: &str) -> u8 {
+    text.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .fold(0, |mask, part| mask | modifier_mask(part))
+}
+
+pub fn format_modifiers(mask: u8) -> String {
+    MODIFIERS
+        .iter()
+        .filter(|(_, bit)| mask & bit != 0)
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn is_bind_keyword(name: &str) -> bool {
+    name.strip_prefix("bind")
+        .is_some_and(|flags| flags.chars().all(|flag| BIND_FLAGS.contains(flag)))
+}
+
+// `has_flag("bindel", 'e')`; "bind" itself must not count as a `d`
+fn has_flag(keyword: &str, flag: char) -> bool {
+    keyword.strip_prefix("bind").is_some_and(|flags| flags.contains(flag))
+}
+
+// The parts of a bind value, e.g. `$mainMod SHIFT, Q, exec, kitty`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BindValue {
+    pub modifiers: String,
+    pub key: String,
+    // Only with the `d` flag
+    pub description: String,
+    pub dispatcher: String,
+    pub args: String,
+}
+
+impl BindValue {
+    pub fn parse(keyword: &str, value: &str) -> BindValue {
+        let described = has_flag(keyword, 'd');
+        let mut parts = value.splitn(if described { 5 } else { 4 }, ',').map(str::trim);
+        let mut next = || parts.next().unwrap_or_default().to_string();
+
+        let modifiers = next();
+        let key = next();
+        let description = if described { next() } else { String::new() };
+        BindValue {
+            modifiers,
+            key,
+            description,
+            dispatcher: next(),
+            args: next(),
+        }
+    }
+
+    pub fn format(&self, keyword: &str) -> String {
+        let mut parts = vec![self.modifiers.as_str(), self.key.as_str()];
+        if has_flag(keyword, 'd') {
+            parts.push(&self.description);
+        }
+        parts.push(&self.dispatcher);
+        if !self.args.is_empty() {
+            parts.push(&self.args);
+        }
+        parts.join(", ")
+    }
+}
+
+// What has to be pressed, for comparing binds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Combination {
+    pub modifiers: u8,
+    pub key: String,
+    // Binds after `submap = name` only apply inside that submap
+    pub submap: Option<String>,
+}
+
+impl Combination {
+    fn new(modifiers: u8, key: &str, submap: Option<String>) -> Combination {
+        Combination {
+            modifiers,
+            key: key.to_lowercase(),
+            submap,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Bind {
+    pub entry: hyprconf::Entry,
+    pub value: BindValue,
+    pub submap: Option<String>,
+    // With $variables substituted
+    pub modifier_mask: u8,
+}
+
+impl Bind {
+    pub fn keyword(&self) -> &str {
+        &self.entry.name
+    }
+
+    pub fn combination(&self) -> Combination {
+        Combination::new(self.modifier_mask, &self.value.key, self.submap.clone())
+    }
+
+    pub fn shortcut_label(&self) -> String {
+        let modifiers = format_modifiers(self.modifier_mask);
+        if modifiers.is_empty() {
+            self.value.key.clone()
+        } else {
+            format!("{} + {}", modifiers.replace(' ', " + "), self.value.key)
+        }
+    }
+}
+
+// Every bind in evaluation order
+pub fn read_binds(config: &hyprconf::Config) -> Vec<Bind> {
+    let mut submap: Option<String> = None;
+    let mut binds = Vec::new();
+
+    for entry in config.entries() {
+        if entry.name == "submap" {
+            submap = (entry.value != "reset").then(|| entry.value.clone());
+            continue;
+        }
+        if !is_bind_keyword(&entry.name) {
+            continue;
+        }
+
+        let value = BindValue::parse(&entry.name, &entry.value);
+        binds.push(Bind {
+            modifier_mask: parse_modifiers(&config.expand_variables(&value.modifiers)),
+            value,
+            entry: entry.clone(),
+            submap: submap.clone(),
+        });
+    }
+
+    binds
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Conflict {
+    // Same keys, same action
+    Duplicate(hyprconf::Location),
+    // Same keys, different action: both run, which is rarely intended
+    Shadows(hyprconf::Location),
+}
+
+// Other binds on the same combination (skipping the one being edited)
+pub fn conflicts(binds: &[Bind], combination: &Combination, value: &BindValue, skip: Option<&hyprconf::Location>) -> Vec<Conflict> {
+    binds
+        .iter()
+        .filter(|other| Some(&other.entry.location) != skip)
+        .filter(|other| other.combination() == *combination)
+        .map(|other| {
+            let same_action =
+                other.value.dispatcher.eq_ignore_ascii_case(&value.dispatcher) && other.value.args == value.args;
+            if same_action {
+                Conflict::Duplicate(other.entry.location.clone())
+            } else {
+                Conflict::Shadows(other.entry.location.clone())
+            }
+        })
+        .collect()
+}
+
+fn describe_location(location: &hyprconf::Location) -> String {
+    let home = glib::home_dir();
+    let file = match location.file.strip_prefix(&home) {
+        Ok(relative) => format!("~/{}", relative.display()),
+        Err(_) => location.file.display().to_string(),
+    };
+    format!("{}:{}", file, location.line)
+}
+
+fn describe_conflicts(conflicts: &[Conflict]) -> String {
+    conflicts
+        .iter()
+        .map(|conflict| match conflict {
+            Conflict::Duplicate(location) => format!("Duplicate of {}", describe_location(location)),
+            Conflict::Shadows(location) => format!("Same keys as {}, with a different action", describe_location(location)),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Debug, Clone)]
+enum Edit {
+    Add { keyword: String, value: String },
+    Change { bind: Bind, keyword: String, value: String },
+    Delete { bind: Bind },
+}
+
+// Re-read the config and make sure the bind is still where we saw it
+fn find_entry(config: &hyprconf::Config, bind: &Bind) -> Result<hyprconf::Entry, String> {
+    config
+        .entries()
+        .iter()
+        .find(|entry| entry.location == bind.entry.location && entry.value == bind.entry.value)
+        .cloned()
+        .ok_or_else(|| {
+            format!(
+                "{} changed on disk since it was read; reload and try again",
+                describe_location(&bind.entry.location)
+            )
+        })
+}
+
+// New binds go to the managed include; changes and deletes are made in
+// whichever file defines the bind
+fn save_edit(edit: &Edit) -> Result<(), String> {
+    match edit {
+        Edit::Add { keyword, value } => hyprland_include::add(keyword, value)?,
+        Edit::Change { bind, keyword, value } => {
+            let mut config = hyprconf::Config::load_default()?;
+            let entry = find_entry(&config, bind)?;
+            if entry.name != *keyword {
+                config.set_key(&entry, keyword);
+            }
+            config.set_value(&entry, value);
+            config.save()?;
+        }
+        Edit::Delete { bind } => {
+            let mut config = hyprconf::Config::load_default()?;
+            let entry = find_entry(&config, bind)?;
+            config.remove(&entry);
+            config.save()?;
+        }
+    }
+
+    hyprland::reload()
+}
+
+// Key names as xkb (and so Hyprland) spells them. Hyprland matches binds on
+// the unshifted keysym, so the key is looked up without modifiers (SHIFT+1
+// is "1", not "exclam"); Shift is kept only in the modifiers.
+fn key_name(display: &gdk::Display, keycode: u32, group: u32, keyval: gdk::Key) -> Option<String> {
+    let unshifted = display
+        .translate_key(keycode, gdk::ModifierType::empty(), group as i32)
+        .map(|(key, ..)| key)
+        .unwrap_or_else(|| keyval.to_lower());
+    let name = unshifted.name()?.to_string();
+    let is_modifier = [
+        "Shift_L", "Shift_R", "Control_L", "Control_R", "Alt_L", "Alt_R", "Super_L", "Super_R", "Meta_L",
+        "Meta_R", "Caps_Lock", "ISO_Level3_Shift",
+    ]
+    .contains(&name.as_str());
+    (!is_modifier).then_some(name)
+}
+
+fn captured_modifiers(state: gdk::ModifierType) -> u8 {
+    [
+        (gdk::ModifierType::SHIFT_MASK, "SHIFT"),
+        (gdk::ModifierType::CONTROL_MASK, "CTRL"),
+        (gdk::ModifierType::ALT_MASK, "ALT"),
+        (gdk::ModifierType::SUPER_MASK, "SUPER"),
+    ]
+    .iter()
+    .filter(|(mask, _)| state.contains(*mask))
+    .fold(0, |modifiers, (_, name)| modifiers | modifier_mask(name))
+}
+
+fn form_row<W: IsA<gtk::Widget>>(label_text: &str, widget: &W) -> Box {
+    let row = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(12)
+        .build();
+
+    let label = Label::builder()
+        .label(label_text)
+        .halign(gtk::Align::Start)
+        .width_chars(12)
+        .xalign(0.0)
+        .build();
+
+    row.append(&label);
+    row.append(widget);
+    row
+}
+
+// Add (`existing` is None) or edit a bind. `on_save` gets the keyword and
+// the new value.
+fn show_bind_dialog<F>(parent: Option<&gtk::Window>, config: &Rc<hyprconf::Config>, binds: &Rc<Vec<Bind>>, existing: Option<&Bind>, on_save: F)
+where
+    F: Fn(String, String) + 'static,
+{
+    let dialog = gtk::Window::builder()
+        .title(if existing.is_some() { "Edit Keybinding" } else { "Add Keybinding" })
+        .modal(true)
+        .default_width(480)
+        .build();
+    dialog.set_transient_for(parent);
+
+    let content = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(8)
+        .margin_top(24)
+        .margin_bottom(24)
+        .margin_start(24)
+        .margin_end(24)
+        .build();
+
+    let value = existing.map(|bind| bind.value.clone()).unwrap_or_default();
+    let keyword = existing.map(|bind| bind.keyword().to_string()).unwrap_or_else(|| "bind".to_string());
+
+    let mut kinds: Vec<String> = BIND_KINDS.iter().map(|(kind, _)| kind.to_string()).collect();
+    let mut kind_labels: Vec<String> = BIND_KINDS
+        .iter()
+        .map(|(kind, label)| format!("{} ({})", label, kind))
+        .collect();
+    if !kinds.contains(&keyword) {
+        kinds.push(keyword.clone());
+        kind_labels.push(keyword.clone());
+    }
+    let kind_labels: Vec<&str> = kind_labels.iter().map(String::as_str).collect();
+    let kind_dropdown = gtk::DropDown::from_strings(&kind_labels);
+    kind_dropdown.set_hexpand(true);
+    kind_dropdown.set_selected(kinds.iter().position(|kind| *kind == keyword).unwrap_or(0) as u32);
+
+    let modifiers_entry = gtk::Entry::builder()
+        .text(value.modifiers.as_str())
+        .placeholder_text("e.g. SUPER SHIFT or $mainMod")
+        .hexpand(true)
+        .build();
+    let key_entry = gtk::Entry::builder()
+        .text(value.key.as_str())
+        .placeholder_text("e.g. Q, Return, mouse:272")
+        .hexpand(true)
+        .build();
+    let capture_btn = gtk::ToggleButton::builder()
+        .label("Record…")
+        .tooltip_text("Press the key combination to use")
+        .build();
+    let key_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .hexpand(true)
+        .build();
+    key_box.append(&key_entry);
+    key_box.append(&capture_btn);
+
+    let description_entry = gtk::Entry::builder()
+        .text(value.description.as_str())
+        .hexpand(true)
+        .sensitive(has_flag(&keyword, 'd'))
+        .build();
+    let dispatcher_entry = gtk::Entry::builder()
+        .text(value.dispatcher.as_str())
+        .placeholder_text("e.g. exec, killactive, workspace")
+        .hexpand(true)
+        .build();
+    let args_entry = gtk::Entry::builder()
+        .text(value.args.as_str())
+        .hexpand(true)
+        .build();
+
+    content.append(&form_row("Type", &kind_dropdown));
+    content.append(&form_row("Modifiers", &modifiers_entry));
+    content.append(&form_row("Key", &key_box));
+    content.append(&form_row("Description", &description_entry));
+    content.append(&form_row("Dispatcher", &dispatcher_entry));
+    content.append(&form_row("Arguments", &args_entry));
+
+    let warning_label = Label::builder()
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .css_classes(["warning"])
+        .build();
+    content.append(&warning_label);
+
+    let buttons = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .halign(gtk::Align::End)
+        .margin_top(8)
+        .build();
+    let cancel_btn = Button::builder().label("Cancel").build();
+    let save_btn = Button::builder()
+        .label("Save")
+        .css_classes(["suggested-action"])
+        .build();
+    buttons.append(&cancel_btn);
+    buttons.append(&save_btn);
+    content.append(&buttons);
+    dialog.set_child(Some(&content));
+
+    let current_keyword = {
+        let kind_dropdown = kind_dropdown.clone();
+        move || kinds[kind_dropdown.selected() as usize].clone()
+    };
+    let current_value = {
+        let (modifiers_entry, key_entry, description_entry, dispatcher_entry, args_entry) = (
+            modifiers_entry.clone(),
+            key_entry.clone(),
+            description_entry.clone(),
+            dispatcher_entry.clone(),
+            args_entry.clone(),
+        );
+        move || BindValue {
+            modifiers: modifiers_entry.text().trim().to_string(),
+            key: key_entry.text().trim().to_string(),
+            description: description_entry.text().trim().to_string(),
+            dispatcher: dispatcher_entry.text().trim().to_string(),
+            args: args_entry.text().trim().to_string(),
+        }
+    };
+
+    // Warn about other binds on the same keys while typing
+    let check: Rc<dyn Fn()> = {
+        let config = config.clone();
+        let binds = binds.clone();
+        let skip = existing.map(|bind| bind.entry.location.clone());
+        let warning_label = warning_label.clone();
+        let save_btn = save_btn.clone();
+        let current_keyword = current_keyword.clone();
+        let current_value = current_value.clone();
+        let description_entry = description_entry.clone();
+        Rc::new(move || {
+            let keyword = current_keyword();
+            let value = current_value();
+            description_entry.set_sensitive(has_flag(&keyword, 'd'));
+            save_btn.set_sensitive(!value.key.is_empty() && !value.dispatcher.is_empty());
+
+            // Edits keep their submap; new binds go to the managed include,
+            // outside any submap
+            let submap = skip
+                .as_ref()
+                .and_then(|location| binds.iter().find(|bind| bind.entry.location == *location))
+                .and_then(|bind| bind.submap.clone());
+            let combination = Combination::new(
+                parse_modifiers(&config.expand_variables(&value.modifiers)),
+                &value.key,
+                submap,
+            );
+            warning_label.set_text(&describe_conflicts(&conflicts(&binds, &combination, &value, skip.as_ref())));
+        })
+    };
+
+    for entry in [&modifiers_entry, &key_entry, &dispatcher_entry, &args_entry] {
+        let check = check.clone();
+        entry.connect_changed(move |_| check());
+    }
+    {
+        let check = check.clone();
+        kind_dropdown.connect_selected_notify(move |_| check());
+    }
+    check();
+
+    // Record a combination while the toggle is down
+    let key_controller = gtk::EventControllerKey::new();
+    key_controller.set_propagation_phase(gtk::PropagationPhase::Capture);
+    {
+        let capture_btn = capture_btn.clone();
+        let modifiers_entry = modifiers_entry.clone();
+        let key_entry = key_entry.clone();
+        key_controller.connect_key_pressed(move |controller, keyval, keycode, state| {
+            if !capture_btn.is_active() {
+                return glib::Propagation::Proceed;
+            }
+            // Wait for a non-modifier key
+            let display = controller
+                .widget()
+                .map(|widget| widget.display())
+                .unwrap_or_else(|| capture_btn.display());
+            let Some(name) = key_name(&display, keycode, controller.group(), keyval) else {
+                return glib::Propagation::Stop;
+            };
+
+            modifiers_entry.set_text(&format_modifiers(captured_modifiers(state)));
+            key_entry.set_text(&name);
+            capture_btn.set_active(false);
+            glib::Propagation::Stop
+        });
+    }
+    dialog.add_controller(key_controller);
+    capture_btn.connect_toggled(|button| {
+        button.set_label(if button.is_active() { "Press keys…" } else { "Record…" });
+    });
+
+    {
+        let dialog = dialog.clone();
+        cancel_btn.connect_clicked(move |_| dialog.close());
+    }
+    {
+        let dialog = dialog.clone();
+        save_btn.connect_clicked(move |_| {
+            let keyword = current_keyword();
+            let value = current_value().format(&keyword);
+            dialog.close();
+            on_save(keyword, value);
+        });
+    }
+
+    dialog.present();
+}
+
+fn show_error(parent: Option<&gtk::Window>, message: &str) {
+    gtk::AlertDialog::builder()
+        .message("Could not save the keybinding")
+        .detail(message)
+        .modal(true)
+        .build()
+        .show(parent);
+}
+
+type Reload = Rc<dyn Fn()>;
+type WeakReload = Weak<dyn Fn()>;
+type Apply = Rc<dyn Fn(Option<&gtk::Window>, Edit)>;
+type Loaded = Rc<RefCell<(Rc<hyprconf::Config>, Rc<Vec<Bind>>)>>;
+
+fn create_bind_row(bind: &Bind, conflicts: &[Conflict], config: &Rc<hyprconf::Config>, binds: &Rc<Vec<Bind>>, apply: &Apply) -> gtk::ListBoxRow {
+    let row_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(12)
+        .margin_top(6)
+        .margin_bottom(6)
+        .margin_start(8)
+        .margin_end(8)
+        .build();
+
+    let shortcut = Label::builder()
+        .label(bind.shortcut_label())
+        .halign(gtk::Align::Start)
+        .width_chars(22)
+        .xalign(0.0)
+        .ellipsize(gtk::pango::EllipsizeMode::End)
+        .tooltip_text(format!("{} = {}", bind.keyword(), bind.entry.value))
+        .build();
+
+    let action_text = match (bind.value.description.is_empty(), bind.value.args.is_empty()) {
+        (false, _) => bind.value.description.clone(),
+        (true, true) => bind.value.dispatcher.clone(),
+        (true, false) => format!("{} {}", bind.value.dispatcher, bind.value.args),
+    };
+    let action = Label::builder()
+        .label(action_text)
+        .halign(gtk::Align::Start)
+        .hexpand(true)
+        .xalign(0.0)
+        .ellipsize(gtk::pango::EllipsizeMode::End)
+        .build();
+
+    let mut source_text = describe_location(&bind.entry.location);
+    if let Some(submap) = &bind.submap {
+        source_text = format!("{} (submap {})", source_text, submap);
+    }
+    let source = Label::builder()
+        .label(source_text)
+        .halign(gtk::Align::End)
+        .ellipsize(gtk::pango::EllipsizeMode::Start)
+        .max_width_chars(32)
+        .css_classes(["dim-label"])
+        .build();
+
+    row_box.append(&shortcut);
+    row_box.append(&action);
+
+    if !conflicts.is_empty() {
+        let warning = gtk::Image::builder()
+            .icon_name("dialog-warning-symbolic")
+            .tooltip_text(describe_conflicts(conflicts))
+            .build();
+        row_box.append(&warning);
+    }
+    row_box.append(&source);
+
+    let edit_btn = Button::builder()
+        .icon_name("document-edit-symbolic")
+        .tooltip_text("Edit")
+        .css_classes(["flat"])
+        .build();
+    let delete_btn = Button::builder()
+        .icon_name("user-trash-symbolic")
+        .tooltip_text("Delete")
+        .css_classes(["flat"])
+        .build();
+    row_box.append(&edit_btn);
+    row_box.append(&delete_btn);
+
+    {
+        let bind = bind.clone();
+        let config = config.clone();
+        let binds = binds.clone();
+        let apply = apply.clone();
+        edit_btn.connect_clicked(move |button| {
+            let parent = button
+                .root()
+                .and_then(|root| root.downcast::<gtk::Window>().ok());
+            let bind_clone = bind.clone();
+            let apply = apply.clone();
+            let parent_clone = parent.clone();
+            show_bind_dialog(parent.as_ref(), &config, &binds, Some(&bind), move |keyword, value| {
+                apply(
+                    parent_clone.as_ref(),
+                    Edit::Change {
+                        bind: bind_clone.clone(),
+                        keyword,
+                        value,
+                    },
+                );
+            });
+        });
+    }
+
+    {
+        let bind = bind.clone();
+        let apply = apply.clone();
+        delete_btn.connect_clicked(move |button| {
+            let parent = button
+                .root()
+                .and_then(|root| root.downcast::<gtk::Window>().ok());
+
+            let dialog = gtk::AlertDialog::builder()
+                .message(format!("Delete {}?", bind.shortcut_label()))
+                .detail(format!(
+                    "The line will be removed from {}.",
+                    describe_location(&bind.entry.location)
+                ))
+                .buttons(["Cancel", "Delete"])
+                .cancel_button(0)
+                .default_button(0)
+                .modal(true)
+                .build();
+
+            let bind = bind.clone();
+            let apply = apply.clone();
+            let parent_clone = parent.clone();
+            dialog.choose(parent.as_ref(), gio::Cancellable::NONE, move |response| {
+                if response == Ok(1) {
+                    apply(parent_clone.as_ref(), Edit::Delete { bind: bind.clone() });
+                }
+            });
+        });
+    }
+
+    gtk::ListBoxRow::builder().child(&row_box).build()
+}
+
+// Text the search box matches against
+fn search_text(bind: &Bind) -> String {
+    format!(
+        "{} {} {} {} {} {} {}",
+        bind.shortcut_label(),
+        bind.value.modifiers,
+        bind.keyword(),
+        bind.value.description,
+        bind.value.dispatcher,
+        bind.value.args,
+        bind.entry.location.file.display(),
+    )
+    .to_lowercase()
+}
+
+pub fn create_section() -> Box {
+    let section = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(8)
+        .build();
+
+    let toolbar = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .build();
+
+    let search_entry = gtk::SearchEntry::builder()
+        .placeholder_text("Search keybindings")
+        .hexpand(true)
+        .build();
+    let add_btn = Button::builder()
+        .icon_name("list-add-symbolic")
+        .tooltip_text("Add keybinding")
+        .build();
+    let reload_btn = Button::builder()
+        .icon_name("view-refresh-symbolic")
+        .tooltip_text("Re-read the Hyprland config")
+        .build();
+
+    toolbar.append(&search_entry);
+    toolbar.append(&add_btn);
+    toolbar.append(&reload_btn);
+    section.append(&toolbar);
+
+    let summary_label = Label::builder()
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .css_classes(["dim-label"])
+        .build();
+    section.append(&summary_label);
+
+    let scrolled = gtk::ScrolledWindow::builder()
+        .height_request(300)
+        .vexpand(true)
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vscrollbar_policy(gtk::PolicyType::Automatic)
+        .build();
+
+    let listbox = gtk::ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .build();
+    scrolled.set_child(Some(&listbox));
+    section.append(&scrolled);
+
+    let status_label = Label::builder()
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .css_classes(["dim-label"])
+        .build();
+    section.append(&status_label);
+
+    // Filled in by `reload`; the add button needs the current binds too
+    let loaded: Loaded = Rc::new(RefCell::new((Rc::new(hyprconf::Config::default()), Rc::new(Vec::new()))));
+    let rows: Rc<RefCell<Vec<(gtk::ListBoxRow, String)>>> = Rc::new(RefCell::new(Vec::new()));
+
+    let filter = {
+        let search_entry = search_entry.clone();
+        let rows = rows.clone();
+        move || {
+            let query = search_entry.text().to_lowercase();
+            for (row, text) in rows.borrow().iter() {
+                row.set_visible(query.split_whitespace().all(|word| text.contains(word)));
+            }
+        }
+    };
+
+    // Set once `reload` exists, which itself needs `apply` for the rows;
+    // weak, as `reload` holds `apply`
+    let reload_slot: Rc<RefCell<Option<WeakReload>>> = Rc::new(RefCell::new(None));
+
+    // Write an edit, reload Hyprland and refresh the list
+    let apply: Apply = {
+        let reload_slot = reload_slot.clone();
+        let status_label = status_label.clone();
+        Rc::new(move |parent: Option<&gtk::Window>, edit: Edit| {
+            match save_edit(&edit) {
+                Ok(()) => {
+                    let (message, value) = match &edit {
+                        Edit::Add { keyword, value } => (format!("Added {} = {}", keyword, value), value.clone()),
+                        Edit::Change { keyword, value, .. } => (format!("Saved {} = {}", keyword, value), value.clone()),
+                        Edit::Delete { bind } => (format!("Deleted {} = {}", bind.keyword(), bind.entry.value), String::new()),
+                    };
+                    status_label.set_text(&message);
+                    hooks::run(
+                        HookEvent::SettingsApplied,
+                        &[("UMMIT_SETTING", "keybinding".to_string()), ("UMMIT_VALUE", value)],
+                    );
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    show_error(parent, &e);
+                }
+            }
+            let reload = reload_slot.borrow().as_ref().and_then(Weak::upgrade);
+            if let Some(reload) = reload {
+                reload();
+            }
+        })
+    };
+
+    let reload: Reload = {
+        let listbox = listbox.clone();
+        let loaded = loaded.clone();
+        let rows = rows.clone();
+        let summary_label = summary_label.clone();
+        let apply = apply.clone();
+        let filter = filter.clone();
+        Rc::new(move || {
+            while let Some(child) = listbox.first_child() {
+                listbox.remove(&child);
+            }
+            rows.borrow_mut().clear();
+
+            let config = match hyprconf::Config::load_default() {
+                Ok(config) => Rc::new(config),
+                Err(e) => {
+                    summary_label.set_text(&e);
+                    return;
+                }
+            };
+            let binds = Rc::new(read_binds(&config));
+
+            let mut conflicting = 0;
+            for bind in binds.iter() {
+                let bind_conflicts = conflicts(&binds, &bind.combination(), &bind.value, Some(&bind.entry.location));
+                if !bind_conflicts.is_empty() {
+                    conflicting += 1;
+                }
+                let row = create_bind_row(bind, &bind_conflicts, &config, &binds, &apply);
+                listbox.append(&row);
+                rows.borrow_mut().push((row, search_text(bind)));
+            }
+
+            let files: BTreeSet<&Path> = binds.iter().map(|bind| bind.entry.location.file.as_path()).collect();
+            let mut summary = format!("{} keybindings in {} files", binds.len(), files.len());
+            if conflicting > 0 {
+                summary.push_str(&format!(", {} sharing keys with another bind", conflicting));
+            }
+            summary_label.set_text(&summary);
+
+            *loaded.borrow_mut() = (config.clone(), binds.clone());
+            filter();
+        })
+    };
+    *reload_slot.borrow_mut() = Some(Rc::downgrade(&reload));
+
+    search_entry.connect_search_changed(move |_| filter());
+
+    {
+        let reload = reload.clone();
+        let status_label = status_label.clone();
+        reload_btn.connect_clicked(move |_| {
+            status_label.set_text("");
+            reload();
+        });
+    }
+
+    {
+        let loaded = loaded.clone();
+        let apply = apply.clone();
+        add_btn.connect_clicked(move |button| {
+            let parent = button
+                .root()
+                .and_then(|root| root.downcast::<gtk::Window>().ok());
+            let (config, binds) = loaded.borrow().clone();
+            let apply = apply.clone();
+            let parent_clone = parent.clone();
+            show_bind_dialog(parent.as_ref(), &config, &binds, None, move |keyword, value| {
+                apply(parent_clone.as_ref(), Edit::Add { keyword, value });
+            });
+        });
+    }
+
+    reload();
+    section
+}
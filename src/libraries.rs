@@ -0,0 +1,115 @@
+use crate::config;
+use crate::paths;
+
+// Named recording directories ("libraries"). Each library is stored as a
+// "Library <name>" group in settings.ini; new recordings go to the selected
+// one.
+
+const CONFIG_GROUP_PREFIX: &str = "Library ";
+const DEFAULT_NAME: &str = "Default";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Library {
+    pub name: String,
+    pub path: String,
+}
+
+// Directory of the default library
+pub fn default_path() -> String {
+    paths::videos_dir().join("wf-recorder").to_string_lossy().to_string()
+}
+
+// All libraries in the order they were added
+pub fn all() -> Vec<Library> {
+    let key_file = config::load();
+
+    let libraries: Vec<Library> = key_file
+        .groups()
+        .iter()
+        .filter_map(|group| {
+            let name = group.strip_prefix(CONFIG_GROUP_PREFIX)?;
+            let path = key_file.string(group, "path").ok()?;
+            Some(Library {
+                name: name.to_string(),
+                path: path.to_string(),
+            })
+        })
+        .collect();
+
+    if libraries.is_empty() {
+        // Before libraries existed there was a single recording directory
+        vec![Library {
+            name: DEFAULT_NAME.to_string(),
+            path: config::get_string("Recording", "directory", &default_path()),
+        }]
+    } else {
+        libraries
+    }
+}
+
+// The library new recordings go to
+pub fn selected() -> Library {
+    let libraries = all();
+    let name = config::get_string("Recording", "library", DEFAULT_NAME);
+
+    libraries
+        .iter()
+        .find(|library| library.name == name)
+        .unwrap_or(&libraries[0])
+        .clone()
+}
+
+pub fn select(name: &str) {
+    config::set_string("Recording", "library", name);
+}
+
+// The name becomes part of a settings.ini group header, which cannot hold
+// brackets or line breaks
+pub fn check_name(name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Enter a library name".to_string());
+    }
+    if name.contains(['[', ']']) || name.chars().any(char::is_control) {
+        return Err("Library names cannot contain brackets or line breaks".to_string());
+    }
+    if all().iter().any(|library| library.name == name) {
+        return Err(format!("A library named \"{}\" already exists", name));
+    }
+    Ok(())
+}
+
+// Add a library or change the directory of an existing one
+pub fn save(library: &Library) -> Result<(), String> {
+    if library.name.contains(['[', ']']) || library.name.chars().any(char::is_control) {
+        return Err(format!("Invalid library name: {}", library.name));
+    }
+
+    let key_file = config::load();
+
+    // Keep the implicit default library once a second one is added
+    if !key_file
+        .groups()
+        .iter()
+        .any(|group| group.starts_with(CONFIG_GROUP_PREFIX))
+    {
+        for existing in all() {
+            key_file.set_string(&format!("{}{}", CONFIG_GROUP_PREFIX, existing.name), "path", &existing.path);
+        }
+    }
+
+    key_file.set_string(&format!("{}{}", CONFIG_GROUP_PREFIX, library.name), "path", &library.path);
+    config::save(&key_file);
+    Ok(())
+}
+
+// Forget a library (its recordings stay on disk). The last one cannot be removed.
+pub fn remove(name: &str) -> bool {
+    if all().len() <= 1 {
+        return false;
+    }
+
+    let key_file = config::load();
+    let _ = key_file.remove_group(&format!("{}{}", CONFIG_GROUP_PREFIX, name));
+    config::save(&key_file);
+    true
+}
@@ -1,15 +1,57 @@
 use gtk::prelude::*;
-use gtk::{glib, Application, ApplicationWindow, Box, Button, HeaderBar, ListBox, ListBoxRow, 
-          Orientation, Paned, ScrolledWindow, Label, Entry, Switch, SpinButton, Adjustment, Image, 
-          MessageDialog, MessageType, ButtonsType, ResponseType};
+use gtk::{gio, glib, Application, ApplicationWindow, Box, Button, HeaderBar, ListBox, ListBoxRow, 
+          Orientation, Paned, ScrolledWindow, Label, Entry, Switch, SpinButton, Adjustment, Image};
 use std::process::Command;
 use std::env;
 use std::fs;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
-use std::time::SystemTime;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+mod apps;
+mod border_indicator;
+mod clipboard;
+mod config;
+mod displays;
+mod dnd;
+mod history;
+mod hooks;
+mod hyprconf;
+mod hyprland;
+mod hyprland_include;
+mod input;
+mod keybinds;
+mod json;
+mod libraries;
+mod markers;
+mod merge;
+mod notifications;
+mod paths;
+mod player;
+mod recorder;
+mod recording;
+mod retention;
+mod revert;
+mod screenshot;
+mod theme;
+mod tray;
+mod waybar;
+
+use hooks::HookEvent;
+use notifications::{NotifyAction, NotifyEvent, Urgency};
+use merge::MergePanel;
+use player::PreviewPlayer;
 
 const APP_ID: &str = "org.ummitos.settings";
+// Give the compositor time to unmap our windows before slurp takes the screen
+const REGION_HIDE_DELAY: Duration = Duration::from_millis(300);
+
+thread_local! {
+    // Set while slurp is waiting for a region, so a second start is ignored
+    static PICKING_REGION: Cell<bool> = const { Cell::new(false) };
+}
 
 // UmmItOS Detection
 fn check_system_requirements() -> bool {
@@ -38,44 +80,117 @@ fn check_system_requirements() -> bool {
 
 // Show GTK error dialog
 fn show_error_dialog_gtk(title: &str, message: &str) {
-    let dialog = MessageDialog::builder()
-        .message_type(MessageType::Error)
-        .buttons(ButtonsType::Ok)
-        .title(title)
-        .text(message)
+    let dialog = gtk::AlertDialog::builder()
+        .message(title)
+        .detail(message)
+        .buttons(["OK"])
         .modal(true)
         .build();
 
-    dialog.set_icon_name(Some("dialog-error"));
-    
-    dialog.connect_response(|dialog, response| {
-        if response == ResponseType::Ok {
-            dialog.close();
-        }
-    });
-    
-    dialog.show();
+    // There is no main loop yet: spin the context until the dialog is answered
+    let answered = Rc::new(std::cell::Cell::new(false));
+    {
+        let answered = answered.clone();
+        dialog.choose(None::<&gtk::Window>, gio::Cancellable::NONE, move |_| answered.set(true));
+    }
     
     let main_context = glib::MainContext::default();
-    while dialog.is_visible() {
+    while !answered.get() {
         main_context.iteration(true);
     }
 }
 
 fn main() -> glib::ExitCode {
+    // Command line subcommands (used by Waybar and keybindings)
+    if let Some(command) = env::args().nth(1) {
+        match command.as_str() {
+            "waybar" => return waybar::run(),
+            "waybar-config" => {
+                println!("{}", waybar::module_snippet());
+                return glib::ExitCode::SUCCESS;
+            }
+            _ => {
+                if let Some(action) = cli_action(&command) {
+                    return run_action_command(action);
+                }
+            }
+        }
+    }
+
     // Check system requirements before starting
     if !check_system_requirements() {
         return glib::ExitCode::FAILURE;
     }
     
-    let app = Application::builder().application_id(APP_ID).build();
-    
+    let app = create_application();
     app.connect_activate(build_ui);
     
     app.run()
 }
 
+fn create_application() -> Application {
+    let app = Application::builder().application_id(APP_ID).build();
+    
+    app.connect_startup(|app| {
+        install_recording_actions(app);
+        tray::install(app);
+        revert::restore_pending();
+        border_indicator::install();
+        dnd::install();
+    });
+    
+    app
+}
+
+// Map a subcommand to the application action it triggers
+fn cli_action(command: &str) -> Option<&'static str> {
+    match command {
+        "record-start" => Some("start-recording"),
+        "record-stop" => Some("stop-recording"),
+        "record-toggle" => Some("toggle-recording"),
+        "record-pause" => Some("toggle-pause"),
+        "open-folder" => Some("open-recordings-folder"),
+        "add-marker" => Some("add-marker"),
+        _ => None,
+    }
+}
+
+// Run an action in the running instance, or headless if there is none
+fn run_action_command(action: &str) -> glib::ExitCode {
+    let app = create_application();
+
+    // The first activation comes from our own run() below; later ones are
+    // real requests to show the window
+    let headless = Rc::new(std::cell::Cell::new(true));
+    app.connect_activate(move |app| {
+        if headless.replace(false) {
+            return;
+        }
+        build_ui(app);
+    });
+
+    if let Err(e) = app.register(None::<&gio::Cancellable>) {
+        eprintln!("Failed to register application: {}", e);
+        return glib::ExitCode::FAILURE;
+    }
+
+    app.activate_action(action, None);
+
+    if app.is_remote() {
+        return glib::ExitCode::SUCCESS;
+    }
+
+    // Primary instance: keeps running only while a recording holds the app
+    app.run_with_args(&["ummit-settings"])
+}
+
 fn build_ui(app: &Application) {
+    // Reuse the existing window (e.g. when activated from the tray icon)
+    if let Some(window) = app.active_window() {
+        window.present();
+        return;
+    }
+
     // Create the main window
     let window = ApplicationWindow::builder()
         .application(app)
@@ -105,11 +220,19 @@ fn build_ui(app: &Application) {
 
     // Create individual pages
     let system_page = create_system_page();
-    let record_page = create_record_page();
+    let displays_page = create_displays_page();
+    let input_page = create_input_page();
+    let keybindings_page = create_keybindings_page();
+    let record_page = create_record_page(&window);
+    let screenshot_page = create_screenshot_page();
     let about_page = create_about_page();
 
     content_stack.add_named(&system_page, Some("system"));
+    content_stack.add_named(&displays_page, Some("displays"));
+    content_stack.add_named(&input_page, Some("input"));
+    content_stack.add_named(&keybindings_page, Some("keybindings"));
     content_stack.add_named(&record_page, Some("record"));
+    content_stack.add_named(&screenshot_page, Some("screenshot"));
     content_stack.add_named(&about_page, Some("about"));
 
     // Show system page by default
@@ -122,6 +245,8 @@ fn build_ui(app: &Application) {
 
     window.set_child(Some(&paned));
     window.present();
+
+    run_retention_policy(&recording_directory());
 }
 
 fn create_sidebar(content_stack: &gtk::Stack) -> ScrolledWindow {
@@ -134,14 +259,19 @@ fn create_sidebar(content_stack: &gtk::Stack) -> ScrolledWindow {
         .selection_mode(gtk::SelectionMode::Single)
         .build();
 
-    // Add sidebar items with icons
+    // Add sidebar items with icons and the stack pages they show
     let sidebar_items = vec![
-        ("System", "preferences-system-symbolic"),
-        ("Record", "media-record-symbolic"),
-        ("About", "help-about-symbolic"),
+        ("System", "preferences-system-symbolic", "system"),
+        ("Displays", "video-display-symbolic", "displays"),
+        ("Input", "input-keyboard-symbolic", "input"),
+        ("Keybindings", "preferences-desktop-keyboard-shortcuts-symbolic", "keybindings"),
+        ("Record", "media-record-symbolic", "record"),
+        ("Screenshot", "camera-photo-symbolic", "screenshot"),
+        ("About", "help-about-symbolic", "about"),
     ];
+    let page_names: Vec<&'static str> = sidebar_items.iter().map(|(_, _, page)| *page).collect();
 
-    for (name, icon_name) in sidebar_items {
+    for (name, icon_name, _) in sidebar_items {
         let row = ListBoxRow::new();
         
         // Create a horizontal box for icon + label
@@ -180,11 +310,8 @@ fn create_sidebar(content_stack: &gtk::Stack) -> ScrolledWindow {
             println!("Selected sidebar item: {}", index);
             
             // Switch to appropriate page based on selection
-            match index {
-                0 => content_stack_clone.set_visible_child_name("system"),
-                1 => content_stack_clone.set_visible_child_name("record"),
-                2 => content_stack_clone.set_visible_child_name("about"),
-                _ => {}
+            if let Some(page) = page_names.get(index as usize) {
+                content_stack_clone.set_visible_child_name(page);
             }
         }
     });
@@ -219,7 +346,85 @@ fn create_system_page() -> Box {
     content_box
 }
 
-fn create_record_page() -> Box {
+fn create_displays_page() -> Box {
+    let content_box = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(12)
+        .margin_top(24)
+        .margin_bottom(24)
+        .margin_start(24)
+        .margin_end(24)
+        .build();
+
+    // Add page title
+    let title_label = Label::builder()
+        .label("<span size='large' weight='bold'>Displays</span>")
+        .use_markup(true)
+        .halign(gtk::Align::Start)
+        .build();
+    
+    content_box.append(&title_label);
+
+    // Add displays section
+    let displays_section = displays::create_section();
+    content_box.append(&displays_section);
+
+    content_box
+}
+
+fn create_input_page() -> Box {
+    let content_box = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(12)
+        .margin_top(24)
+        .margin_bottom(24)
+        .margin_start(24)
+        .margin_end(24)
+        .build();
+
+    // Add page title
+    let title_label = Label::builder()
+        .label("<span size='large' weight='bold'>Input</span>")
+        .use_markup(true)
+        .halign(gtk::Align::Start)
+        .build();
+    
+    content_box.append(&title_label);
+
+    // Add input section
+    let input_section = input::create_section();
+    content_box.append(&input_section);
+
+    content_box
+}
+
+fn create_keybindings_page() -> Box {
+    let content_box = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(12)
+        .margin_top(24)
+        .margin_bottom(24)
+        .margin_start(24)
+        .margin_end(24)
+        .build();
+
+    // Add page title
+    let title_label = Label::builder()
+        .label("<span size='large' weight='bold'>Keybindings</span>")
+        .use_markup(true)
+        .halign(gtk::Align::Start)
+        .build();
+    
+    content_box.append(&title_label);
+
+    // Add keybindings section
+    let keybindings_section = keybinds::create_section();
+    content_box.append(&keybindings_section);
+
+    content_box
+}
+
+fn create_record_page(window: &ApplicationWindow) -> Box {
     let content_box = Box::builder()
         .orientation(Orientation::Vertical)
         .spacing(12)
@@ -239,12 +444,38 @@ fn create_record_page() -> Box {
     content_box.append(&title_label);
 
     // Add recording section
-    let recording_section = create_recording_section();
+    let recording_section = create_recording_section(window);
     content_box.append(&recording_section);
 
     content_box
 }
 
+fn create_screenshot_page() -> Box {
+    let content_box = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(12)
+        .margin_top(24)
+        .margin_bottom(24)
+        .margin_start(24)
+        .margin_end(24)
+        .build();
+
+    // Add page title
+    let title_label = Label::builder()
+        .label("<span size='large' weight='bold'>Screenshots</span>")
+        .use_markup(true)
+        .halign(gtk::Align::Start)
+        .build();
+    
+    content_box.append(&title_label);
+
+    // Add screenshot section
+    let screenshot_section = screenshot::create_section();
+    content_box.append(&screenshot_section);
+
+    content_box
+}
+
 fn create_about_page() -> Box {
     let content_box = Box::builder()
         .orientation(Orientation::Vertical)
@@ -271,14 +502,14 @@ fn create_about_page() -> Box {
     content_box
 }
 
-fn create_recording_section() -> Box {
+fn create_recording_section(window: &ApplicationWindow) -> Box {
     let section_box = Box::builder()
         .orientation(Orientation::Vertical)
         .spacing(8)
         .build();
 
     let section_title = Label::builder()
-        .label("<span weight='bold'>Screen Recording (wf-recorder)</span>")
+        .label("<span weight='bold'>Screen Recording</span>")
         .use_markup(true)
         .halign(gtk::Align::Start)
         .margin_top(12)
@@ -287,16 +518,84 @@ fn create_recording_section() -> Box {
     section_box.append(&section_title);
 
     let description = Label::builder()
-        .label("Record your screen using wf-recorder")
+        .label("Record your screen using wf-recorder, wl-screenrec or gpu-screen-recorder")
         .halign(gtk::Align::Start)
         .wrap(true)
         .build();
     
     section_box.append(&description);
 
+    // Library selection
+    let library_row = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .margin_top(8)
+        .build();
+
+    let library_label = Label::builder()
+        .label("Library:")
+        .halign(gtk::Align::Start)
+        .build();
+
+    let library_names = gtk::StringList::new(&[]);
+    let library_dropdown = gtk::DropDown::builder()
+        .model(&library_names)
+        .hexpand(true)
+        .tooltip_text("New recordings are saved to the selected library")
+        .build();
+
+    let add_library_entry = Entry::builder()
+        .placeholder_text("Library name")
+        .build();
+
+    let add_library_btn = Button::builder()
+        .label("Add")
+        .build();
+    add_library_btn.add_css_class("suggested-action");
+
+    let add_library_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(4)
+        .build();
+    add_library_box.append(&add_library_entry);
+    add_library_box.append(&add_library_btn);
+
+    let add_library_error = Label::builder()
+        .halign(gtk::Align::Start)
+        .visible(false)
+        .build();
+    add_library_error.add_css_class("error");
+
+    let add_library_content = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(4)
+        .build();
+    add_library_content.append(&add_library_box);
+    add_library_content.append(&add_library_error);
+
+    let add_library_popover = gtk::Popover::builder()
+        .child(&add_library_content)
+        .build();
+
+    let new_library_btn = gtk::MenuButton::builder()
+        .icon_name("list-add-symbolic")
+        .tooltip_text("Add a library")
+        .popover(&add_library_popover)
+        .build();
+
+    let remove_library_btn = Button::builder()
+        .icon_name("list-remove-symbolic")
+        .tooltip_text("Remove the selected library (its recordings are kept)")
+        .build();
+
+    library_row.append(&library_label);
+    library_row.append(&library_dropdown);
+    library_row.append(&new_library_btn);
+    library_row.append(&remove_library_btn);
+    section_box.append(&library_row);
+
     // Recording directory setting
-    let user = env::var("USER").unwrap_or_else(|_| "user".to_string());
-    let default_dir = format!("/home/{}/Videos/wf-recorder", user);
+    let default_dir = recording_directory();
     
     let dir_row = Box::builder()
         .orientation(Orientation::Horizontal)
@@ -322,6 +621,12 @@ fn create_recording_section() -> Box {
         .spacing(4)
         .build();
 
+    // Folder chooser
+    let browse_btn = Button::builder()
+        .icon_name("folder-open-symbolic")
+        .tooltip_text("Choose a folder")
+        .build();
+
     // Path validation button with checkmark icon
     let validate_btn = Button::builder()
         .icon_name("emblem-ok-symbolic")
@@ -341,6 +646,36 @@ fn create_recording_section() -> Box {
     // Current applied path (shared state)
     let current_path = Rc::new(RefCell::new(default_dir.clone()));
 
+    // Connect folder chooser
+    {
+        let dir_entry_clone = dir_entry.clone();
+
+        browse_btn.connect_clicked(move |button| {
+            let parent = button
+                .root()
+                .and_then(|root| root.downcast::<gtk::Window>().ok());
+
+            let dialog = gtk::FileDialog::builder()
+                .title("Choose Recording Directory")
+                .modal(true)
+                .build();
+
+            let current = std::path::PathBuf::from(paths::expand(&dir_entry_clone.text()));
+            if current.is_dir() {
+                dialog.set_initial_folder(Some(&gio::File::for_path(&current)));
+            }
+
+            let dir_entry_clone = dir_entry_clone.clone();
+            dialog.select_folder(parent.as_ref(), gio::Cancellable::NONE, move |result| {
+                if let Ok(folder) = result
+                    && let Some(path) = folder.path()
+                {
+                    dir_entry_clone.set_text(&path.to_string_lossy());
+                }
+            });
+        });
+    }
+
     // Connect validation logic
     {
         let dir_entry_clone = dir_entry.clone();
@@ -359,11 +694,13 @@ fn create_recording_section() -> Box {
         let apply_btn_clone = apply_btn.clone();
         
         apply_btn.connect_clicked(move |_| {
-            let path = dir_entry_clone.text().to_string();
+            let path = paths::expand(&dir_entry_clone.text());
+            dir_entry_clone.set_text(&path);
             apply_directory_path(&path, &current_path_clone, &apply_btn_clone);
         });
     }
 
+    buttons_container.append(&browse_btn);
     buttons_container.append(&validate_btn);
     buttons_container.append(&apply_btn);
 
@@ -372,6 +709,251 @@ fn create_recording_section() -> Box {
     dir_row.append(&buttons_container);
     section_box.append(&dir_row);
 
+    // Set while the list is refilled, so the selection changes it causes are
+    // not taken for the user picking a library
+    let updating_libraries = Rc::new(std::cell::Cell::new(false));
+
+    // Refill the library list, keeping the selected library selected
+    let reload_libraries = {
+        let library_names = library_names.clone();
+        let library_dropdown = library_dropdown.clone();
+        let remove_library_btn = remove_library_btn.clone();
+        let updating_libraries = updating_libraries.clone();
+
+        Rc::new(move || {
+            let all_libraries = libraries::all();
+            let selected = libraries::selected();
+            let names: Vec<&str> = all_libraries.iter().map(|library| library.name.as_str()).collect();
+
+            updating_libraries.set(true);
+            library_names.splice(0, library_names.n_items(), &names);
+            library_dropdown.set_selected(
+                all_libraries
+                    .iter()
+                    .position(|library| library.name == selected.name)
+                    .unwrap_or(0) as u32,
+            );
+            updating_libraries.set(false);
+            remove_library_btn.set_sensitive(all_libraries.len() > 1);
+        })
+    };
+    reload_libraries();
+
+    // Switching library switches the directory being edited and browsed
+    {
+        let dir_entry_clone = dir_entry.clone();
+        let current_path_clone = current_path.clone();
+
+        library_dropdown.connect_selected_notify(move |dropdown| {
+            if updating_libraries.get() {
+                return;
+            }
+            let Some(library) = libraries::all().into_iter().nth(dropdown.selected() as usize) else {
+                return;
+            };
+
+            libraries::select(&library.name);
+            dir_entry_clone.set_text(&library.path);
+            *current_path_clone.borrow_mut() = library.path.clone();
+            println!("Selected library: {} ({})", library.name, library.path);
+        });
+    }
+
+    {
+        let reload_libraries = reload_libraries.clone();
+        let add_library_entry_clone = add_library_entry.clone();
+        let add_library_popover = add_library_popover.clone();
+        let add_library_error = add_library_error.clone();
+        let dir_entry_clone = dir_entry.clone();
+        let current_path_clone = current_path.clone();
+
+        let add_library = move || {
+            let name = add_library_entry_clone.text().trim().to_string();
+            let path = format!("{}/{}", libraries::default_path(), name);
+            let saved = libraries::check_name(&name)
+                .and_then(|_| libraries::save(&libraries::Library { name: name.clone(), path: path.clone() }));
+            if let Err(e) = saved {
+                add_library_entry_clone.add_css_class("error");
+                add_library_error.set_text(&e);
+                add_library_error.set_visible(true);
+                return;
+            }
+            libraries::select(&name);
+
+            add_library_entry_clone.set_text("");
+            add_library_entry_clone.remove_css_class("error");
+            add_library_error.set_visible(false);
+            add_library_popover.popdown();
+            reload_libraries();
+
+            // The list refill does not count as a selection, so switch here
+            dir_entry_clone.set_text(&path);
+            *current_path_clone.borrow_mut() = path.clone();
+        };
+
+        let add_library = Rc::new(add_library);
+        {
+            let add_library = add_library.clone();
+            add_library_btn.connect_clicked(move |_| add_library());
+        }
+        add_library_entry.connect_activate(move |_| add_library());
+    }
+
+    {
+        let reload_libraries = reload_libraries.clone();
+        let dir_entry_clone = dir_entry.clone();
+        let current_path_clone = current_path.clone();
+
+        remove_library_btn.connect_clicked(move |_| {
+            let library = libraries::selected();
+            if libraries::remove(&library.name) {
+                println!("Removed library: {}", library.name);
+                reload_libraries();
+
+                // The dropdown index may not have changed, so update directly
+                let selected = libraries::selected();
+                dir_entry_clone.set_text(&selected.path);
+                *current_path_clone.borrow_mut() = selected.path;
+            }
+        });
+    }
+
+    // Recorder backend and options
+    let backends = recorder::backends();
+    let backend_labels: Vec<String> = backends
+        .iter()
+        .map(|backend| {
+            if backend.is_installed() {
+                backend.name().to_string()
+            } else {
+                format!("{} (not installed)", backend.name())
+            }
+        })
+        .collect();
+    let backend_labels: Vec<&str> = backend_labels.iter().map(String::as_str).collect();
+
+    let backend_dropdown = gtk::DropDown::from_strings(&backend_labels);
+    let selected_backend = recorder::selected_backend();
+    backend_dropdown.set_selected(
+        backends
+            .iter()
+            .position(|backend| backend.name() == selected_backend.name())
+            .unwrap_or(0) as u32,
+    );
+    section_box.append(&create_setting_row("Recorder", backend_dropdown.clone()));
+
+    let preset_labels: Vec<&str> = recorder::Preset::ALL.iter().map(|preset| preset.label()).collect();
+    let preset_dropdown = gtk::DropDown::from_strings(&preset_labels);
+    preset_dropdown.set_selected(
+        recorder::Preset::ALL
+            .iter()
+            .position(|preset| *preset == recorder::selected_preset())
+            .unwrap_or(0) as u32,
+    );
+    preset_dropdown.connect_selected_notify(|dropdown| {
+        if let Some(preset) = recorder::Preset::ALL.get(dropdown.selected() as usize) {
+            recorder::set_selected_preset(*preset);
+        }
+    });
+    section_box.append(&create_setting_row("Preset", preset_dropdown.clone()));
+
+    let audio_switch = create_switch();
+    audio_switch.set_active(recorder::record_audio());
+    audio_switch.connect_active_notify(|switch| {
+        recorder::set_record_audio(switch.is_active());
+    });
+    section_box.append(&create_setting_row("Record audio", audio_switch.clone()));
+
+    let region_switch = create_switch();
+    region_switch.set_active(recorder::select_region());
+    region_switch.set_tooltip_text(Some("Pick the area to record with slurp when recording starts"));
+    region_switch.connect_active_notify(|switch| {
+        recorder::set_select_region(switch.is_active());
+    });
+    section_box.append(&create_setting_row("Select region before recording", region_switch.clone()));
+
+    // Grey out what the selected backend cannot do
+    let update_capabilities = move |backend: &dyn recorder::RecorderBackend| {
+        let capabilities = backend.capabilities();
+        preset_dropdown.set_sensitive(capabilities.framerate || capabilities.codec);
+        audio_switch.set_sensitive(capabilities.audio);
+        region_switch.set_sensitive(capabilities.region);
+    };
+    update_capabilities(selected_backend.as_ref());
+
+    backend_dropdown.connect_selected_notify(move |dropdown| {
+        if let Some(backend) = recorder::backends().into_iter().nth(dropdown.selected() as usize) {
+            recorder::set_selected_backend(backend.name());
+            update_capabilities(backend.as_ref());
+        }
+    });
+
+    // Border indicator setting
+    let border_switch = create_switch();
+    border_switch.set_active(border_indicator::is_enabled());
+    border_switch.set_valign(gtk::Align::Center);
+    border_switch.connect_active_notify(|switch| {
+        border_indicator::set_enabled(switch.is_active());
+    });
+
+    let border_colour_entry = Entry::builder()
+        .text(border_indicator::colour())
+        .placeholder_text(border_indicator::DEFAULT_COLOUR)
+        .tooltip_text("Border colour in Hyprland syntax, e.g. rgba(ff3333ee)")
+        .width_chars(16)
+        .build();
+    connect_entry_commit(&border_colour_entry, |entry| {
+        let colour = entry.text();
+        let colour = if colour.trim().is_empty() { border_indicator::DEFAULT_COLOUR } else { colour.trim() };
+        border_indicator::set_colour(colour);
+    });
+
+    let border_controls = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .build();
+    border_controls.append(&border_colour_entry);
+    border_controls.append(&border_switch);
+
+    let border_row = create_setting_row("Highlight active border while recording", border_controls);
+    section_box.append(&border_row);
+
+    // Do not disturb setting
+    let dnd_switch = create_switch();
+    dnd_switch.set_active(dnd::is_enabled());
+    dnd_switch.set_tooltip_text(Some(&match dnd::detect_backend() {
+        Some(backend) => format!("Notifications are paused through {} while recording", backend.name()),
+        None => "No supported notification daemon (mako, dunst, swaync) is running".to_string(),
+    }));
+    dnd_switch.connect_active_notify(|switch| {
+        dnd::set_enabled(switch.is_active());
+    });
+
+    let dnd_row = create_setting_row("Do not disturb while recording", dnd_switch);
+    section_box.append(&dnd_row);
+
+    // Chapters setting
+    let chapters_switch = create_switch();
+    chapters_switch.set_active(markers::embed_chapters_enabled());
+    chapters_switch.set_tooltip_text(Some("Requires ffmpeg"));
+    chapters_switch.connect_active_notify(|switch| {
+        markers::set_embed_chapters_enabled(switch.is_active());
+    });
+
+    let chapters_row = create_setting_row("Embed markers as chapters when stopping", chapters_switch);
+    section_box.append(&chapters_row);
+
+    // Clipboard setting
+    let copy_switch = create_switch();
+    copy_switch.set_active(recorder::copy_on_save());
+    copy_switch.set_tooltip_text(Some("Requires wl-clipboard"));
+    copy_switch.connect_active_notify(|switch| {
+        recorder::set_copy_on_save(switch.is_active());
+    });
+
+    let copy_row = create_setting_row("Copy recording to clipboard when saved", copy_switch);
+    section_box.append(&copy_row);
+
     // Recording status
     let status_label = Label::builder()
         .label("Status: Ready")
@@ -381,9 +963,6 @@ fn create_recording_section() -> Box {
     
     section_box.append(&status_label);
 
-    // Recording state - shared between buttons
-    let is_recording = Rc::new(RefCell::new(false));
-
     // Create buttons row
     let buttons_box = Box::builder()
         .orientation(Orientation::Horizontal)
@@ -394,83 +973,771 @@ fn create_recording_section() -> Box {
     // Start recording button
     let start_btn = Button::builder()
         .label("Start Recording")
+        .action_name("app.start-recording")
         .build();
     
     // Stop recording button
     let stop_btn = Button::builder()
         .label("Stop Recording")
-        .sensitive(false)
+        .action_name("app.stop-recording")
+        .build();
+
+    // Pause/resume button
+    let pause_btn = Button::builder()
+        .label("Pause")
+        .action_name("app.toggle-pause")
+        .build();
+
+    // Add marker button
+    let marker_btn = Button::builder()
+        .label("Add Marker")
+        .tooltip_text("Mark the current position of the recording")
+        .action_name("app.add-marker")
         .build();
 
     // Open recordings folder button
     let open_folder_btn = Button::builder()
         .label("Open Recordings Folder")
+        .action_name("app.open-recordings-folder")
         .build();
 
-    // Connect start button
-    {
+    // Keep the status label and pause button in sync with the recording state
+    // for as long as this window exists
+    let status_subscription = {
         let status_label_clone = status_label.clone();
-        let current_path_clone = current_path.clone();
-        let is_recording_clone = is_recording.clone();
-        let start_btn_clone = start_btn.clone();
-        let stop_btn_clone = stop_btn.clone();
-        
-        start_btn.connect_clicked(move |_| {
-            if *is_recording_clone.borrow() {
-                status_label_clone.set_text("Error: Recording already in progress");
-                return;
-            }
-            
-            let recording_dir = current_path_clone.borrow().clone();
-            if start_recording(&recording_dir, &status_label_clone) {
-                *is_recording_clone.borrow_mut() = true;
-                start_btn_clone.set_sensitive(false);
-                stop_btn_clone.set_sensitive(true);
-            }
-        });
-    }
+        recording::subscribe_status(move |message| status_label_clone.set_text(message))
+    };
+
+    let session_subscription = {
+        let pause_btn_clone = pause_btn.clone();
+        
+        recording::subscribe(move |session| {
+            let paused = session.map(|session| session.is_paused()).unwrap_or(false);
+            pause_btn_clone.set_label(if paused { "Resume" } else { "Pause" });
+        })
+    };
+
+    let subscriptions = std::cell::Cell::new(vec![status_subscription, session_subscription]);
+    window.connect_destroy(move |_| drop(subscriptions.take()));
+
+    buttons_box.append(&start_btn);
+    buttons_box.append(&stop_btn);
+    buttons_box.append(&pause_btn);
+    buttons_box.append(&marker_btn);
+    buttons_box.append(&open_folder_btn);
+
+    section_box.append(&buttons_box);
+
+    let file_list_section = create_file_list_section(&current_path);
+    section_box.append(&file_list_section);
+
+    let history_section = create_history_section();
+    section_box.append(&history_section);
+
+    let storage_section = create_storage_section(&current_path);
+    section_box.append(&storage_section);
+
+    let notification_section = create_notification_settings_section();
+    section_box.append(&notification_section);
+
+    let hooks_section = create_hooks_section();
+    section_box.append(&hooks_section);
+
+    let waybar_section = create_waybar_section();
+    section_box.append(&waybar_section);
+
+    section_box
+}
+
+// Directory of the selected library (persisted across restarts)
+fn recording_directory() -> String {
+    libraries::selected().path
+}
+
+// Recording actions live on the application so the tray icon, Waybar and the
+// command line can use them even when no window is open
+fn install_recording_actions(app: &Application) {
+    let start_action = gio::SimpleAction::new("start-recording", None);
+    let stop_action = gio::SimpleAction::new("stop-recording", None);
+    let toggle_action = gio::SimpleAction::new("toggle-recording", None);
+    let pause_action = gio::SimpleAction::new("toggle-pause", None);
+    let open_folder_action = gio::SimpleAction::new("open-recordings-folder", None);
+    let marker_action = gio::SimpleAction::new("add-marker", None);
+
+    start_action.connect_activate(|_, _| {
+        if recording::is_recording() {
+            recording::report_status("Error: Recording already in progress");
+            return;
+        }
+        
+        start_recording(&recording_directory());
+    });
+
+    stop_action.connect_activate(|_, _| {
+        let Some(session) = recording::current() else {
+            recording::report_status("Error: No recording in progress");
+            return;
+        };
+        
+        stop_recording(&session.recording_dir);
+    });
+
+    toggle_action.connect_activate(|_, _| match recording::current() {
+        Some(session) => stop_recording(&session.recording_dir),
+        None => {
+            start_recording(&recording_directory());
+        }
+    });
+
+    pause_action.connect_activate(|_, _| {
+        if let Some(session) = recording::current() {
+            recording::set_paused(!session.is_paused());
+        }
+    });
+
+    marker_action.connect_activate(|_, _| {
+        markers::add_marker();
+    });
+
+    open_folder_action.connect_activate(|_, _| {
+        // Follow the active recording if there is one
+        let recording_dir = recording::current()
+            .map(|session| session.recording_dir)
+            .unwrap_or_else(recording_directory);
+        open_recordings_folder(&recording_dir);
+    });
 
-    // Connect stop button
     {
-        let status_label_clone = status_label.clone();
-        let current_path_clone = current_path.clone();
-        let is_recording_clone = is_recording.clone();
-        let start_btn_clone = start_btn.clone();
-        let stop_btn_clone = stop_btn.clone();
+        let start_action = start_action.clone();
+        let stop_action = stop_action.clone();
+        let pause_action = pause_action.clone();
+        let marker_action = marker_action.clone();
         
-        stop_btn.connect_clicked(move |_| {
-            if !*is_recording_clone.borrow() {
-                status_label_clone.set_text("Error: No recording in progress");
-                return;
+        recording::subscribe(move |session| {
+            start_action.set_enabled(session.is_none());
+            stop_action.set_enabled(session.is_some());
+            pause_action.set_enabled(session.is_some_and(|session| {
+                recorder::backend_by_name(&session.backend).is_some_and(|backend| backend.capabilities().pause)
+            }));
+            marker_action.set_enabled(session.is_some());
+        })
+        .keep();
+    }
+
+    app.add_action(&start_action);
+    app.add_action(&stop_action);
+    app.add_action(&toggle_action);
+    app.add_action(&pause_action);
+    app.add_action(&open_folder_action);
+    app.add_action(&marker_action);
+
+    // Notice when the recorder exits without being stopped from here
+    glib::timeout_add_seconds_local(1, || {
+        if recording::has_exited()
+            && let Some(session) = recording::end()
+        {
+            let error = format!("{} exited unexpectedly", session.backend);
+            println!("{} while recording {}", error, session.file_path);
+            recording::report_status(&format!("Error: {}", error));
+
+            history::record_finished(
+                &session,
+                chrono::Local::now().timestamp(),
+                history::Outcome::Failed,
+                std::path::Path::new(&session.file_path),
+            );
+
+            let mut env = recording_hook_env(&session);
+            env.push(("UMMIT_ERROR", error.clone()));
+            hooks::run(HookEvent::RecordingFailed, &env);
+
+            notifications::notify(
+                NotifyEvent::RecordingFailed,
+                "Recording ended unexpectedly",
+                &format!("{} while recording {}", error, session.file_path),
+                &[NotifyAction::OpenFolder(session.recording_dir.clone())],
+            );
+        }
+        glib::ControlFlow::Continue
+    });
+}
+
+fn create_waybar_section() -> gtk::Expander {
+    let expander = gtk::Expander::builder()
+        .label("Waybar Integration")
+        .margin_top(8)
+        .build();
+
+    let content_box = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(8)
+        .margin_top(8)
+        .build();
+
+    let description = Label::builder()
+        .label("Add this module to your Waybar config to show the recording state. Click toggles recording, right-click pauses.")
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .build();
+    description.add_css_class("dim-label");
+    content_box.append(&description);
+
+    let snippet = waybar::module_snippet();
+
+    let text_view = gtk::TextView::builder()
+        .editable(false)
+        .monospace(true)
+        .wrap_mode(gtk::WrapMode::None)
+        .build();
+    text_view.buffer().set_text(&snippet);
+
+    let scrolled = ScrolledWindow::builder()
+        .height_request(220)
+        .child(&text_view)
+        .build();
+    content_box.append(&scrolled);
+
+    let copy_btn = Button::builder()
+        .label("Copy Snippet")
+        .halign(gtk::Align::Start)
+        .build();
+
+    copy_btn.connect_clicked(move |button| {
+        button.clipboard().set_text(&snippet);
+        button.set_label("Copied");
+    });
+
+    content_box.append(&copy_btn);
+
+    expander.set_child(Some(&content_box));
+    expander
+}
+
+// Environment passed to hook commands for a recording session
+fn recording_hook_env(session: &recording::RecordingSession) -> Vec<(&'static str, String)> {
+    vec![
+        ("UMMIT_FILE", session.file_path.clone()),
+        ("UMMIT_RECORDING_DIR", session.recording_dir.clone()),
+        ("UMMIT_DURATION", session.elapsed().as_secs().to_string()),
+        ("UMMIT_PRESET", session.preset.clone()),
+    ]
+}
+
+fn create_hooks_section() -> gtk::Expander {
+    let expander = gtk::Expander::builder()
+        .label("Hook Commands")
+        .margin_top(8)
+        .build();
+
+    let content_box = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(4)
+        .margin_top(8)
+        .build();
+
+    let description = Label::builder()
+        .label(format!(
+            "Shell commands run on each event. Event data is available as $UMMIT_EVENT, $UMMIT_FILE, $UMMIT_DURATION, $UMMIT_PRESET, $UMMIT_RECORDING_DIR, $UMMIT_ERROR, $UMMIT_SETTING and $UMMIT_VALUE. Output is logged to {}",
+            hooks::log_file().display()
+        ))
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .build();
+    description.add_css_class("dim-label");
+    content_box.append(&description);
+
+    for event in HookEvent::ALL {
+        let command_entry = Entry::builder()
+            .text(hooks::command(event))
+            .placeholder_text("e.g. notify-send \"$UMMIT_FILE\"")
+            .hexpand(true)
+            .width_chars(30)
+            .build();
+
+        connect_entry_commit(&command_entry, move |entry| {
+            hooks::set_command(event, entry.text().trim());
+        });
+
+        let row = create_setting_row(event.label(), command_entry);
+        content_box.append(&row);
+    }
+
+    let timeout_adj = Adjustment::new(hooks::timeout_secs() as f64, 1.0, 3600.0, 1.0, 10.0, 0.0);
+    let timeout_spin = SpinButton::new(Some(&timeout_adj), 1.0, 0);
+    timeout_spin.connect_value_changed(|spin| {
+        hooks::set_timeout_secs(spin.value_as_int());
+    });
+
+    let timeout_row = create_setting_row("Hook timeout (seconds)", timeout_spin);
+    content_box.append(&timeout_row);
+
+    expander.set_child(Some(&content_box));
+    expander
+}
+
+fn create_history_section() -> gtk::Expander {
+    let expander = gtk::Expander::builder()
+        .label("Recording History")
+        .margin_top(8)
+        .build();
+
+    let content_box = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(4)
+        .margin_top(8)
+        .build();
+
+    let header_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .build();
+
+    let history_label = Label::builder()
+        .label(format!("Sessions logged in {}", history::history_file().display()))
+        .halign(gtk::Align::Start)
+        .hexpand(true)
+        .wrap(true)
+        .build();
+    history_label.add_css_class("dim-label");
+
+    let refresh_btn = Button::builder()
+        .icon_name("view-refresh-symbolic")
+        .tooltip_text("Refresh history")
+        .build();
+
+    header_box.append(&history_label);
+    header_box.append(&refresh_btn);
+    content_box.append(&header_box);
+
+    let scrolled = ScrolledWindow::builder()
+        .height_request(200)
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .vscrollbar_policy(gtk::PolicyType::Automatic)
+        .build();
+
+    let history_listbox = ListBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .build();
+
+    scrolled.set_child(Some(&history_listbox));
+    content_box.append(&scrolled);
+
+    let refresh_history = {
+        let history_listbox = history_listbox.clone();
+
+        Rc::new(move || {
+            while let Some(child) = history_listbox.first_child() {
+                history_listbox.remove(&child);
             }
-            
+
+            let mut entries = history::load();
+            history::locate_moved(&mut entries);
+            if entries.is_empty() {
+                let empty_label = Label::builder()
+                    .label("No recordings yet")
+                    .margin_top(12)
+                    .margin_bottom(12)
+                    .build();
+                empty_label.add_css_class("dim-label");
+                history_listbox.append(&empty_label);
+            }
+
+            for entry in entries.iter().take(200) {
+                history_listbox.append(&create_history_row(entry));
+            }
+        })
+    };
+
+    {
+        let refresh_history_clone = refresh_history.clone();
+        refresh_btn.connect_clicked(move |_| refresh_history_clone());
+    }
+
+    {
+        let refresh_history_clone = refresh_history.clone();
+        expander.connect_expanded_notify(move |expander| {
+            if expander.is_expanded() {
+                refresh_history_clone();
+            }
+        });
+    }
+
+    expander.set_child(Some(&content_box));
+    expander
+}
+
+fn create_history_row(entry: &history::HistoryEntry) -> Box {
+    let row_box = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(2)
+        .margin_start(4)
+        .margin_end(4)
+        .margin_top(4)
+        .margin_bottom(4)
+        .build();
+
+    let started = chrono::DateTime::from_timestamp(entry.started, 0)
+        .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default();
+    let duration = entry
+        .ended
+        .map(|ended| recording::format_elapsed(Duration::from_secs((ended - entry.started).max(0) as u64)))
+        .unwrap_or_else(|| "--:--:--".to_string());
+
+    let path = std::path::Path::new(entry.path());
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| entry.path().to_string());
+
+    // The file may have been moved or deleted since
+    let file_state = if let Ok(metadata) = fs::metadata(path) {
+        format_file_size(metadata.len())
+    } else if entry.removed {
+        "deleted".to_string()
+    } else if entry.outcome == history::Outcome::Completed || entry.outcome == history::Outcome::Failed {
+        let size = entry.size.map(|size| format!(", was {}", format_file_size(size))).unwrap_or_default();
+        format!("missing{}", size)
+    } else {
+        "no file".to_string()
+    };
+
+    let title_label = Label::builder()
+        .label(format!(
+            "<b>{}</b>  {}",
+            glib::markup_escape_text(&started),
+            glib::markup_escape_text(entry.outcome.label())
+        ))
+        .use_markup(true)
+        .halign(gtk::Align::Start)
+        .build();
+    if matches!(entry.outcome, history::Outcome::Failed | history::Outcome::Crashed) {
+        title_label.add_css_class("error");
+    }
+
+    let details_label = Label::builder()
+        .label(format!(
+            "{} • {} • {} • {} • {}",
+            name, duration, file_state, entry.region, entry.audio_source
+        ))
+        .tooltip_text(format!("{}\nPreset: {}", entry.path(), entry.preset))
+        .halign(gtk::Align::Start)
+        .ellipsize(gtk::pango::EllipsizeMode::Middle)
+        .build();
+    details_label.add_css_class("dim-label");
+
+    row_box.append(&title_label);
+    row_box.append(&details_label);
+    row_box
+}
+
+fn create_storage_section(current_path: &Rc<RefCell<String>>) -> gtk::Expander {
+    let expander = gtk::Expander::builder()
+        .label("Storage")
+        .margin_top(8)
+        .build();
+
+    let content_box = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(4)
+        .margin_top(8)
+        .build();
+
+    let summary_label = Label::builder()
+        .halign(gtk::Align::Start)
+        .build();
+    content_box.append(&summary_label);
+
+    let months_label = Label::builder()
+        .halign(gtk::Align::Start)
+        .build();
+    months_label.add_css_class("dim-label");
+    months_label.add_css_class("monospace");
+    content_box.append(&months_label);
+
+    let refresh_usage = {
+        let current_path_clone = current_path.clone();
+        let summary_label_clone = summary_label.clone();
+        let months_label_clone = months_label.clone();
+
+        Rc::new(move || {
+            let files = retention::scan(&current_path_clone.borrow());
+            let total: u64 = files.iter().map(|file| file.size).sum();
+            summary_label_clone.set_text(&format!(
+                "{} recordings, {} in total",
+                files.len(),
+                format_file_size(total)
+            ));
+
+            let months: Vec<String> = retention::usage_by_month(&files)
+                .iter()
+                .map(|usage| format!("{}   {:>4} files   {:>10}", usage.month, usage.count, format_file_size(usage.size)))
+                .collect();
+            months_label_clone.set_text(&months.join("\n"));
+            months_label_clone.set_visible(!months.is_empty());
+        })
+    };
+
+    {
+        let refresh_usage_clone = refresh_usage.clone();
+        expander.connect_expanded_notify(move |expander| {
+            if expander.is_expanded() {
+                refresh_usage_clone();
+            }
+        });
+    }
+
+    // Retention policy
+    let policy = retention::Policy::load();
+
+    let retention_title = Label::builder()
+        .label("<span weight='bold'>Retention Policy</span>")
+        .use_markup(true)
+        .halign(gtk::Align::Start)
+        .margin_top(12)
+        .build();
+    content_box.append(&retention_title);
+
+    let retention_description = Label::builder()
+        .label("Remove old recordings automatically on startup and after each recording. Pinned recordings and the latest recording are always kept.")
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .build();
+    retention_description.add_css_class("dim-label");
+    content_box.append(&retention_description);
+
+    let enabled_switch = create_switch();
+    enabled_switch.set_active(policy.enabled);
+
+    let age_adj = Adjustment::new(policy.max_age_days as f64, 0.0, 3650.0, 1.0, 7.0, 0.0);
+    let age_spin = SpinButton::new(Some(&age_adj), 1.0, 0);
+    age_spin.set_tooltip_text(Some("0 keeps recordings regardless of age"));
+
+    let size_adj = Adjustment::new(policy.max_size_gb as f64, 0.0, 100000.0, 1.0, 10.0, 0.0);
+    let size_spin = SpinButton::new(Some(&size_adj), 1.0, 0);
+    size_spin.set_tooltip_text(Some("0 disables the size quota"));
+
+    let action_labels: Vec<&str> = retention::RemovalAction::ALL.iter().map(|action| action.label()).collect();
+    let action_dropdown = gtk::DropDown::from_strings(&action_labels);
+    action_dropdown.set_selected(
+        retention::RemovalAction::ALL
+            .iter()
+            .position(|action| *action == policy.action)
+            .unwrap_or(0) as u32,
+    );
+
+    let save_policy = {
+        let enabled_switch = enabled_switch.clone();
+        let age_spin = age_spin.clone();
+        let size_spin = size_spin.clone();
+        let action_dropdown = action_dropdown.clone();
+
+        Rc::new(move || {
+            let policy = retention::Policy {
+                enabled: enabled_switch.is_active(),
+                max_age_days: age_spin.value_as_int(),
+                max_size_gb: size_spin.value_as_int(),
+                action: retention::RemovalAction::ALL
+                    .get(action_dropdown.selected() as usize)
+                    .copied()
+                    .unwrap_or(retention::RemovalAction::Trash),
+            };
+            // A changed policy may remove different files, so preview it again
+            if policy != retention::Policy::load() {
+                retention::set_confirmed(false);
+            }
+            policy.save();
+        })
+    };
+
+    {
+        let save_policy = save_policy.clone();
+        enabled_switch.connect_active_notify(move |_| save_policy());
+    }
+    {
+        let save_policy = save_policy.clone();
+        age_spin.connect_value_changed(move |_| save_policy());
+    }
+    {
+        let save_policy = save_policy.clone();
+        size_spin.connect_value_changed(move |_| save_policy());
+    }
+    {
+        let save_policy = save_policy.clone();
+        action_dropdown.connect_selected_notify(move |_| save_policy());
+    }
+
+    content_box.append(&create_setting_row("Enable retention policy", enabled_switch));
+    content_box.append(&create_setting_row("Remove recordings older than (days)", age_spin));
+    content_box.append(&create_setting_row("Keep total size below (GB)", size_spin));
+    content_box.append(&create_setting_row("Removed recordings are", action_dropdown));
+
+    let apply_btn = Button::builder()
+        .label("Preview and Apply Now")
+        .halign(gtk::Align::Start)
+        .margin_top(8)
+        .build();
+
+    {
+        let current_path_clone = current_path.clone();
+        let refresh_usage_clone = refresh_usage.clone();
+        apply_btn.connect_clicked(move |_| {
             let recording_dir = current_path_clone.borrow().clone();
-            stop_recording(&recording_dir, &status_label_clone);
-            *is_recording_clone.borrow_mut() = false;
-            start_btn_clone.set_sensitive(true);
-            stop_btn_clone.set_sensitive(false);
+            let policy = retention::Policy::load();
+            let files = retention::plan(&recording_dir, &policy);
+
+            if files.is_empty() {
+                recording::report_status("Retention policy: nothing to remove");
+                return;
+            }
+
+            let refresh_usage = refresh_usage_clone.clone();
+            show_retention_preview(files, policy.action, move || refresh_usage());
         });
     }
+    content_box.append(&apply_btn);
+
+    expander.set_child(Some(&content_box));
+    expander
+}
+
+// Apply the retention policy, asking for confirmation the first time
+fn run_retention_policy(recording_dir: &str) {
+    let policy = retention::Policy::load();
+    let files = retention::plan(recording_dir, &policy);
+    if files.is_empty() {
+        return;
+    }
+
+    if retention::is_confirmed() {
+        let removed = retention::remove(&files, policy.action);
+        println!("Retention policy removed {} recordings", removed);
+    } else {
+        show_retention_preview(files, policy.action, || {});
+    }
+}
+
+// List what the policy will remove and let the user confirm it
+fn show_retention_preview<F: Fn() + 'static>(files: Vec<retention::RecordingFile>, action: retention::RemovalAction, on_removed: F) {
+    let total: u64 = files.iter().map(|file| file.size).sum();
+    let names: Vec<String> = files
+        .iter()
+        .map(|file| {
+            let name = file.path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            format!("{} ({})", name, format_file_size(file.size))
+        })
+        .collect();
+
+    let parent = gio::Application::default()
+        .and_downcast::<Application>()
+        .and_then(|app| app.active_window());
+
+    let dialog = gtk::AlertDialog::builder()
+        .message(format!("Remove {} recordings ({})?", files.len(), format_file_size(total)))
+        .detail(format!(
+            "The retention policy will {} these recordings:\n\n{}",
+            action.label().to_lowercase(),
+            names.join("\n")
+        ))
+        .buttons(["Cancel", "Remove"])
+        .cancel_button(0)
+        .default_button(0)
+        .modal(true)
+        .build();
+
+    dialog.choose(parent.as_ref(), gio::Cancellable::NONE, move |response| {
+        if response == Ok(1) {
+            retention::set_confirmed(true);
+            let removed = retention::remove(&files, action);
+            recording::report_status(&format!("Retention policy removed {} recordings", removed));
+            on_removed();
+        }
+    });
+}
+
+fn create_notification_settings_section() -> gtk::Expander {
+    let expander = gtk::Expander::builder()
+        .label("Notification Settings")
+        .margin_top(8)
+        .build();
+
+    let content_box = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(4)
+        .margin_top(8)
+        .build();
+
+    let description = Label::builder()
+        .label("Choose which events show a notification, for how long (ms, 0 keeps it until dismissed) and how urgent it is")
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .build();
+    description.add_css_class("dim-label");
+    content_box.append(&description);
 
-    // Connect open folder button
-    {
-        let current_path_clone = current_path.clone();
-        open_folder_btn.connect_clicked(move |_| {
-            let recording_dir = current_path_clone.borrow().clone();
-            open_recordings_folder(&recording_dir);
-        });
-    }
+    let urgency_labels: Vec<&str> = Urgency::ALL.iter().map(|urgency| urgency.label()).collect();
 
-    buttons_box.append(&start_btn);
-    buttons_box.append(&stop_btn);
-    buttons_box.append(&open_folder_btn);
+    for event in NotifyEvent::ALL {
+        let settings = notifications::event_settings(event);
 
-    section_box.append(&buttons_box);
+        let enabled_switch = create_switch();
+        enabled_switch.set_active(settings.enabled);
+        enabled_switch.set_valign(gtk::Align::Center);
 
-    let file_list_section = create_file_list_section(&current_path);
-    section_box.append(&file_list_section);
+        let timeout_adj = Adjustment::new(settings.timeout_ms as f64, 0.0, 60000.0, 500.0, 1000.0, 0.0);
+        let timeout_spin = SpinButton::new(Some(&timeout_adj), 500.0, 0);
+        timeout_spin.set_tooltip_text(Some("Timeout in milliseconds"));
 
-    section_box
+        let urgency_dropdown = gtk::DropDown::from_strings(&urgency_labels);
+        let urgency_index = Urgency::ALL.iter().position(|urgency| *urgency == settings.urgency).unwrap_or(1);
+        urgency_dropdown.set_selected(urgency_index as u32);
+        urgency_dropdown.set_tooltip_text(Some("Urgency"));
+
+        let controls_box = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .build();
+        controls_box.append(&timeout_spin);
+        controls_box.append(&urgency_dropdown);
+        controls_box.append(&enabled_switch);
+
+        // Save whenever any of the controls change
+        let save_settings = {
+            let enabled_switch = enabled_switch.clone();
+            let timeout_spin = timeout_spin.clone();
+            let urgency_dropdown = urgency_dropdown.clone();
+
+            Rc::new(move || {
+                let urgency = Urgency::ALL
+                    .get(urgency_dropdown.selected() as usize)
+                    .copied()
+                    .unwrap_or(Urgency::Normal);
+
+                let settings = notifications::EventSettings {
+                    enabled: enabled_switch.is_active(),
+                    timeout_ms: timeout_spin.value_as_int(),
+                    urgency,
+                };
+                notifications::save_event_settings(event, &settings);
+            })
+        };
+
+        {
+            let save_settings = save_settings.clone();
+            enabled_switch.connect_active_notify(move |_| save_settings());
+        }
+        {
+            let save_settings = save_settings.clone();
+            connect_spin_debounced(&timeout_spin, move |_| save_settings());
+        }
+        urgency_dropdown.connect_selected_notify(move |_| save_settings());
+
+        let row = create_setting_row(event.label(), controls_box);
+        content_box.append(&row);
+    }
+
+    expander.set_child(Some(&content_box));
+    expander
 }
 
 fn create_file_list_section(current_path: &Rc<RefCell<String>>) -> gtk::Expander {
@@ -510,6 +1777,10 @@ fn create_file_list_section(current_path: &Rc<RefCell<String>>) -> gtk::Expander
         .halign(gtk::Align::Start)
         .build();
 
+    // Browse the selected library or all of them
+    let scope_dropdown = gtk::DropDown::from_strings(&["Selected library", "All libraries"]);
+    scope_dropdown.set_tooltip_text(Some("Which libraries to list"));
+
     let refresh_btn = Button::builder()
         .icon_name("view-refresh-symbolic")
         .tooltip_text("Refresh file list")
@@ -518,6 +1789,7 @@ fn create_file_list_section(current_path: &Rc<RefCell<String>>) -> gtk::Expander
     header_content.append(&header_icon);
     header_content.append(&files_label);
     header_box.append(&header_content);
+    header_box.append(&scope_dropdown);
     header_box.append(&refresh_btn);
     content_box.append(&header_box);
 
@@ -535,19 +1807,35 @@ fn create_file_list_section(current_path: &Rc<RefCell<String>>) -> gtk::Expander
     scrolled.set_child(Some(&files_listbox));
     content_box.append(&scrolled);
 
+    // Merge several recordings into one
+    let merge_panel = MergePanel::new(current_path);
+    content_box.append(merge_panel.widget());
+
+    // In-app preview player
+    let preview_player = PreviewPlayer::new();
+    content_box.append(preview_player.widget());
+
     // Function to refresh file list
     let refresh_files = {
         let current_path_clone = current_path.clone();
         let files_listbox_clone = files_listbox.clone();
+        let preview_player_clone = preview_player.clone();
+        let scope_dropdown_clone = scope_dropdown.clone();
         
         Rc::new(move || {
+            // Drop the preview if its file was deleted
+            preview_player_clone.forget_if_missing();
+
             // Clear existing items
             while let Some(child) = files_listbox_clone.first_child() {
                 files_listbox_clone.remove(&child);
             }
 
-            let recording_dir = current_path_clone.borrow().clone();
-            let files = list_recording_files(&recording_dir);
+            let files = if scope_dropdown_clone.selected() == 1 {
+                list_all_library_files()
+            } else {
+                list_recording_files(&current_path_clone.borrow())
+            };
 
             if files.is_empty() {
                 // Show empty state
@@ -556,7 +1844,7 @@ fn create_file_list_section(current_path: &Rc<RefCell<String>>) -> gtk::Expander
             } else {
                 // Add file rows with refresh callback (we'll pass a dummy callback for now)
                 for file_info in files {
-                    let file_row = create_file_row(file_info, &recording_dir);
+                    let file_row = create_file_row(file_info, &preview_player_clone);
                     files_listbox_clone.append(&file_row);
                 }
             }
@@ -571,6 +1859,13 @@ fn create_file_list_section(current_path: &Rc<RefCell<String>>) -> gtk::Expander
         });
     }
 
+    {
+        let refresh_files_clone = refresh_files.clone();
+        scope_dropdown.connect_selected_notify(move |_| {
+            refresh_files_clone();
+        });
+    }
+
     // Auto-refresh when expander is opened
     {
         let refresh_files_clone = refresh_files.clone();
@@ -604,6 +1899,9 @@ fn create_file_list_section(current_path: &Rc<RefCell<String>>) -> gtk::Expander
 #[derive(Debug, Clone)]
 struct FileInfo {
     name: String,
+    dir: String,
+    // Set when browsing all libraries
+    library: Option<String>,
     size: String,
     modified: String,
 }
@@ -621,6 +1919,11 @@ fn list_recording_files(recording_dir: &str) -> Vec<FileInfo> {
                 if metadata.is_file() {
                     let file_name = entry.file_name().to_string_lossy().to_string();
                     
+                    // Skip hidden temporaries such as a chapter rewrite in progress
+                    if file_name.starts_with('.') {
+                        continue;
+                    }
+
                     // Only include video files
                     if file_name.ends_with(".mp4") || 
                        file_name.ends_with(".mkv") || 
@@ -632,6 +1935,8 @@ fn list_recording_files(recording_dir: &str) -> Vec<FileInfo> {
                         
                         files.push(FileInfo {
                             name: file_name,
+                            dir: recording_dir.to_string(),
+                            library: None,
                             size,
                             modified,
                         });
@@ -646,8 +1951,31 @@ fn list_recording_files(recording_dir: &str) -> Vec<FileInfo> {
     files
 }
 
+// List recording files of every library, newest first
+fn list_all_library_files() -> Vec<FileInfo> {
+    let mut seen_dirs: Vec<String> = Vec::new();
+    let mut files = Vec::new();
+
+    for library in libraries::all() {
+        // Two libraries may point at the same directory
+        if seen_dirs.contains(&library.path) {
+            continue;
+        }
+        seen_dirs.push(library.path.clone());
+
+        files.extend(list_recording_files(&library.path).into_iter().map(|file_info| FileInfo {
+            library: Some(library.name.clone()),
+            ..file_info
+        }));
+    }
+
+    files.sort_by(|a, b| b.modified.cmp(&a.modified));
+    files
+}
+
 // Create a file row widget (keeping original implementation)
-fn create_file_row(file_info: FileInfo, recording_dir: &str) -> Box {
+fn create_file_row(file_info: FileInfo, preview_player: &PreviewPlayer) -> Box {
+    let recording_dir = file_info.dir.as_str();
     let row_box = Box::builder()
         .orientation(Orientation::Horizontal)
         .spacing(8)
@@ -684,8 +2012,23 @@ fn create_file_row(file_info: FileInfo, recording_dir: &str) -> Box {
         .ellipsize(gtk::pango::EllipsizeMode::End)
         .build();
 
+    let file_path = std::path::PathBuf::from(format!("{}/{}", recording_dir, file_info.name));
+    let tags = retention::tags(&file_path);
+
+    let details = match &file_info.library {
+        Some(library) => format!("{} • {} • {}", library, file_info.size, file_info.modified),
+        None => format!("{} • {}", file_info.size, file_info.modified),
+    };
+    let details_with_tags = move |tags: &[String]| {
+        if tags.is_empty() {
+            details.clone()
+        } else {
+            format!("{} • {}", details, tags.join(", "))
+        }
+    };
+
     let details_label = Label::builder()
-        .label(&format!("{} • {}", file_info.size, file_info.modified))
+        .label(details_with_tags(&tags))
         .halign(gtk::Align::Start)
         .build();
     
@@ -702,7 +2045,30 @@ fn create_file_row(file_info: FileInfo, recording_dir: &str) -> Box {
 
     let play_btn = Button::builder()
         .icon_name("media-playback-start-symbolic")
-        .tooltip_text("Play video")
+        .tooltip_text("Preview video")
+        .build();
+
+    let pin_btn = gtk::ToggleButton::builder()
+        .icon_name("view-pin-symbolic")
+        .tooltip_text("Pin (never removed by the retention policy)")
+        .active(retention::is_pinned(&file_path))
+        .build();
+
+    // Tags, edited in a popover
+    let tags_entry = Entry::builder()
+        .text(tags.join(", "))
+        .placeholder_text("Comma-separated tags")
+        .width_chars(24)
+        .build();
+
+    let tags_popover = gtk::Popover::builder()
+        .child(&tags_entry)
+        .build();
+
+    let tags_btn = gtk::MenuButton::builder()
+        .icon_name("bookmark-new-symbolic")
+        .tooltip_text("Tags (tagged recordings are never removed by the retention policy)")
+        .popover(&tags_popover)
         .build();
 
     let delete_btn = Button::builder()
@@ -710,13 +2076,106 @@ fn create_file_row(file_info: FileInfo, recording_dir: &str) -> Box {
         .tooltip_text("Delete file")
         .build();
 
-    // Connect play button
+    // Copy menu
+    let copy_popover_box = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(4)
+        .build();
+
+    let copy_file_btn = Button::builder()
+        .label("Copy file")
+        .tooltip_text("Paste as an attachment in file managers, browsers and chat apps")
+        .build();
+    copy_file_btn.add_css_class("flat");
+
+    let copy_path_btn = Button::builder()
+        .label("Copy path")
+        .build();
+    copy_path_btn.add_css_class("flat");
+
+    copy_popover_box.append(&copy_file_btn);
+    copy_popover_box.append(&copy_path_btn);
+
+    let copy_popover = gtk::Popover::builder()
+        .child(&copy_popover_box)
+        .build();
+
+    let copy_btn = gtk::MenuButton::builder()
+        .icon_name("edit-copy-symbolic")
+        .tooltip_text("Copy to clipboard")
+        .popover(&copy_popover)
+        .build();
+
+    {
+        let file_path = file_path.clone();
+        let copy_popover = copy_popover.clone();
+        copy_file_btn.connect_clicked(move |_| {
+            copy_popover.popdown();
+            match clipboard::copy_file(&file_path) {
+                Ok(()) => recording::report_status(&format!("Copied {}", file_path.display())),
+                Err(e) => eprintln!("Failed to copy file: {}", e),
+            }
+        });
+    }
+
+    {
+        let file_path = file_path.clone();
+        let copy_popover = copy_popover.clone();
+        copy_path_btn.connect_clicked(move |_| {
+            copy_popover.popdown();
+            match clipboard::copy_text(&file_path.to_string_lossy()) {
+                Ok(()) => recording::report_status(&format!("Copied path of {}", file_path.display())),
+                Err(e) => eprintln!("Failed to copy path: {}", e),
+            }
+        });
+    }
+
+    // Drag the file out as a text/uri-list
+    let drag_source = gtk::DragSource::builder()
+        .actions(gtk::gdk::DragAction::COPY)
+        .build();
+    {
+        let file_path = file_path.clone();
+        drag_source.connect_prepare(move |_, _, _| {
+            let files = gtk::gdk::FileList::from_array(&[gio::File::for_path(&file_path)]);
+            Some(gtk::gdk::ContentProvider::for_value(&files.to_value()))
+        });
+    }
+    {
+        let file_icon = file_icon.clone();
+        drag_source.connect_drag_begin(move |source, _| {
+            source.set_icon(Some(&gtk::WidgetPaintable::new(Some(&file_icon))), 0, 0);
+        });
+    }
+    row_box.add_controller(drag_source);
+
+    // Connect play button to the in-app preview
     {
         let file_path = format!("{}/{}", recording_dir, file_info.name);
+        let preview_player_clone = preview_player.clone();
         play_btn.connect_clicked(move |_| {
-            let _ = std::process::Command::new("xdg-open")
-                .arg(&file_path)
-                .spawn();
+            preview_player_clone.load(&file_path);
+        });
+    }
+
+    // Connect pin button
+    {
+        let file_path = file_path.clone();
+        pin_btn.connect_toggled(move |button| {
+            retention::set_pinned(&file_path, button.is_active());
+        });
+    }
+
+    // Save tags when the entry is committed and show them in the details
+    {
+        let details_label = details_label.clone();
+        connect_entry_commit(&tags_entry, move |entry| {
+            let tags = retention::parse_tags(&entry.text());
+            if tags == retention::tags(&file_path) {
+                return;
+            }
+            retention::set_tags(&file_path, &tags);
+            details_label.set_text(&details_with_tags(&tags));
         });
     }
 
@@ -730,6 +2189,9 @@ fn create_file_row(file_info: FileInfo, recording_dir: &str) -> Box {
     }
 
     actions_box.append(&play_btn);
+    actions_box.append(&pin_btn);
+    actions_box.append(&tags_btn);
+    actions_box.append(&copy_btn);
     actions_box.append(&delete_btn);
 
     row_box.append(&file_icon);
@@ -818,6 +2280,8 @@ fn show_delete_confirmation(file_path: &str, file_name: &str) {
             // User confirmed deletion
             match fs::remove_file(file_path) {
                 Ok(_) => {
+                    let _ = fs::remove_file(markers::sidecar_file(std::path::Path::new(file_path)));
+                    history::record_removed(std::path::Path::new(file_path));
                     println!("Deleted file: {} (Auto-refresh will update list within 3 seconds)", file_path);
                 },
                 Err(e) => {
@@ -871,9 +2335,7 @@ fn create_about_section() -> Box {
         .build();
     
     dotfiles_btn.connect_clicked(|_| {
-        let _ = Command::new("xdg-open")
-            .arg("https://github.com/UmmItOS/ummit-dots")
-            .spawn();
+        apps::open_uri("https://github.com/UmmItOS/ummit-dots");
     });
 
     links_box.append(&dotfiles_btn);
@@ -899,25 +2361,200 @@ fn create_system_section() -> Box {
 
     // Theme setting
     let theme_switch = create_switch();
-    theme_switch.set_sensitive(false);
-    theme_switch.set_tooltip_text(Some("This feature is not yet implemented"));
+    theme_switch.set_active(theme::is_dark());
+    theme_switch.set_tooltip_text(Some("Sets the GNOME colour scheme and the GTK 3/4 settings.ini files"));
+    theme_switch.connect_active_notify(|switch| {
+        let dark = switch.is_active();
+        if let Err(e) = theme::set_dark(dark) {
+            eprintln!("Failed to apply colour scheme: {}", e);
+        }
+
+        hooks::run(
+            HookEvent::SettingsApplied,
+            &[
+                ("UMMIT_SETTING", "dark-theme".to_string()),
+                ("UMMIT_VALUE", dark.to_string()),
+            ],
+        );
+    });
     let theme_row = create_setting_row("Dark Theme", theme_switch);
     section_box.append(&theme_row);
 
+    // Where the Hyprland options below are set, for their tooltips
+    let hypr_config = hyprconf::Config::load_default()
+        .map_err(|e| eprintln!("Failed to read Hyprland config: {}", e))
+        .ok();
+    let defined_in = |option: &str| -> String {
+        match hypr_config.as_ref().and_then(|config| config.get(option)) {
+            Some(entry) => format!("Set in {}:{}", entry.location.file.display(), entry.location.line),
+            None => "Not set in the Hyprland config (default)".to_string(),
+        }
+    };
+
     // Animations setting
     let animations_switch = create_switch();
-    animations_switch.set_sensitive(false);
-    animations_switch.set_tooltip_text(Some("This feature is not yet implemented"));
+    match hyprland::get_bool(hyprland::ANIMATIONS_ENABLED) {
+        Some(enabled) => {
+            animations_switch.set_active(enabled);
+            animations_switch.set_tooltip_text(Some(&defined_in(hyprland::ANIMATIONS_ENABLED)));
+        }
+        None => {
+            animations_switch.set_sensitive(false);
+            animations_switch.set_tooltip_text(Some("Hyprland is not running"));
+        }
+    }
+    // Set while a reverted value is put back, so that is not applied again
+    let restoring = Rc::new(Cell::new(false));
+    animations_switch.connect_active_notify(move |switch| {
+        if restoring.get() {
+            return;
+        }
+        let value = if switch.is_active() { "1" } else { "0" };
+        let switch_clone = switch.clone();
+        let restoring = restoring.clone();
+        apply_hyprland_option(switch, hyprland::ANIMATIONS_ENABLED, value, move |previous| {
+            restoring.set(true);
+            switch_clone.set_active(previous != "0");
+            restoring.set(false);
+        });
+    });
     let animations_row = create_setting_row("Enable Animations", animations_switch);
     section_box.append(&animations_row);
 
-    // Transparency setting
-    let transparency_adj = Adjustment::new(0.8, 0.0, 1.0, 0.1, 0.1, 0.0);
-    let transparency_spin = SpinButton::new(Some(&transparency_adj), 0.1, 2);
-    transparency_spin.set_sensitive(false);
-    transparency_spin.set_tooltip_text(Some("This feature is not yet implemented"));
-    let transparency_row = create_setting_row("Window Transparency", transparency_spin);
-    section_box.append(&transparency_row);
+    // Opacity settings
+    for (label, option) in [
+        ("Active Window Opacity", hyprland::ACTIVE_OPACITY),
+        ("Inactive Window Opacity", hyprland::INACTIVE_OPACITY),
+    ] {
+        let opacity_adj = Adjustment::new(1.0, 0.0, 1.0, 0.05, 0.1, 0.0);
+        let opacity_spin = SpinButton::new(Some(&opacity_adj), 0.05, 2);
+        match hyprland::get_float(option) {
+            Some(value) => {
+                opacity_spin.set_value(value);
+                opacity_spin.set_tooltip_text(Some(&defined_in(option)));
+            }
+            None => {
+                opacity_spin.set_sensitive(false);
+                opacity_spin.set_tooltip_text(Some("Hyprland is not running"));
+            }
+        }
+        // The value in effect, so a settled spin that lands back on it (e.g.
+        // after a revert) is not applied again
+        let applied = Rc::new(Cell::new(opacity_spin.value()));
+        // Apply once the value settles, so stepping through low opacities
+        // asks for confirmation only for the final one
+        connect_spin_debounced(&opacity_spin, move |spin| {
+            let value = format!("{:.2}", spin.value());
+            if value == format!("{:.2}", applied.get()) {
+                return;
+            }
+            applied.set(spin.value());
+
+            let spin_clone = spin.clone();
+            let applied = applied.clone();
+            apply_hyprland_option(spin, option, &value, move |previous| {
+                if let Ok(previous) = previous.parse() {
+                    applied.set(previous);
+                    spin_clone.set_value(previous);
+                }
+            });
+        });
+        let opacity_row = create_setting_row(label, opacity_spin);
+        section_box.append(&opacity_row);
+    }
+
+    section_box.append(&create_preferred_apps_section());
+
+    section_box
+}
+
+// Apply a Hyprland option live and persist it in the managed include file.
+// Risky values must be confirmed first; `on_reverted` gets the old value back.
+fn apply_hyprland_option<W, F>(widget: &W, option: &str, value: &str, on_reverted: F)
+where
+    W: IsA<gtk::Widget>,
+    F: Fn(&str) + 'static,
+{
+    let run_hook = |option: &str, value: &str| {
+        hooks::run(
+            HookEvent::SettingsApplied,
+            &[
+                ("UMMIT_SETTING", option.to_string()),
+                ("UMMIT_VALUE", value.to_string()),
+            ],
+        );
+    };
+
+    if !revert::is_risky(option, value) {
+        match hyprland_include::apply(option, value) {
+            Ok(()) => run_hook(option, value),
+            Err(e) => eprintln!("{}", e),
+        }
+        return;
+    }
+
+    let Some(change) = revert::Change::new(option, value) else {
+        eprintln!("Cannot read {}, not applying {} without a way back", option, value);
+        return;
+    };
+
+    let parent = widget
+        .root()
+        .and_then(|root| root.downcast::<gtk::Window>().ok());
+    let previous = change.previous.clone();
+    let (option, value) = (option.to_string(), value.to_string());
+    revert::apply_with_confirmation(parent.as_ref(), vec![change], move |kept| {
+        if kept {
+            run_hook(&option, &value);
+        } else {
+            on_reverted(&previous);
+        }
+    });
+}
+
+fn create_preferred_apps_section() -> Box {
+    let section_box = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(8)
+        .build();
+
+    let section_title = Label::builder()
+        .label("<span weight='bold'>Preferred Applications</span>")
+        .use_markup(true)
+        .halign(gtk::Align::Start)
+        .margin_top(12)
+        .build();
+    
+    section_box.append(&section_title);
+
+    for role in apps::AppRole::ALL {
+        let candidates = apps::candidates(role);
+        let ids: Vec<String> = candidates
+            .iter()
+            .map(|app_info| app_info.id().map(|id| id.to_string()).unwrap_or_default())
+            .collect();
+
+        let mut names = vec!["System default".to_string()];
+        names.extend(candidates.iter().map(|app_info| app_info.display_name().to_string()));
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+
+        let dropdown = gtk::DropDown::from_strings(&names);
+        let preferred = apps::preferred_id(role);
+        if let Some(index) = ids.iter().position(|id| *id == preferred) {
+            dropdown.set_selected(index as u32 + 1);
+        }
+
+        dropdown.connect_selected_notify(move |dropdown| {
+            let id = match dropdown.selected() {
+                0 => "",
+                index => ids.get(index as usize - 1).map(String::as_str).unwrap_or_default(),
+            };
+            apps::set_preferred_id(role, id);
+        });
+
+        let row = create_setting_row(role.label(), dropdown);
+        section_box.append(&row);
+    }
 
     section_box
 }
@@ -942,164 +2579,345 @@ fn create_setting_row<W: IsA<gtk::Widget>>(label_text: &str, widget: W) -> Box {
     row_box
 }
 
+// Save a text setting on Enter or when the entry loses focus, rather than
+// rewriting the config file on every keystroke
+fn connect_entry_commit<F: Fn(&Entry) + 'static>(entry: &Entry, commit: F) {
+    let commit = Rc::new(commit);
+
+    {
+        let commit = commit.clone();
+        entry.connect_activate(move |entry| commit(entry));
+    }
+
+    let focus = gtk::EventControllerFocus::new();
+    focus.connect_leave(move |controller| {
+        if let Some(entry) = controller.widget().and_downcast::<Entry>() {
+            commit(&entry);
+        }
+    });
+    entry.add_controller(focus);
+}
+
+// Commit a spin button value once it has stopped changing, so holding an
+// arrow key or scrolling does not act on every intermediate step
+fn connect_spin_debounced<F: Fn(&SpinButton) + 'static>(spin: &SpinButton, commit: F) {
+    let commit = Rc::new(commit);
+    let pending: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
+
+    spin.connect_value_changed(move |spin| {
+        if let Some(source) = pending.borrow_mut().take() {
+            source.remove();
+        }
+
+        let commit = commit.clone();
+        let spin = spin.clone();
+        let pending_inner = pending.clone();
+        let source = glib::timeout_add_local_once(std::time::Duration::from_millis(400), move || {
+            pending_inner.borrow_mut().take();
+            commit(&spin);
+        });
+        *pending.borrow_mut() = Some(source);
+    });
+}
+
 fn create_switch() -> Switch {
     Switch::builder()
         .halign(gtk::Align::End)
         .build()
 }
 
-fn start_recording(recording_dir: &str, status_label: &Label) -> bool {
-    println!("Starting wf-recorder...");
-    
-    // Check if wf-recorder is already running
-    let check_running = Command::new("pidof")
-        .arg("wf-recorder")
-        .output();
+fn start_recording(recording_dir: &str) {
+    if PICKING_REGION.get() {
+        recording::report_status("Region selection already in progress");
+        return;
+    }
+
+    let backend = recorder::selected_backend();
+    let capabilities = backend.capabilities();
+    println!("Starting {}...", backend.name());
     
-    if let Ok(output) = check_running {
-        let pid_str = String::from_utf8_lossy(&output.stdout);
-        if !pid_str.trim().is_empty() {
-            println!("wf-recorder is already running with PID: {}", pid_str.trim());
-            status_label.set_text("Error: wf-recorder is already running");
-            return false;
-        }
+    // Check if a recorder is already running
+    if let Some(running) = recorder::any_running() {
+        println!("{} is already running", running);
+        recording::report_status(&format!("Error: {} is already running", running));
+        return;
     }
     
     // Create recording directory if it doesn't exist
     if let Err(e) = fs::create_dir_all(recording_dir) {
         println!("Error creating directory {}: {}", recording_dir, e);
-        status_label.set_text("Error: Failed to create recording directory");
-        return false;
+        recording::report_status("Error: Failed to create recording directory");
+        return;
+    }
+
+    if capabilities.region && recorder::select_region() {
+        pick_region_then_record(recording_dir);
+    } else {
+        launch_recording(recording_dir, None);
+    }
+}
+
+// Run slurp off the main thread with our windows hidden, then start the
+// recorder once a region has been chosen
+fn pick_region_then_record(recording_dir: &str) {
+    PICKING_REGION.set(true);
+
+    let windows: Vec<gtk::Window> = gio::Application::default()
+        .and_downcast::<Application>()
+        .map(|app| app.windows().into_iter().filter(|window| window.is_visible()).collect())
+        .unwrap_or_default();
+    for window in &windows {
+        window.set_visible(false);
+    }
+
+    let result: Arc<Mutex<Option<Option<String>>>> = Arc::new(Mutex::new(None));
+    {
+        let result = result.clone();
+        thread::spawn(move || {
+            thread::sleep(REGION_HIDE_DELAY);
+            *result.lock().unwrap() = Some(recorder::pick_region());
+        });
     }
 
+    let recording_dir = recording_dir.to_string();
+    glib::timeout_add_local(Duration::from_millis(100), move || {
+        let Some(region) = result.lock().unwrap().take() else {
+            return glib::ControlFlow::Continue;
+        };
+
+        PICKING_REGION.set(false);
+        for window in &windows {
+            window.present();
+        }
+
+        match region {
+            Some(region) => launch_recording(&recording_dir, Some(region)),
+            None => recording::report_status("Region selection cancelled"),
+        }
+        glib::ControlFlow::Break
+    });
+}
+
+fn launch_recording(recording_dir: &str, region: Option<String>) {
+    let backend = recorder::selected_backend();
+    let capabilities = backend.capabilities();
+
+    let options = recorder::RecordingOptions {
+        preset: recorder::selected_preset(),
+        audio: capabilities.audio && recorder::record_audio(),
+        region,
+    };
+
     // Generate filename with current timestamp
     let timestamp = chrono::Local::now().format("%Y-%m-%d-%H-%M-%S");
-    let filename = format!("{}/wf-recorder-{}.mp4", recording_dir, timestamp);
+    let filename = format!("{}/{}-{}.mp4", recording_dir, backend.name(), timestamp);
 
-    // Start wf-recorder
-    let result = Command::new("wf-recorder")
-        .arg("-a")
-        .arg("--file")
-        .arg(&filename)
+    // Start the recorder
+    let result = Command::new(backend.name())
+        .args(backend.build_args(&options, &filename))
         .spawn();
 
     match result {
-        Ok(_) => {
-            // Update status after successful start
-            status_label.set_text(&format!("Recording to: {}", filename));
-            
-            // Send notification via hyprctl
-            let _ = Command::new("hyprctl")
-                .arg("notify")
-                .arg("1")
-                .arg("5000")
-                .arg("rgb(00FF00)")
-                .arg("fontsize:35   Video recording started with wf-recorder 📹")
-                .spawn();
+        Ok(child) => {
+            // Track the session (this also updates the status label and tray icon)
+            recording::begin(child, &filename, recording_dir, backend.name(), options.preset.key());
+
+            if let Some(session) = recording::current() {
+                history::record_started(
+                    &session,
+                    options.preset.key(),
+                    if options.audio { "default audio" } else { "none" },
+                    options.region.as_deref().unwrap_or("Full screen"),
+                );
+                hooks::run(HookEvent::RecordingStarted, &recording_hook_env(&session));
+            }
             
-            true
+            notifications::notify(
+                NotifyEvent::RecordingStarted,
+                "Recording started",
+                &format!("Video recording started with {} 📹", backend.name()),
+                &[],
+            );
         }
         Err(e) => {
-            println!("Failed to start wf-recorder: {}", e);
-            status_label.set_text("Error: Failed to start wf-recorder");
-            false
+            println!("Failed to start {}: {}", backend.name(), e);
+            recording::report_status(&format!("Error: Failed to start {}", backend.name()));
+
+            notifications::notify(
+                NotifyEvent::RecordingFailed,
+                "Recording failed",
+                &format!("Failed to start {}: {}", backend.name(), e),
+                &[],
+            );
+
+            hooks::run(
+                HookEvent::RecordingFailed,
+                &[
+                    ("UMMIT_RECORDING_DIR", recording_dir.to_string()),
+                    ("UMMIT_ERROR", format!("Failed to start {}: {}", backend.name(), e)),
+                ],
+            );
         }
     }
 }
 
-fn stop_recording(recording_dir: &str, status_label: &Label) {
-    println!("Stopping wf-recorder...");
+fn stop_recording(recording_dir: &str) {
+    let backend = recording::current()
+        .and_then(|session| recorder::backend_by_name(&session.backend))
+        .unwrap_or_else(recorder::selected_backend);
+    println!("Stopping {}...", backend.name());
     
-    // Find wf-recorder process and send SIGINT
-    let output = Command::new("pidof")
-        .arg("wf-recorder")
-        .output();
-
-    match output {
-        Ok(output) => {
-            let pid_str = String::from_utf8_lossy(&output.stdout);
-            let pid_str = pid_str.trim();
-            
-            if !pid_str.is_empty() {
-                println!("Sending SIGINT to wf-recorder PID: {}", pid_str);
-                let _ = Command::new("kill")
-                    .arg("-SIGINT")
-                    .arg(pid_str)
-                    .spawn();
-
-                // Get most recent file
-                if let Ok(entries) = fs::read_dir(recording_dir) {
-                    let mut files: Vec<_> = entries
-                        .filter_map(|entry| entry.ok())
-                        .filter(|entry| {
-                            entry.path().extension()
-                                .and_then(|ext| ext.to_str())
-                                .map(|ext| ext == "mp4")
-                                .unwrap_or(false)
-                        })
-                        .collect();
-                    
-                    files.sort_by_key(|entry| {
-                        entry.metadata()
-                            .and_then(|m| m.modified())
-                            .unwrap_or(std::time::SystemTime::UNIX_EPOCH)
-                    });
-                    
-                    if let Some(latest_file) = files.last() {
-                        let filename = latest_file.file_name();
-                        let filename_str = filename.to_string_lossy();
-                        
-                        status_label.set_text(&format!("Saved: {}", filename_str));
-                        
-                        // Send notification
-                        let notification_text = format!("fontsize:35   Video recording ended and saved to: {}/{} 📹", recording_dir, filename_str);
-                        let _ = Command::new("hyprctl")
-                            .arg("notify")
-                            .arg("5")
-                            .arg("5000")
-                            .arg("rgb(00FF00)")
-                            .arg(&notification_text)
-                            .spawn();
-                    } else {
-                        status_label.set_text("Recording stopped");
-                    }
-                } else {
-                    status_label.set_text("Recording stopped");
+    // Find the recorder process and ask it to finish the file
+    let pids = backend.running_pids();
+    if pids.is_empty() {
+        println!("{} is not running", backend.name());
+        recording::end();
+        recording::report_status(&format!("{} is not running", backend.name()));
+        return;
+    }
+
+    // A paused (SIGSTOP'd) recorder must be resumed to handle SIGINT
+    recording::set_paused(false);
+
+    for pid in pids {
+        println!("Sending {} to {} PID: {}", backend.stop_signal(), backend.name(), pid);
+        let _ = Command::new("kill")
+            .arg(backend.stop_signal())
+            .arg(pid.to_string())
+            .spawn();
+    }
+
+    let session = recording::end();
+    if let Some(session) = session.as_ref() {
+        hooks::run(HookEvent::RecordingStopped, &recording_hook_env(session));
+
+        // Log the final size once the recorder has written the file
+        let session = session.clone();
+        let ended = chrono::Local::now().timestamp();
+        thread::spawn(move || {
+            recording::wait_for_exit(session.pid, Duration::from_secs(30));
+            history::record_finished(
+                &session,
+                ended,
+                history::Outcome::Completed,
+                std::path::Path::new(&session.file_path),
+            );
+        });
+    }
+
+    // The session knows its file; a recorder started elsewhere only leaves
+    // the most recent file to go by
+    let saved_file = session
+        .as_ref()
+        .map(|session| std::path::PathBuf::from(&session.file_path))
+        .or_else(|| latest_recording(recording_dir));
+
+    if let Some(saved_file) = saved_file {
+        let filename_str = saved_file
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        recording::report_status(&format!("Saved: {}", filename_str));
+
+        let file_path = saved_file.to_string_lossy().to_string();
+
+        let mut env = vec![
+            ("UMMIT_FILE", file_path.clone()),
+            ("UMMIT_RECORDING_DIR", recording_dir.to_string()),
+        ];
+        if let Some(session) = session.as_ref() {
+            env.push(("UMMIT_DURATION", session.elapsed().as_secs().to_string()));
+            env.push(("UMMIT_PRESET", session.preset.clone()));
+        }
+
+        // The file is only complete once the recorder has exited
+        let finished = session
+            .as_ref()
+            .map(|session| (session.pid, session.file_path.clone(), session.elapsed()));
+        let saved_path = file_path.clone();
+        thread::spawn(move || {
+            if let Some((pid, video_path, duration)) = finished {
+                recording::wait_for_exit(pid, Duration::from_secs(30));
+
+                if markers::embed_chapters_enabled()
+                    && let Err(e) = markers::embed_chapters(std::path::Path::new(&video_path), duration)
+                {
+                    eprintln!("Failed to embed chapters: {}", e);
                 }
-            } else {
-                println!("wf-recorder is not running");
-                status_label.set_text("wf-recorder is not running");
             }
-        }
-        Err(e) => {
-            println!("Error finding wf-recorder process: {}", e);
-            status_label.set_text("Error: Could not find wf-recorder process");
-        }
+            if recorder::copy_on_save()
+                && let Err(e) = clipboard::copy_file(std::path::Path::new(&saved_path))
+            {
+                eprintln!("Failed to copy recording to clipboard: {}", e);
+            }
+            hooks::run(HookEvent::FileSaved, &env);
+        });
+
+        notifications::notify(
+            NotifyEvent::RecordingSaved,
+            "Recording saved",
+            &format!("Video recording ended and saved to: {} 📹", file_path),
+            &[
+                NotifyAction::OpenFile(file_path.clone()),
+                NotifyAction::OpenFolder(recording_dir.to_string()),
+            ],
+        );
+    } else {
+        recording::report_status("Recording stopped");
     }
+
+    run_retention_policy(recording_dir);
+}
+
+// Most recently modified recording in a directory
+fn latest_recording(recording_dir: &str) -> Option<std::path::PathBuf> {
+    fs::read_dir(recording_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| recorder::is_video_file(&entry.path()) && !entry.file_name().to_string_lossy().starts_with('.'))
+        .max_by_key(|entry| {
+            entry.metadata()
+                .and_then(|m| m.modified())
+                .unwrap_or(std::time::SystemTime::UNIX_EPOCH)
+        })
+        .map(|entry| entry.path())
 }
 
 fn apply_directory_path(path: &str, current_path: &Rc<RefCell<String>>, button: &Button) {
     println!("Applying directory path: {}", path);
     
-    // First validate the path
-    match fs::create_dir_all(path) {
+    // First validate the path, then create it
+    match paths::check_directory(path).and_then(|_| fs::create_dir_all(path).map_err(|e| e.to_string())) {
         Ok(_) => {
-            // Path is valid, apply it
+            // Path is valid, apply it and remember it for next time
             *current_path.borrow_mut() = path.to_string();
+            if let Err(e) = libraries::save(&libraries::Library {
+                name: libraries::selected().name,
+                path: path.to_string(),
+            }) {
+                eprintln!("Failed to save library: {}", e);
+            }
+
+            hooks::run(
+                HookEvent::SettingsApplied,
+                &[
+                    ("UMMIT_SETTING", "recording-directory".to_string()),
+                    ("UMMIT_VALUE", path.to_string()),
+                ],
+            );
             
             button.set_icon_name("emblem-ok-symbolic");
             button.set_tooltip_text(Some("Directory path applied successfully"));
             button.add_css_class("success");
             button.remove_css_class("destructive-action");
             
-            // Send success notification
-            let _ = Command::new("hyprctl")
-                .arg("notify")
-                .arg("2")
-                .arg("3000")
-                .arg("rgb(00FF00)")
-                .arg(&format!("fontsize:35 Applied recording directory: {}", path))
-                .spawn();
+            notifications::notify(
+                NotifyEvent::DirectoryApplied,
+                "Recording directory applied",
+                &format!("Applied recording directory: {}", path),
+                &[NotifyAction::OpenFolder(path.to_string())],
+            );
                 
             println!("Recording directory applied: {}", path);
         }
@@ -1111,38 +2929,46 @@ fn apply_directory_path(path: &str, current_path: &Rc<RefCell<String>>, button:
             button.remove_css_class("success");
             button.add_css_class("destructive-action");
             
-            // Send error notification
-            let _ = Command::new("hyprctl")
-                .arg("notify")
-                .arg("0")
-                .arg("3000")
-                .arg("rgb(FF0000)")
-                .arg(&format!("fontsize:35 Cannot apply invalid path: {}", e))
-                .spawn();
+            notifications::notify(
+                NotifyEvent::DirectoryInvalid,
+                "Invalid recording directory",
+                &format!("Cannot apply invalid path: {}", e),
+                &[],
+            );
         }
     }
 }
 
 fn validate_directory_path(path: &str, button: &Button) {
+    let path = paths::expand(path);
     println!("Validating directory path: {}", path);
     
-    // Check if path is valid and can be created
-    match fs::create_dir_all(path) {
-        Ok(_) => {
-            // Path is valid and directory exists/was created
-            button.set_icon_name("emblem-ok-symbolic");
-            button.set_tooltip_text(Some("Directory path is valid"));
+    // Check the path without creating anything; Apply creates it
+    match paths::check_directory(&path) {
+        Ok(check) => {
+            let mut message = if check.exists {
+                format!("Directory is valid: {}", path)
+            } else {
+                format!("Directory will be created on Apply: {}", path)
+            };
+            if let Some(free) = check.free_bytes {
+                message.push_str(&format!("\n{} free", format_file_size(free)));
+            }
+            if check.is_low_on_space() {
+                message.push_str(" (low on space)");
+            }
+
+            button.set_icon_name(if check.is_low_on_space() { "dialog-warning-symbolic" } else { "emblem-ok-symbolic" });
+            button.set_tooltip_text(Some(&message));
             button.add_css_class("suggested-action");
             button.remove_css_class("destructive-action");
             
-            // Send success notification
-            let _ = Command::new("hyprctl")
-                .arg("notify")
-                .arg("2")
-                .arg("3000")
-                .arg("rgb(00FF00)")
-                .arg(&format!("fontsize:35 Directory validated: {}", path))
-                .spawn();
+            notifications::notify(
+                NotifyEvent::DirectoryValidated,
+                "Directory validated",
+                &message,
+                &[],
+            );
         }
         Err(e) => {
             // Path is invalid
@@ -1152,14 +2978,12 @@ fn validate_directory_path(path: &str, button: &Button) {
             button.remove_css_class("suggested-action");
             button.add_css_class("destructive-action");
             
-            // Send error notification
-            let _ = Command::new("hyprctl")
-                .arg("notify")
-                .arg("0")
-                .arg("3000")
-                .arg("rgb(FF0000)")
-                .arg(&format!("Invalid directory path: {}", e))
-                .spawn();
+            notifications::notify(
+                NotifyEvent::DirectoryInvalid,
+                "Invalid recording directory",
+                &format!("Invalid directory path: {}", e),
+                &[],
+            );
         }
     }
 }
@@ -1173,42 +2997,5 @@ fn open_recordings_folder(recording_dir: &str) {
         return;
     }
     
-    let result = Command::new("xdg-open")
-        .arg(recording_dir)
-        .status();
-    
-    if result.is_err() {
-        println!("xdg-open failed, trying nautilus...");
-        let result = Command::new("nautilus")
-            .arg(recording_dir)
-            .status();
-        
-        if result.is_err() {
-            println!("nautilus failed, trying thunar...");
-            let result = Command::new("thunar")
-                .arg(recording_dir)
-                .status();
-            
-            if result.is_err() {
-                println!("thunar failed, trying dolphin...");
-                let result = Command::new("dolphin")
-                    .arg(recording_dir)
-                    .status();
-                
-                if result.is_err() {
-                    println!("All file managers failed. Opening terminal in directory...");
-                    let _ = Command::new("kitty")
-                        .arg("--directory")
-                        .arg(recording_dir)
-                        .spawn()
-                        .or_else(|_| {
-                            Command::new("gnome-terminal")
-                                .arg("--working-directory")
-                                .arg(recording_dir)
-                                .spawn()
-                        });
-                }
-            }
-        }
-    }
-}
\ No newline at end of file
+    apps::open(std::path::Path::new(recording_dir));
+}
@@ -0,0 +1,180 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use crate::config;
+use crate::recording;
+
+// Markers dropped during a recording. They are stored next to the video in a
+// sidecar file (one "seconds<TAB>title" line each) and can be embedded as
+// chapters with ffmpeg once the recording is finished.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Marker {
+    pub offset: Duration,
+    pub title: String,
+}
+
+pub fn sidecar_file(video_path: &Path) -> PathBuf {
+    let mut path = video_path.as_os_str().to_owned();
+    path.push(".markers");
+    PathBuf::from(path)
+}
+
+pub fn embed_chapters_enabled() -> bool {
+    config::get_bool("Recording", "embed-chapters", false)
+}
+
+pub fn set_embed_chapters_enabled(enabled: bool) {
+    config::set_bool("Recording", "embed-chapters", enabled);
+}
+
+// Add a marker at the current position of the active recording
+pub fn add_marker() -> Option<Marker> {
+    let session = recording::current()?;
+    let path = sidecar_file(Path::new(&session.file_path));
+
+    let marker = Marker {
+        offset: session.elapsed(),
+        title: format!("Marker {}", load(Path::new(&session.file_path)).len() + 1),
+    };
+
+    let line = format!("{:.3}\t{}\n", marker.offset.as_secs_f64(), marker.title);
+    let written = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(line.as_bytes()));
+
+    match written {
+        Ok(()) => {
+            recording::report_status(&format!(
+                "{} at {}: {}",
+                marker.title,
+                recording::format_elapsed(marker.offset),
+                session.file_path
+            ));
+            Some(marker)
+        }
+        Err(e) => {
+            eprintln!("Failed to write marker to {}: {}", path.display(), e);
+            recording::report_status("Error: Could not save marker");
+            None
+        }
+    }
+}
+
+// Markers saved for a recording, in order
+pub fn load(video_path: &Path) -> Vec<Marker> {
+    let Ok(contents) = fs::read_to_string(sidecar_file(video_path)) else {
+        return Vec::new();
+    };
+
+    let mut markers: Vec<Marker> = contents
+        .lines()
+        .filter_map(|line| {
+            let (seconds, title) = line.split_once('\t')?;
+            let seconds = seconds.trim().parse::<f64>().ok().filter(|seconds| *seconds >= 0.0)?;
+            Some(Marker {
+                offset: Duration::from_secs_f64(seconds),
+                title: title.trim().to_string(),
+            })
+        })
+        .collect();
+
+    markers.sort_by_key(|marker| marker.offset);
+    markers
+}
+
+// Rewrite the video with its markers as chapters. Blocking; run it off the UI
+// thread once the recorder has exited.
+pub fn embed_chapters(video_path: &Path, duration: Duration) -> Result<(), String> {
+    let markers = load(video_path);
+    if markers.is_empty() {
+        return Ok(());
+    }
+
+    let file_name = video_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let metadata_path = video_path.with_file_name(format!(".{}.ffmetadata", file_name));
+    // Same container as the source, so the rename below keeps the file
+    // matching its extension
+    let stem = video_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let output_path = match video_path.extension() {
+        Some(extension) => video_path.with_file_name(format!(".{}.chapters.{}", stem, extension.to_string_lossy())),
+        None => video_path.with_file_name(format!(".{}.chapters.mp4", stem)),
+    };
+
+    fs::write(&metadata_path, ffmetadata(&markers, duration))
+        .map_err(|e| format!("Failed to write {}: {}", metadata_path.display(), e))?;
+
+    let result = Command::new("ffmpeg")
+        .args(["-y", "-loglevel", "error", "-i"])
+        .arg(video_path)
+        .arg("-i")
+        .arg(&metadata_path)
+        .args(["-map", "0", "-map_metadata", "1", "-map_chapters", "1", "-codec", "copy"])
+        .arg(&output_path)
+        .output();
+
+    let _ = fs::remove_file(&metadata_path);
+
+    let output = result.map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+    if !output.status.success() {
+        let _ = fs::remove_file(&output_path);
+        return Err(format!(
+            "ffmpeg failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    fs::rename(&output_path, video_path)
+        .map_err(|e| format!("Failed to replace {}: {}", video_path.display(), e))?;
+
+    println!("Embedded {} chapters into {}", markers.len(), video_path.display());
+    Ok(())
+}
+
+// Chapters in ffmpeg's metadata format: each marker runs until the next one
+fn ffmetadata(markers: &[Marker], duration: Duration) -> String {
+    let mut chapters = Vec::new();
+    if markers.first().map(|marker| !marker.offset.is_zero()).unwrap_or(false) {
+        chapters.push((Duration::ZERO, "Start".to_string()));
+    }
+    chapters.extend(markers.iter().map(|marker| (marker.offset, marker.title.clone())));
+
+    let mut metadata = String::from(";FFMETADATA1\n");
+    for (index, (start, title)) in chapters.iter().enumerate() {
+        let end = chapters
+            .get(index + 1)
+            .map(|(next, _)| *next)
+            .unwrap_or(duration)
+            .max(*start);
+
+        metadata.push_str(&format!(
+            "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+            start.as_millis(),
+            end.as_millis(),
+            escape_metadata(title)
+        ));
+    }
+    metadata
+}
+
+fn escape_metadata(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
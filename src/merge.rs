@@ -0,0 +1,679 @@
+use gtk::prelude::*;
+use gtk::{glib, Box, Button, CheckButton, Label, Orientation};
+use std::cell::RefCell;
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use crate::json::{self, JsonValue};
+use crate::notifications::{self, NotifyAction, NotifyEvent};
+use crate::recorder::{self, Preset};
+
+// Merge several recordings into one video. Inputs with matching streams are
+// joined losslessly with ffmpeg's concat demuxer; anything else is
+// transcoded through a preset. The job runs on a background thread and the
+// panel below the file list shows its progress.
+
+// Stream layout of an input, as reported by ffprobe
+#[derive(Debug, Clone, PartialEq)]
+struct SourceInfo {
+    video_codec: String,
+    width: i64,
+    height: i64,
+    frame_rate: String,
+    pix_fmt: String,
+    audio_codec: Option<String>,
+    sample_rate: Option<String>,
+    channels: Option<i64>,
+    duration: f64,
+}
+
+fn probe(path: &Path) -> Result<SourceInfo, String> {
+    let output = Command::new("ffprobe")
+        .args([
+            "-v",
+            "error",
+            "-show_entries",
+            "stream=codec_type,codec_name,width,height,r_frame_rate,pix_fmt,sample_rate,channels:format=duration",
+            "-of",
+            "json",
+        ])
+        .arg(path)
+        .output()
+        .map_err(|e| format!("Failed to run ffprobe: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "ffprobe failed for {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let info = json::parse(&String::from_utf8_lossy(&output.stdout))
+        .map_err(|e| format!("Invalid ffprobe output: {}", e))?;
+    let streams = info.get("streams").and_then(JsonValue::as_array).unwrap_or(&[]);
+    let stream_of = |kind: &str| streams.iter().find(|stream| stream.str_field("codec_type") == Some(kind));
+
+    let video = stream_of("video").ok_or_else(|| format!("{} has no video stream", path.display()))?;
+    let audio = stream_of("audio");
+
+    Ok(SourceInfo {
+        video_codec: video.str_field("codec_name").unwrap_or_default().to_string(),
+        width: video.i64_field("width").unwrap_or(0),
+        height: video.i64_field("height").unwrap_or(0),
+        frame_rate: video.str_field("r_frame_rate").unwrap_or_default().to_string(),
+        pix_fmt: video.str_field("pix_fmt").unwrap_or_default().to_string(),
+        audio_codec: audio.and_then(|audio| audio.str_field("codec_name")).map(str::to_string),
+        sample_rate: audio.and_then(|audio| audio.str_field("sample_rate")).map(str::to_string),
+        channels: audio.and_then(|audio| audio.i64_field("channels")),
+        duration: info
+            .get("format")
+            .and_then(|format| format.str_field("duration"))
+            .and_then(|duration| duration.parse().ok())
+            .unwrap_or(0.0),
+    })
+}
+
+// The concat demuxer copies packets as they are, so every stream parameter
+// has to match or the joined file plays back wrong
+fn can_concat_losslessly(sources: &[SourceInfo]) -> bool {
+    sources.windows(2).all(|pair| {
+        pair[0].video_codec == pair[1].video_codec
+            && pair[0].width == pair[1].width
+            && pair[0].height == pair[1].height
+            && pair[0].frame_rate == pair[1].frame_rate
+            && pair[0].pix_fmt == pair[1].pix_fmt
+            && pair[0].audio_codec == pair[1].audio_codec
+            && pair[0].sample_rate == pair[1].sample_rate
+            && pair[0].channels == pair[1].channels
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum JobState {
+    Probing,
+    Running { fraction: f64, lossless: bool },
+    Finished(Result<PathBuf, String>),
+}
+
+#[derive(Clone)]
+struct MergeJob {
+    state: Arc<Mutex<JobState>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl MergeJob {
+    fn start(inputs: Vec<PathBuf>, output: PathBuf, preset: Preset) -> Self {
+        let job = MergeJob {
+            state: Arc::new(Mutex::new(JobState::Probing)),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        };
+
+        let job_clone = job.clone();
+        thread::spawn(move || {
+            let result = job_clone.run(&inputs, &output, preset);
+            if result.is_err() {
+                let _ = fs::remove_file(&output);
+            }
+            job_clone.set_state(JobState::Finished(result.map(|_| output)));
+        });
+
+        job
+    }
+
+    fn state(&self) -> JobState {
+        self.state.lock().map(|state| state.clone()).unwrap_or(JobState::Probing)
+    }
+
+    fn set_state(&self, new_state: JobState) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = new_state;
+        }
+    }
+
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    fn run(&self, inputs: &[PathBuf], output: &Path, preset: Preset) -> Result<(), String> {
+        let sources = inputs.iter().map(|input| probe(input)).collect::<Result<Vec<_>, _>>()?;
+        let total_duration: f64 = sources.iter().map(|source| source.duration).sum();
+        let lossless = can_concat_losslessly(&sources);
+
+        self.set_state(JobState::Running { fraction: 0.0, lossless });
+
+        if lossless {
+            let list_path = output.with_extension("concat.txt");
+            let list: String = inputs
+                .iter()
+                .map(|input| format!("file '{}'\n", input.to_string_lossy().replace('\'', "'\\''")))
+                .collect();
+            fs::write(&list_path, list).map_err(|e| format!("Failed to write {}: {}", list_path.display(), e))?;
+
+            let mut command = Command::new("ffmpeg");
+            command
+                .args(["-f", "concat", "-safe", "0", "-i"])
+                .arg(&list_path)
+                .args(["-map", "0", "-c", "copy"]);
+
+            let result = self.run_ffmpeg(command, output, total_duration, lossless);
+            let _ = fs::remove_file(&list_path);
+            result
+        } else {
+            self.run_ffmpeg(transcode_command(inputs, &sources, preset), output, total_duration, lossless)
+        }
+    }
+
+    // Run ffmpeg, following its -progress output until it exits
+    fn run_ffmpeg(&self, mut command: Command, output: &Path, total_duration: f64, lossless: bool) -> Result<(), String> {
+        let mut child = command
+            .args(["-y", "-loglevel", "error", "-nostats", "-progress", "pipe:1"])
+            .arg(output)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("Failed to run ffmpeg: {}", e))?;
+
+        let stderr_reader = child.stderr.take().map(|mut stderr| {
+            thread::spawn(move || {
+                let mut output = String::new();
+                let _ = stderr.read_to_string(&mut output);
+                output
+            })
+        });
+
+        if let Some(stdout) = child.stdout.take() {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if self.cancelled.load(Ordering::SeqCst) {
+                    let _ = child.kill();
+                    break;
+                }
+
+                // out_time_ms is in microseconds as well (historical name)
+                let out_time = line
+                    .strip_prefix("out_time_us=")
+                    .or_else(|| line.strip_prefix("out_time_ms="))
+                    .and_then(|value| value.trim().parse::<f64>().ok());
+
+                if let Some(micros) = out_time
+                    && total_duration > 0.0
+                {
+                    let fraction = (micros / 1_000_000.0 / total_duration).clamp(0.0, 1.0);
+                    self.set_state(JobState::Running { fraction, lossless });
+                }
+            }
+        }
+
+        let status = child.wait().map_err(|e| format!("Failed to wait for ffmpeg: {}", e))?;
+        let errors = stderr_reader.and_then(|reader| reader.join().ok()).unwrap_or_default();
+
+        if self.cancelled.load(Ordering::SeqCst) {
+            Err("Merge cancelled".to_string())
+        } else if status.success() {
+            Ok(())
+        } else {
+            Err(format!("ffmpeg failed: {}", errors.trim()))
+        }
+    }
+}
+
+// Re-encode through the concat filter, scaling everything to the first
+// input's size. Inputs without audio get silence when others have sound.
+fn transcode_command(inputs: &[PathBuf], sources: &[SourceInfo], preset: Preset) -> Command {
+    let mut command = Command::new("ffmpeg");
+    for input in inputs {
+        command.arg("-i").arg(input);
+    }
+
+    let width = sources[0].width.max(2);
+    let height = sources[0].height.max(2);
+    let with_audio = sources.iter().any(|source| source.audio_codec.is_some());
+
+    let mut filter = String::new();
+    let mut concat_inputs = String::new();
+    let mut silence_inputs = inputs.len();
+
+    for (index, source) in sources.iter().enumerate() {
+        filter.push_str(&format!(
+            "[{index}:v]scale={width}:{height}:force_original_aspect_ratio=decrease,pad={width}:{height}:(ow-iw)/2:(oh-ih)/2,setsar=1[v{index}];"
+        ));
+        concat_inputs.push_str(&format!("[v{}]", index));
+
+        if with_audio {
+            let audio_input = if source.audio_codec.is_some() {
+                index
+            } else {
+                command
+                    .args(["-f", "lavfi", "-t", &format!("{:.3}", source.duration), "-i"])
+                    .arg("anullsrc=channel_layout=stereo:sample_rate=48000");
+                silence_inputs += 1;
+                silence_inputs - 1
+            };
+
+            filter.push_str(&format!(
+                "[{audio_input}:a]aresample=48000,aformat=sample_fmts=fltp:channel_layouts=stereo[a{index}];"
+            ));
+            concat_inputs.push_str(&format!("[a{}]", index));
+        }
+    }
+
+    filter.push_str(&format!(
+        "{}concat=n={}:v=1:a={}[v]{}",
+        concat_inputs,
+        sources.len(),
+        if with_audio { 1 } else { 0 },
+        if with_audio { "[a]" } else { "" }
+    ));
+
+    command.args(["-filter_complex", &filter, "-map", "[v]"]);
+    if with_audio {
+        command.args(["-map", "[a]", "-c:a", "aac", "-b:a", "160k"]);
+    }
+    command.args(preset.ffmpeg_args()).args(["-pix_fmt", "yuv420p"]);
+    command
+}
+
+// Recordings in a directory, oldest first (the order they were taken)
+fn list_recordings(recording_dir: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(recording_dir) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<(SystemTime, PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| recorder::is_video_file(path) && !file_name(path).starts_with('.'))
+        .map(|path| {
+            let modified = fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            (modified, path)
+        })
+        .collect();
+
+    files.sort();
+    files.into_iter().map(|(_, path)| path).collect()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+// Redraws the merge dialog's list after reordering
+type RebuildSlot = Rc<RefCell<Option<Rc<dyn Fn()>>>>;
+
+// "Merge recordings" button plus the progress of a running merge job
+#[derive(Clone)]
+pub struct MergePanel {
+    container: Box,
+    merge_btn: Button,
+    progress_box: Box,
+    progress_bar: gtk::ProgressBar,
+    job: Rc<RefCell<Option<MergeJob>>>,
+}
+
+impl MergePanel {
+    pub fn new(current_path: &Rc<RefCell<String>>) -> Self {
+        let container = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(4)
+            .margin_top(4)
+            .build();
+
+        let merge_btn = Button::builder()
+            .label("Merge Recordings…")
+            .tooltip_text("Join several recordings into one video")
+            .halign(gtk::Align::Start)
+            .build();
+
+        let progress_box = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .visible(false)
+            .build();
+
+        let progress_bar = gtk::ProgressBar::builder()
+            .show_text(true)
+            .hexpand(true)
+            .valign(gtk::Align::Center)
+            .build();
+
+        let cancel_btn = Button::builder()
+            .icon_name("process-stop-symbolic")
+            .tooltip_text("Cancel merge")
+            .build();
+
+        progress_box.append(&progress_bar);
+        progress_box.append(&cancel_btn);
+        container.append(&merge_btn);
+        container.append(&progress_box);
+
+        let panel = Self {
+            container,
+            merge_btn,
+            progress_box,
+            progress_bar,
+            job: Rc::new(RefCell::new(None)),
+        };
+
+        {
+            let panel_clone = panel.clone();
+            let current_path_clone = current_path.clone();
+            panel.merge_btn.connect_clicked(move |button| {
+                let recording_dir = current_path_clone.borrow().clone();
+                panel_clone.show_dialog(button, &recording_dir);
+            });
+        }
+
+        {
+            let panel_clone = panel.clone();
+            cancel_btn.connect_clicked(move |_| {
+                if let Some(job) = panel_clone.job.borrow().as_ref() {
+                    job.cancel();
+                }
+            });
+        }
+
+        panel
+    }
+
+    pub fn widget(&self) -> &Box {
+        &self.container
+    }
+
+    fn start(&self, inputs: Vec<PathBuf>, output: PathBuf, preset: Preset) {
+        println!("Merging {} recordings into {}", inputs.len(), output.display());
+
+        let job = MergeJob::start(inputs, output, preset);
+        *self.job.borrow_mut() = Some(job.clone());
+
+        self.merge_btn.set_sensitive(false);
+        self.progress_box.set_visible(true);
+        self.progress_bar.set_fraction(0.0);
+        self.progress_bar.set_text(Some("Inspecting recordings…"));
+
+        let panel = self.clone();
+        glib::timeout_add_local(Duration::from_millis(250), move || match job.state() {
+            JobState::Probing => glib::ControlFlow::Continue,
+            JobState::Running { fraction, lossless } => {
+                let mode = if lossless { "Joining" } else { "Transcoding" };
+                panel.progress_bar.set_fraction(fraction);
+                panel.progress_bar.set_text(Some(&format!("{}… {:.0}%", mode, fraction * 100.0)));
+                glib::ControlFlow::Continue
+            }
+            JobState::Finished(result) => {
+                panel.finish(result);
+                glib::ControlFlow::Break
+            }
+        });
+    }
+
+    fn finish(&self, result: Result<PathBuf, String>) {
+        *self.job.borrow_mut() = None;
+        self.merge_btn.set_sensitive(true);
+        self.progress_box.set_visible(false);
+
+        match result {
+            Ok(output) => {
+                let output = output.to_string_lossy().to_string();
+                println!("Merge finished: {}", output);
+                notifications::notify(
+                    NotifyEvent::MergeFinished,
+                    "Merge finished",
+                    &format!("Recordings merged into: {}", output),
+                    &[NotifyAction::OpenFile(output.clone())],
+                );
+            }
+            Err(e) => {
+                eprintln!("Merge failed: {}", e);
+                notifications::notify(NotifyEvent::MergeFailed, "Merge failed", &e, &[]);
+            }
+        }
+    }
+
+    // Pick and order the recordings to merge
+    fn show_dialog(&self, widget: &impl IsA<gtk::Widget>, recording_dir: &str) {
+        let parent = widget
+            .root()
+            .and_then(|root| root.downcast::<gtk::Window>().ok());
+
+        let dialog = gtk::Window::builder()
+            .title("Merge Recordings")
+            .modal(true)
+            .default_width(480)
+            .default_height(420)
+            .build();
+        dialog.set_transient_for(parent.as_ref());
+
+        let content_box = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(8)
+            .margin_top(12)
+            .margin_bottom(12)
+            .margin_start(12)
+            .margin_end(12)
+            .build();
+
+        let description = Label::builder()
+            .label("Select the recordings to merge and put them in order:")
+            .halign(gtk::Align::Start)
+            .wrap(true)
+            .build();
+        content_box.append(&description);
+
+        // (path, selected) in merge order
+        let entries: Rc<RefCell<Vec<(PathBuf, bool)>>> = Rc::new(RefCell::new(
+            list_recordings(recording_dir).into_iter().map(|path| (path, false)).collect(),
+        ));
+
+        let listbox = gtk::ListBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .build();
+
+        let scrolled = gtk::ScrolledWindow::builder()
+            .vexpand(true)
+            .hscrollbar_policy(gtk::PolicyType::Never)
+            .child(&listbox)
+            .build();
+        content_box.append(&scrolled);
+
+        let rebuild: RebuildSlot = Rc::new(RefCell::new(None));
+        {
+            let entries_clone = entries.clone();
+            let listbox_clone = listbox.clone();
+            let rebuild_clone = rebuild.clone();
+
+            *rebuild.borrow_mut() = Some(Rc::new(move || {
+                while let Some(child) = listbox_clone.first_child() {
+                    listbox_clone.remove(&child);
+                }
+
+                let count = entries_clone.borrow().len();
+                for (index, (path, selected)) in entries_clone.borrow().iter().enumerate() {
+                    let row = Box::builder()
+                        .orientation(Orientation::Horizontal)
+                        .spacing(4)
+                        .margin_top(2)
+                        .margin_bottom(2)
+                        .build();
+
+                    let check = CheckButton::builder()
+                        .label(file_name(path))
+                        .active(*selected)
+                        .hexpand(true)
+                        .build();
+
+                    let up_btn = Button::builder()
+                        .icon_name("go-up-symbolic")
+                        .tooltip_text("Move up")
+                        .sensitive(index > 0)
+                        .build();
+
+                    let down_btn = Button::builder()
+                        .icon_name("go-down-symbolic")
+                        .tooltip_text("Move down")
+                        .sensitive(index + 1 < count)
+                        .build();
+
+                    {
+                        let entries = entries_clone.clone();
+                        check.connect_toggled(move |check| {
+                            if let Some(entry) = entries.borrow_mut().get_mut(index) {
+                                entry.1 = check.is_active();
+                            }
+                        });
+                    }
+
+                    for (button, target) in [(&up_btn, index.wrapping_sub(1)), (&down_btn, index + 1)] {
+                        let entries = entries_clone.clone();
+                        let rebuild = rebuild_clone.clone();
+                        button.connect_clicked(move |_| {
+                            if target < entries.borrow().len() {
+                                entries.borrow_mut().swap(index, target);
+                                // Rebuild once this handler has returned
+                                let rebuild = rebuild.borrow().clone();
+                                glib::idle_add_local_once(move || {
+                                    if let Some(rebuild) = rebuild {
+                                        rebuild();
+                                    }
+                                });
+                            }
+                        });
+                    }
+
+                    row.append(&check);
+                    row.append(&up_btn);
+                    row.append(&down_btn);
+                    listbox_clone.append(&row);
+                }
+            }));
+        }
+
+        if let Some(rebuild) = rebuild.borrow().clone() {
+            rebuild();
+        }
+
+        // The rows hold the rebuild closure, which holds the rows: break the cycle
+        {
+            let rebuild = rebuild.clone();
+            dialog.connect_close_request(move |_| {
+                rebuild.borrow_mut().take();
+                glib::Propagation::Proceed
+            });
+        }
+
+        let output_entry = gtk::Entry::builder()
+            .text(format!("merged-{}.mp4", chrono::Local::now().format("%Y-%m-%d-%H%M%S")))
+            .hexpand(true)
+            .build();
+
+        let preset_labels: Vec<&str> = Preset::ALL.iter().map(|preset| preset.label()).collect();
+        let preset_dropdown = gtk::DropDown::from_strings(&preset_labels);
+        if let Some(index) = Preset::ALL.iter().position(|preset| *preset == recorder::selected_preset()) {
+            preset_dropdown.set_selected(index as u32);
+        }
+        preset_dropdown.set_tooltip_text(Some("Used when the recordings cannot be joined losslessly"));
+
+        for (label_text, widget) in [
+            ("Output file:", output_entry.clone().upcast::<gtk::Widget>()),
+            ("Transcode preset:", preset_dropdown.clone().upcast::<gtk::Widget>()),
+        ] {
+            let row = Box::builder()
+                .orientation(Orientation::Horizontal)
+                .spacing(8)
+                .build();
+            let label = Label::builder()
+                .label(label_text)
+                .halign(gtk::Align::Start)
+                .build();
+            row.append(&label);
+            row.append(&widget);
+            content_box.append(&row);
+        }
+
+        let error_label = Label::builder()
+            .halign(gtk::Align::Start)
+            .wrap(true)
+            .visible(false)
+            .build();
+        error_label.add_css_class("error");
+        content_box.append(&error_label);
+
+        let buttons_box = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .halign(gtk::Align::End)
+            .build();
+
+        let cancel_btn = Button::builder().label("Cancel").build();
+        let merge_btn = Button::builder().label("Merge").build();
+        merge_btn.add_css_class("suggested-action");
+
+        buttons_box.append(&cancel_btn);
+        buttons_box.append(&merge_btn);
+        content_box.append(&buttons_box);
+
+        {
+            let dialog_clone = dialog.clone();
+            cancel_btn.connect_clicked(move |_| dialog_clone.close());
+        }
+
+        {
+            let panel = self.clone();
+            let dialog_clone = dialog.clone();
+            let recording_dir = recording_dir.to_string();
+
+            merge_btn.connect_clicked(move |_| {
+                let inputs: Vec<PathBuf> = entries
+                    .borrow()
+                    .iter()
+                    .filter(|(_, selected)| *selected)
+                    .map(|(path, _)| path.clone())
+                    .collect();
+
+                let mut name = output_entry.text().trim().to_string();
+                if !name.ends_with(".mp4") {
+                    name.push_str(".mp4");
+                }
+                let output = Path::new(&recording_dir).join(&name);
+
+                let error = if inputs.len() < 2 {
+                    Some("Select at least two recordings".to_string())
+                } else if name.contains('/') || name == ".mp4" {
+                    Some("Enter a file name for the merged video".to_string())
+                } else if output.exists() {
+                    Some(format!("{} already exists", name))
+                } else {
+                    None
+                };
+
+                if let Some(error) = error {
+                    error_label.set_text(&error);
+                    error_label.set_visible(true);
+                    return;
+                }
+
+                let preset = Preset::ALL
+                    .get(preset_dropdown.selected() as usize)
+                    .copied()
+                    .unwrap_or(Preset::Default);
+
+                panel.start(inputs, output, preset);
+                dialog_clone.close();
+            });
+        }
+
+        dialog.set_child(Some(&content_box));
+        dialog.present();
+    }
+}
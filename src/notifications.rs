@@ -0,0 +1,378 @@
+use gtk::prelude::*;
+use gtk::{gio, glib};
+use std::cell::{OnceCell, RefCell};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+use crate::apps;
+use crate::config;
+
+// Notification service: sends org.freedesktop.Notifications over D-Bus and
+// falls back to `hyprctl notify` when no notification daemon answers
+
+const NOTIFICATIONS_BUS: &str = "org.freedesktop.Notifications";
+const NOTIFICATIONS_PATH: &str = "/org/freedesktop/Notifications";
+const CONFIG_GROUP_PREFIX: &str = "Notification ";
+
+// Events that can raise a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyEvent {
+    RecordingStarted,
+    RecordingSaved,
+    RecordingFailed,
+    DirectoryValidated,
+    DirectoryApplied,
+    DirectoryInvalid,
+    MergeFinished,
+    MergeFailed,
+    ScreenshotSaved,
+    ScreenshotFailed,
+}
+
+impl NotifyEvent {
+    pub const ALL: [NotifyEvent; 10] = [
+        NotifyEvent::RecordingStarted,
+        NotifyEvent::RecordingSaved,
+        NotifyEvent::RecordingFailed,
+        NotifyEvent::DirectoryValidated,
+        NotifyEvent::DirectoryApplied,
+        NotifyEvent::DirectoryInvalid,
+        NotifyEvent::MergeFinished,
+        NotifyEvent::MergeFailed,
+        NotifyEvent::ScreenshotSaved,
+        NotifyEvent::ScreenshotFailed,
+    ];
+
+    fn key(self) -> &'static str {
+        match self {
+            NotifyEvent::RecordingStarted => "recording-started",
+            NotifyEvent::RecordingSaved => "recording-saved",
+            NotifyEvent::RecordingFailed => "recording-failed",
+            NotifyEvent::DirectoryValidated => "directory-validated",
+            NotifyEvent::DirectoryApplied => "directory-applied",
+            NotifyEvent::DirectoryInvalid => "directory-invalid",
+            NotifyEvent::MergeFinished => "merge-finished",
+            NotifyEvent::MergeFailed => "merge-failed",
+            NotifyEvent::ScreenshotSaved => "screenshot-saved",
+            NotifyEvent::ScreenshotFailed => "screenshot-failed",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            NotifyEvent::RecordingStarted => "Recording started",
+            NotifyEvent::RecordingSaved => "Recording saved",
+            NotifyEvent::RecordingFailed => "Recording failed",
+            NotifyEvent::DirectoryValidated => "Directory validated",
+            NotifyEvent::DirectoryApplied => "Directory applied",
+            NotifyEvent::DirectoryInvalid => "Invalid directory",
+            NotifyEvent::MergeFinished => "Merge finished",
+            NotifyEvent::MergeFailed => "Merge failed",
+            NotifyEvent::ScreenshotSaved => "Screenshot saved",
+            NotifyEvent::ScreenshotFailed => "Screenshot failed",
+        }
+    }
+
+    fn icon_name(self) -> &'static str {
+        match self {
+            NotifyEvent::RecordingStarted => "media-record",
+            NotifyEvent::RecordingSaved | NotifyEvent::MergeFinished => "video-x-generic",
+            NotifyEvent::RecordingFailed
+            | NotifyEvent::MergeFailed
+            | NotifyEvent::ScreenshotFailed
+            | NotifyEvent::DirectoryInvalid => "dialog-error",
+            NotifyEvent::DirectoryValidated | NotifyEvent::DirectoryApplied => "folder-videos",
+            NotifyEvent::ScreenshotSaved => "image-x-generic",
+        }
+    }
+
+    // Icon id understood by `hyprctl notify` (0 warning, 1 info, 2 hint, 3 error, 5 ok)
+    fn hyprctl_icon(self) -> &'static str {
+        match self {
+            NotifyEvent::RecordingStarted => "1",
+            NotifyEvent::RecordingSaved | NotifyEvent::MergeFinished | NotifyEvent::ScreenshotSaved => "5",
+            NotifyEvent::RecordingFailed | NotifyEvent::MergeFailed | NotifyEvent::ScreenshotFailed => "3",
+            NotifyEvent::DirectoryValidated | NotifyEvent::DirectoryApplied => "2",
+            NotifyEvent::DirectoryInvalid => "0",
+        }
+    }
+
+    fn default_settings(self) -> EventSettings {
+        match self {
+            NotifyEvent::RecordingStarted | NotifyEvent::RecordingSaved | NotifyEvent::MergeFinished => EventSettings {
+                enabled: true,
+                timeout_ms: 5000,
+                urgency: Urgency::Normal,
+            },
+            NotifyEvent::RecordingFailed | NotifyEvent::MergeFailed => EventSettings {
+                enabled: true,
+                timeout_ms: 5000,
+                urgency: Urgency::Critical,
+            },
+            NotifyEvent::DirectoryValidated | NotifyEvent::DirectoryApplied | NotifyEvent::ScreenshotSaved => EventSettings {
+                enabled: true,
+                timeout_ms: 3000,
+                urgency: Urgency::Low,
+            },
+            NotifyEvent::DirectoryInvalid | NotifyEvent::ScreenshotFailed => EventSettings {
+                enabled: true,
+                timeout_ms: 3000,
+                urgency: Urgency::Critical,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Urgency {
+    Low,
+    Normal,
+    Critical,
+}
+
+impl Urgency {
+    pub const ALL: [Urgency; 3] = [Urgency::Low, Urgency::Normal, Urgency::Critical];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Urgency::Low => "Low",
+            Urgency::Normal => "Normal",
+            Urgency::Critical => "Critical",
+        }
+    }
+
+    fn from_label(label: &str) -> Option<Urgency> {
+        Urgency::ALL.into_iter().find(|urgency| urgency.label().eq_ignore_ascii_case(label))
+    }
+
+    // Value of the "urgency" hint in the notification spec
+    fn hint_value(self) -> u8 {
+        match self {
+            Urgency::Low => 0,
+            Urgency::Normal => 1,
+            Urgency::Critical => 2,
+        }
+    }
+
+    fn hyprctl_colour(self) -> &'static str {
+        match self {
+            Urgency::Critical => "rgb(FF0000)",
+            _ => "rgb(00FF00)",
+        }
+    }
+}
+
+// Per-event user configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventSettings {
+    pub enabled: bool,
+    pub timeout_ms: i32,
+    pub urgency: Urgency,
+}
+
+pub fn event_settings(event: NotifyEvent) -> EventSettings {
+    let defaults = event.default_settings();
+    let key_file = config::load();
+    let group = format!("{}{}", CONFIG_GROUP_PREFIX, event.key());
+
+    EventSettings {
+        enabled: key_file.boolean(&group, "enabled").unwrap_or(defaults.enabled),
+        timeout_ms: key_file.integer(&group, "timeout-ms").unwrap_or(defaults.timeout_ms),
+        urgency: key_file
+            .string(&group, "urgency")
+            .ok()
+            .and_then(|value| Urgency::from_label(&value))
+            .unwrap_or(defaults.urgency),
+    }
+}
+
+pub fn save_event_settings(event: NotifyEvent, settings: &EventSettings) {
+    let key_file = config::load();
+    let group = format!("{}{}", CONFIG_GROUP_PREFIX, event.key());
+
+    key_file.set_boolean(&group, "enabled", settings.enabled);
+    key_file.set_integer(&group, "timeout-ms", settings.timeout_ms);
+    key_file.set_string(&group, "urgency", settings.urgency.label());
+    config::save(&key_file);
+}
+
+// Actions attached to a notification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NotifyAction {
+    OpenFile(String),
+    OpenFolder(String),
+}
+
+impl NotifyAction {
+    fn key(&self) -> &'static str {
+        match self {
+            NotifyAction::OpenFile(_) => "open-file",
+            NotifyAction::OpenFolder(_) => "open-folder",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            NotifyAction::OpenFile(_) => "Open file",
+            NotifyAction::OpenFolder(_) => "Open folder",
+        }
+    }
+
+    fn run(&self) {
+        let path = match self {
+            NotifyAction::OpenFile(path) | NotifyAction::OpenFolder(path) => path,
+        };
+
+        apps::open(Path::new(path));
+    }
+}
+
+thread_local! {
+    static SESSION_BUS: OnceCell<Option<gio::DBusConnection>> = const { OnceCell::new() };
+    static PENDING_ACTIONS: RefCell<HashMap<u32, Vec<NotifyAction>>> = RefCell::new(HashMap::new());
+}
+
+// Session bus connection, subscribed once to the daemon's action signals
+fn session_bus() -> Option<gio::DBusConnection> {
+    SESSION_BUS.with(|cell| {
+        cell.get_or_init(|| {
+            let connection = match gio::bus_get_sync(gio::BusType::Session, None::<&gio::Cancellable>) {
+                Ok(connection) => connection,
+                Err(e) => {
+                    eprintln!("Session bus unavailable: {}", e);
+                    return None;
+                }
+            };
+
+            connection.signal_subscribe(
+                None,
+                Some(NOTIFICATIONS_BUS),
+                Some("ActionInvoked"),
+                Some(NOTIFICATIONS_PATH),
+                None,
+                gio::DBusSignalFlags::NONE,
+                |_, _, _, _, _, parameters| {
+                    if let Some((id, action_key)) = parameters.get::<(u32, String)>() {
+                        handle_action(id, &action_key);
+                    }
+                },
+            );
+
+            connection.signal_subscribe(
+                None,
+                Some(NOTIFICATIONS_BUS),
+                Some("NotificationClosed"),
+                Some(NOTIFICATIONS_PATH),
+                None,
+                gio::DBusSignalFlags::NONE,
+                |_, _, _, _, _, parameters| {
+                    if let Some((id, _reason)) = parameters.get::<(u32, u32)>() {
+                        PENDING_ACTIONS.with(|pending| pending.borrow_mut().remove(&id));
+                    }
+                },
+            );
+
+            Some(connection)
+        })
+        .clone()
+    })
+}
+
+fn handle_action(id: u32, action_key: &str) {
+    let actions = PENDING_ACTIONS.with(|pending| pending.borrow().get(&id).cloned());
+
+    if let Some(actions) = actions {
+        // "default" is sent when the notification body itself is clicked
+        let action = actions
+            .iter()
+            .find(|action| action.key() == action_key)
+            .or_else(|| actions.first().filter(|_| action_key == "default"));
+
+        if let Some(action) = action {
+            println!("Notification action invoked: {}", action_key);
+            action.run();
+        }
+    }
+}
+
+// Send a notification for an event, honouring the user's per-event settings
+pub fn notify(event: NotifyEvent, summary: &str, body: &str, actions: &[NotifyAction]) {
+    let settings = event_settings(event);
+    if !settings.enabled {
+        return;
+    }
+
+    let Some(connection) = session_bus() else {
+        notify_hyprctl(event, &settings, summary, body);
+        return;
+    };
+
+    let mut action_list = Vec::new();
+    if let Some(first) = actions.first() {
+        action_list.push("default".to_string());
+        action_list.push(first.label().to_string());
+    }
+    for action in actions {
+        action_list.push(action.key().to_string());
+        action_list.push(action.label().to_string());
+    }
+
+    let mut hints: HashMap<String, glib::Variant> = HashMap::new();
+    hints.insert("urgency".to_string(), settings.urgency.hint_value().to_variant());
+    hints.insert("desktop-entry".to_string(), "ummit-settings".to_variant());
+
+    let parameters = (
+        "UmmItOS Settings",
+        0u32,
+        event.icon_name(),
+        summary,
+        body,
+        action_list,
+        hints,
+        settings.timeout_ms,
+    )
+        .to_variant();
+
+    let actions = actions.to_vec();
+    let summary = summary.to_string();
+    let body = body.to_string();
+
+    connection.call(
+        Some(NOTIFICATIONS_BUS),
+        NOTIFICATIONS_PATH,
+        NOTIFICATIONS_BUS,
+        "Notify",
+        Some(&parameters),
+        Some(glib::VariantTy::new("(u)").unwrap()),
+        gio::DBusCallFlags::NONE,
+        -1,
+        None::<&gio::Cancellable>,
+        move |result| match result {
+            Ok(reply) => {
+                if let Some((id,)) = reply.get::<(u32,)>()
+                    && !actions.is_empty()
+                {
+                    PENDING_ACTIONS.with(|pending| pending.borrow_mut().insert(id, actions));
+                }
+            }
+            Err(e) => {
+                println!("No notification daemon available ({}), falling back to hyprctl", e);
+                notify_hyprctl(event, &settings, &summary, &body);
+            }
+        },
+    );
+}
+
+fn notify_hyprctl(event: NotifyEvent, settings: &EventSettings, summary: &str, body: &str) {
+    let text = if body.is_empty() { summary } else { body };
+    let timeout_ms = if settings.timeout_ms > 0 { settings.timeout_ms } else { 5000 };
+
+    let _ = Command::new("hyprctl")
+        .arg("notify")
+        .arg(event.hyprctl_icon())
+        .arg(timeout_ms.to_string())
+        .arg(settings.urgency.hyprctl_colour())
+        .arg(format!("fontsize:35 {}", text))
+        .spawn();
+}
@@ -0,0 +1,137 @@
+use gtk::prelude::*;
+use gtk::{gio, glib};
+use std::env;
+use std::path::{Path, PathBuf};
+
+// User-entered directory paths: `~` and environment variable expansion, the
+// XDG videos directory, and checks that do not touch the filesystem.
+
+// Warn below this much free space
+const LOW_SPACE_BYTES: u64 = 1024 * 1024 * 1024;
+
+// XDG_VIDEOS_DIR from user-dirs.dirs, falling back to ~/Videos
+pub fn videos_dir() -> PathBuf {
+    glib::user_special_dir(glib::UserDirectory::Videos)
+        .unwrap_or_else(|| glib::home_dir().join("Videos"))
+}
+
+// Expand a leading `~` and `$VAR` / `${VAR}` references
+pub fn expand(input: &str) -> String {
+    let input = input.trim();
+    let home = glib::home_dir().to_string_lossy().to_string();
+
+    let input = if input == "~" {
+        home.clone()
+    } else if let Some(rest) = input.strip_prefix("~/") {
+        format!("{}/{}", home, rest)
+    } else {
+        input.to_string()
+    };
+
+    let mut expanded = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            expanded.push(c);
+            continue;
+        }
+
+        let braced = chars.peek() == Some(&'{');
+        if braced {
+            chars.next();
+        }
+
+        let mut name = String::new();
+        while let Some(&next) = chars.peek() {
+            if next.is_ascii_alphanumeric() || next == '_' {
+                name.push(next);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        if braced {
+            if chars.peek() == Some(&'}') {
+                chars.next();
+            } else {
+                // Not a valid ${VAR}: keep the text as typed
+                expanded.push_str("${");
+                expanded.push_str(&name);
+                continue;
+            }
+        }
+
+        if name.is_empty() {
+            expanded.push('$');
+            if braced {
+                expanded.push_str("{}");
+            }
+        } else {
+            expanded.push_str(&env::var(&name).unwrap_or_default());
+        }
+    }
+
+    expanded
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryCheck {
+    // False if Apply will have to create it
+    pub exists: bool,
+    pub free_bytes: Option<u64>,
+}
+
+impl DirectoryCheck {
+    pub fn is_low_on_space(&self) -> bool {
+        self.free_bytes.is_some_and(|free| free < LOW_SPACE_BYTES)
+    }
+}
+
+// Check that a directory exists and is writable, or could be created, without
+// creating anything
+pub fn check_directory(path: &str) -> Result<DirectoryCheck, String> {
+    if path.trim().is_empty() {
+        return Err("No directory given".to_string());
+    }
+
+    let path = Path::new(path);
+    if !path.is_absolute() {
+        return Err(format!("{} is not an absolute path", path.display()));
+    }
+
+    // The nearest existing ancestor decides whether we can write here
+    let existing = path
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .ok_or_else(|| format!("No part of {} exists", path.display()))?;
+
+    if !existing.is_dir() {
+        return Err(format!("{} is not a directory", existing.display()));
+    }
+
+    let file = gio::File::for_path(existing);
+    let can_write = file
+        .query_info(
+            gio::FILE_ATTRIBUTE_ACCESS_CAN_WRITE,
+            gio::FileQueryInfoFlags::NONE,
+            gio::Cancellable::NONE,
+        )
+        .map(|info| info.boolean(gio::FILE_ATTRIBUTE_ACCESS_CAN_WRITE))
+        .map_err(|e| format!("Cannot access {}: {}", existing.display(), e))?;
+
+    if !can_write {
+        return Err(format!("{} is not writable", existing.display()));
+    }
+
+    let free_bytes = file
+        .query_filesystem_info(gio::FILE_ATTRIBUTE_FILESYSTEM_FREE, gio::Cancellable::NONE)
+        .ok()
+        .map(|info| info.attribute_uint64(gio::FILE_ATTRIBUTE_FILESYSTEM_FREE));
+
+    Ok(DirectoryCheck {
+        exists: existing == path,
+        free_bytes,
+    })
+}
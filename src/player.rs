@@ -0,0 +1,227 @@
+use gtk::prelude::*;
+use gtk::{gio, Box, Button, Label, Orientation};
+
+use crate::markers;
+use crate::recording;
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+// In-app preview pane for recordings (gtk::Video brings its own
+// scrubber, play/pause and volume controls). Markers saved with the
+// recording are listed below the video and seek to their position.
+#[derive(Clone)]
+pub struct PreviewPlayer {
+    container: Box,
+    video: gtk::Video,
+    title_label: Label,
+    external_btn: Button,
+    markers_box: gtk::FlowBox,
+    current_file: Rc<RefCell<Option<PathBuf>>>,
+}
+
+impl PreviewPlayer {
+    pub fn new() -> Self {
+        let container = Box::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(6)
+            .margin_top(12)
+            .build();
+
+        // Header with file name and secondary actions
+        let header_box = Box::builder()
+            .orientation(Orientation::Horizontal)
+            .spacing(8)
+            .build();
+
+        let title_label = Label::builder()
+            .label("Select a recording to preview it here")
+            .halign(gtk::Align::Start)
+            .hexpand(true)
+            .ellipsize(gtk::pango::EllipsizeMode::Middle)
+            .build();
+        title_label.add_css_class("dim-label");
+
+        let external_btn = Button::builder()
+            .icon_name("document-open-symbolic")
+            .tooltip_text("Open in external player")
+            .sensitive(false)
+            .build();
+
+        let close_btn = Button::builder()
+            .icon_name("window-close-symbolic")
+            .tooltip_text("Close preview")
+            .build();
+
+        header_box.append(&title_label);
+        header_box.append(&external_btn);
+        header_box.append(&close_btn);
+        container.append(&header_box);
+
+        let video = gtk::Video::builder()
+            .autoplay(false)
+            .height_request(240)
+            .hexpand(true)
+            .visible(false)
+            .build();
+
+        container.append(&video);
+
+        let markers_box = gtk::FlowBox::builder()
+            .selection_mode(gtk::SelectionMode::None)
+            .column_spacing(4)
+            .row_spacing(4)
+            .visible(false)
+            .build();
+
+        container.append(&markers_box);
+
+        let player = Self {
+            container,
+            video,
+            title_label,
+            external_btn,
+            markers_box,
+            current_file: Rc::new(RefCell::new(None)),
+        };
+
+        // Connect external player button
+        {
+            let player_clone = player.clone();
+            player.external_btn.connect_clicked(move |button| {
+                if let Some(path) = player_clone.current_file.borrow().clone() {
+                    open_with_app_chooser(button, &path);
+                }
+            });
+        }
+
+        // Connect close button
+        {
+            let player_clone = player.clone();
+            close_btn.connect_clicked(move |_| {
+                player_clone.clear();
+            });
+        }
+
+        player
+    }
+
+    pub fn widget(&self) -> &Box {
+        &self.container
+    }
+
+    // Load a recording into the preview pane and start playback
+    pub fn load(&self, file_path: &str) {
+        println!("Previewing recording: {}", file_path);
+
+        let path = PathBuf::from(file_path);
+        let media = gtk::MediaFile::for_filename(&path);
+        media.play();
+
+        self.video.set_media_stream(Some(&media));
+        self.video.set_visible(true);
+
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| file_path.to_string());
+        self.title_label.set_text(&file_name);
+        self.title_label.remove_css_class("dim-label");
+        self.external_btn.set_sensitive(true);
+
+        self.show_markers(&path);
+
+        *self.current_file.borrow_mut() = Some(path);
+    }
+
+    // List the recording's markers as buttons that seek to them
+    fn show_markers(&self, path: &Path) {
+        self.clear_markers();
+
+        let markers = markers::load(path);
+        self.markers_box.set_visible(!markers.is_empty());
+
+        for marker in markers {
+            let button = Button::builder()
+                .label(format!("{} {}", recording::format_elapsed(marker.offset), marker.title))
+                .tooltip_text(format!("Jump to {}", marker.title))
+                .build();
+            button.add_css_class("flat");
+
+            let video_clone = self.video.clone();
+            button.connect_clicked(move |_| {
+                if let Some(stream) = video_clone.media_stream() {
+                    stream.seek(marker.offset.as_micros() as i64);
+                    stream.play();
+                }
+            });
+
+            self.markers_box.insert(&button, -1);
+        }
+    }
+
+    fn clear_markers(&self) {
+        while let Some(child) = self.markers_box.first_child() {
+            self.markers_box.remove(&child);
+        }
+        self.markers_box.set_visible(false);
+    }
+
+    // Stop playback and reset the pane
+    pub fn clear(&self) {
+        if let Some(stream) = self.video.media_stream() {
+            stream.pause();
+        }
+
+        self.video.set_media_stream(None::<&gtk::MediaStream>);
+        self.video.set_visible(false);
+        self.title_label.set_text("Select a recording to preview it here");
+        self.title_label.add_css_class("dim-label");
+        self.external_btn.set_sensitive(false);
+
+        self.clear_markers();
+
+        *self.current_file.borrow_mut() = None;
+    }
+
+    // Clear the preview if the file it shows has been removed
+    pub fn forget_if_missing(&self) {
+        let missing = self
+            .current_file
+            .borrow()
+            .as_ref()
+            .map(|path| !path.exists())
+            .unwrap_or(false);
+
+        if missing {
+            self.clear();
+        }
+    }
+}
+
+// Let the user pick an application for the recording via the AppInfo chooser
+// (deprecated in GTK 4.10 without a replacement)
+#[allow(deprecated)]
+fn open_with_app_chooser(widget: &impl IsA<gtk::Widget>, path: &Path) {
+    let parent = widget
+        .root()
+        .and_then(|root| root.downcast::<gtk::Window>().ok());
+    let file = gio::File::for_path(path);
+
+    let dialog = gtk::AppChooserDialog::new(parent.as_ref(), gtk::DialogFlags::MODAL, &file);
+    dialog.set_title(Some("Open Recording With"));
+
+    dialog.connect_response(move |dialog, response| {
+        if response == gtk::ResponseType::Ok
+            && let Some(app_info) = dialog.app_info()
+        {
+            let context = WidgetExt::display(dialog).app_launch_context();
+            if let Err(e) = app_info.launch(std::slice::from_ref(&file), Some(&context)) {
+                eprintln!("Failed to launch {}: {}", app_info.name(), e);
+            }
+        }
+        dialog.close();
+    });
+
+    dialog.present();
+}
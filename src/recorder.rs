@@ -0,0 +1,349 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::config;
+
+// Screen recorder backends. Each backend knows its CLI flags, how to pause
+// and stop it, and which options it supports; the record page greys out the
+// rest.
+
+// Containers the recorders write; other tools list files with these
+pub const VIDEO_EXTENSIONS: [&str; 3] = ["mp4", "mkv", "webm"];
+
+pub fn is_video_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| VIDEO_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Capabilities {
+    pub audio: bool,
+    pub region: bool,
+    pub framerate: bool,
+    pub codec: bool,
+    pub pause: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    H264,
+    Hevc,
+}
+
+// Named sets of encoding options, translated to each backend's flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    Default,
+    Smooth,
+    Compact,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 3] = [Preset::Default, Preset::Smooth, Preset::Compact];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Preset::Default => "default",
+            Preset::Smooth => "smooth",
+            Preset::Compact => "compact",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Preset::Default => "Default",
+            Preset::Smooth => "Smooth (60 fps)",
+            Preset::Compact => "Compact (30 fps, H.265)",
+        }
+    }
+
+    // 0 leaves the frame rate to the recorder
+    fn framerate(self) -> u32 {
+        match self {
+            Preset::Default => 0,
+            Preset::Smooth => 60,
+            Preset::Compact => 30,
+        }
+    }
+
+    fn codec(self) -> Codec {
+        match self {
+            Preset::Default | Preset::Smooth => Codec::H264,
+            Preset::Compact => Codec::Hevc,
+        }
+    }
+
+    // ffmpeg video encoding flags for re-encoding with this preset
+    pub fn ffmpeg_args(self) -> Vec<String> {
+        let mut args: Vec<String> = match self.codec() {
+            Codec::H264 => ["-c:v", "libx264", "-preset", "veryfast", "-crf", "23"],
+            Codec::Hevc => ["-c:v", "libx265", "-preset", "medium", "-crf", "28"],
+        }
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+        if self.framerate() > 0 {
+            args.extend(["-r".to_string(), self.framerate().to_string()]);
+        }
+        args
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordingOptions {
+    pub preset: Preset,
+    pub audio: bool,
+    // slurp geometry ("x,y wxh"); None records the whole screen
+    pub region: Option<String>,
+}
+
+pub trait RecorderBackend {
+    // Config key, also the executable and process name
+    fn name(&self) -> &'static str;
+
+    fn capabilities(&self) -> Capabilities;
+
+    // Command line for recording to `file`
+    fn build_args(&self, options: &RecordingOptions, file: &str) -> Vec<String>;
+
+    // Signal that makes the recorder finish the file and exit
+    fn stop_signal(&self) -> &'static str {
+        "-INT"
+    }
+
+    // Signal to send for pausing or resuming
+    fn pause_signal(&self, paused: bool) -> &'static str {
+        if paused { "-STOP" } else { "-CONT" }
+    }
+
+    fn is_installed(&self) -> bool {
+        Command::new("which")
+            .arg(self.name())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    }
+
+    // Pids of running recorder processes, whoever started them
+    fn running_pids(&self) -> Vec<u32> {
+        Command::new("pidof")
+            .arg(self.name())
+            .output()
+            .map(|output| {
+                String::from_utf8_lossy(&output.stdout)
+                    .split_whitespace()
+                    .filter_map(|pid| pid.parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+struct WfRecorder;
+
+impl RecorderBackend for WfRecorder {
+    fn name(&self) -> &'static str {
+        "wf-recorder"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            audio: true,
+            region: true,
+            framerate: true,
+            codec: true,
+            pause: true,
+        }
+    }
+
+    fn build_args(&self, options: &RecordingOptions, file: &str) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if options.audio {
+            args.push("-a".to_string());
+        }
+        if let Some(region) = &options.region {
+            args.extend(["-g".to_string(), region.clone()]);
+        }
+        if options.preset.framerate() > 0 {
+            args.extend(["-r".to_string(), options.preset.framerate().to_string()]);
+        }
+        let codec = match options.preset.codec() {
+            Codec::H264 => "libx264",
+            Codec::Hevc => "libx265",
+        };
+        args.extend(["-c".to_string(), codec.to_string()]);
+        args.extend(["--file".to_string(), file.to_string()]);
+        args
+    }
+}
+
+// Hardware encoding through VA-API; stopping and resuming its capture
+// pipeline is not supported, so neither is pausing
+struct WlScreenrec;
+
+impl RecorderBackend for WlScreenrec {
+    fn name(&self) -> &'static str {
+        "wl-screenrec"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            audio: true,
+            region: true,
+            framerate: false,
+            codec: true,
+            pause: false,
+        }
+    }
+
+    fn build_args(&self, options: &RecordingOptions, file: &str) -> Vec<String> {
+        let mut args = Vec::new();
+
+        if options.audio {
+            args.push("--audio".to_string());
+        }
+        if let Some(region) = &options.region {
+            args.extend(["-g".to_string(), region.clone()]);
+        }
+        let codec = match options.preset.codec() {
+            Codec::H264 => "avc",
+            Codec::Hevc => "hevc",
+        };
+        args.extend(["--codec".to_string(), codec.to_string()]);
+        args.extend(["-f".to_string(), file.to_string()]);
+        args
+    }
+}
+
+struct GpuScreenRecorder;
+
+impl RecorderBackend for GpuScreenRecorder {
+    fn name(&self) -> &'static str {
+        "gpu-screen-recorder"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities {
+            audio: true,
+            region: true,
+            framerate: true,
+            codec: true,
+            pause: true,
+        }
+    }
+
+    fn build_args(&self, options: &RecordingOptions, file: &str) -> Vec<String> {
+        let mut args = Vec::new();
+
+        match options.region.as_deref().and_then(slurp_to_gsr_region) {
+            Some(region) => args.extend(["-w".to_string(), "region".to_string(), "-region".to_string(), region]),
+            None => args.extend(["-w".to_string(), "screen".to_string()]),
+        }
+        if options.audio {
+            args.extend(["-a".to_string(), "default_output".to_string()]);
+        }
+        let framerate = match options.preset.framerate() {
+            0 => 60,
+            framerate => framerate,
+        };
+        args.extend(["-f".to_string(), framerate.to_string()]);
+        let codec = match options.preset.codec() {
+            Codec::H264 => "h264",
+            Codec::Hevc => "hevc",
+        };
+        args.extend(["-k".to_string(), codec.to_string()]);
+        args.extend(["-o".to_string(), file.to_string()]);
+        args
+    }
+
+    // SIGUSR2 toggles pause in gpu-screen-recorder
+    fn pause_signal(&self, _paused: bool) -> &'static str {
+        "-USR2"
+    }
+}
+
+// "x,y wxh" (slurp) to "WxH+X+Y" (gpu-screen-recorder)
+fn slurp_to_gsr_region(region: &str) -> Option<String> {
+    let (position, size) = region.trim().split_once(' ')?;
+    let (x, y) = position.split_once(',')?;
+    let (width, height) = size.split_once('x')?;
+    Some(format!("{}x{}+{}+{}", width, height, x, y))
+}
+
+pub fn backends() -> Vec<Box<dyn RecorderBackend>> {
+    vec![Box::new(WfRecorder), Box::new(WlScreenrec), Box::new(GpuScreenRecorder)]
+}
+
+pub fn backend_by_name(name: &str) -> Option<Box<dyn RecorderBackend>> {
+    backends().into_iter().find(|backend| backend.name() == name)
+}
+
+// Backend chosen by the user (wf-recorder unless changed)
+pub fn selected_backend() -> Box<dyn RecorderBackend> {
+    backend_by_name(&config::get_string("Recording", "backend", "wf-recorder"))
+        .unwrap_or_else(|| Box::new(WfRecorder))
+}
+
+pub fn set_selected_backend(name: &str) {
+    config::set_string("Recording", "backend", name);
+}
+
+pub fn selected_preset() -> Preset {
+    let key = config::get_string("Recording", "preset", Preset::Default.key());
+    Preset::ALL
+        .into_iter()
+        .find(|preset| preset.key() == key)
+        .unwrap_or(Preset::Default)
+}
+
+pub fn set_selected_preset(preset: Preset) {
+    config::set_string("Recording", "preset", preset.key());
+}
+
+pub fn record_audio() -> bool {
+    config::get_bool("Recording", "audio", true)
+}
+
+pub fn set_record_audio(enabled: bool) {
+    config::set_bool("Recording", "audio", enabled);
+}
+
+pub fn select_region() -> bool {
+    config::get_bool("Recording", "select-region", false)
+}
+
+pub fn set_select_region(enabled: bool) {
+    config::set_bool("Recording", "select-region", enabled);
+}
+
+// Put the saved recording on the clipboard when it is stopped
+pub fn copy_on_save() -> bool {
+    config::get_bool("Recording", "copy-on-save", false)
+}
+
+pub fn set_copy_on_save(enabled: bool) {
+    config::set_bool("Recording", "copy-on-save", enabled);
+}
+
+// Ask the user for a screen region with slurp; None if cancelled
+pub fn pick_region() -> Option<String> {
+    let output = Command::new("slurp").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let region = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if region.is_empty() { None } else { Some(region) }
+}
+
+// Any known recorder running, e.g. started outside ummit-settings
+pub fn any_running() -> Option<&'static str> {
+    backends()
+        .into_iter()
+        .find(|backend| !backend.running_pids().is_empty())
+        .map(|backend| backend.name())
+}
@@ -0,0 +1,287 @@
+use gtk::glib;
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Child, Command};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::recorder;
+
+// Shared state of the active recorder session. The record page, the tray
+// icon and anything else interested subscribe to state changes here.
+
+#[derive(Debug, Clone)]
+pub struct RecordingSession {
+    pub file_path: String,
+    pub recording_dir: String,
+    pub pid: u32,
+    // Recorder backend and preset the session was started with
+    pub backend: String,
+    pub preset: String,
+    started_unix: i64,
+    started_at: Instant,
+    paused_since: Option<Instant>,
+    paused_total: Duration,
+}
+
+impl RecordingSession {
+    // Recorded time, not counting pauses
+    pub fn elapsed(&self) -> Duration {
+        let paused_now = self
+            .paused_since
+            .map(|since| since.elapsed())
+            .unwrap_or_default();
+
+        self.started_at
+            .elapsed()
+            .saturating_sub(self.paused_total + paused_now)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_since.is_some()
+    }
+
+    // Start time as a Unix timestamp
+    pub fn started_unix(&self) -> i64 {
+        self.started_unix
+    }
+}
+
+type Listener = Rc<dyn Fn(Option<&RecordingSession>)>;
+type StatusListener = Rc<dyn Fn(&str)>;
+
+thread_local! {
+    static SESSION: RefCell<Option<RecordingSession>> = const { RefCell::new(None) };
+    static CHILD: RefCell<Option<Child>> = const { RefCell::new(None) };
+    static LISTENERS: RefCell<Vec<(u64, Listener)>> = const { RefCell::new(Vec::new()) };
+    static STATUS_LISTENERS: RefCell<Vec<(u64, StatusListener)>> = const { RefCell::new(Vec::new()) };
+    static NEXT_LISTENER_ID: Cell<u64> = const { Cell::new(0) };
+}
+
+fn next_listener_id() -> u64 {
+    NEXT_LISTENER_ID.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        id
+    })
+}
+
+// Removes its listener when dropped, so callbacks stop once the widgets they
+// update are gone (e.g. when the window is closed but the app keeps running)
+#[must_use = "the listener is removed as soon as the subscription is dropped"]
+pub struct Subscription {
+    id: u64,
+    status: bool,
+}
+
+impl Subscription {
+    // For listeners that live as long as the application
+    pub fn keep(self) {
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let id = self.id;
+        if self.status {
+            STATUS_LISTENERS.with(|listeners| listeners.borrow_mut().retain(|(existing, _)| *existing != id));
+        } else {
+            LISTENERS.with(|listeners| listeners.borrow_mut().retain(|(existing, _)| *existing != id));
+        }
+    }
+}
+
+pub fn current() -> Option<RecordingSession> {
+    SESSION.with(|session| session.borrow().clone())
+}
+
+pub fn is_recording() -> bool {
+    SESSION.with(|session| session.borrow().is_some())
+}
+
+// Register a callback that runs whenever the session starts, stops or pauses
+pub fn subscribe<F: Fn(Option<&RecordingSession>) + 'static>(callback: F) -> Subscription {
+    let callback: Listener = Rc::new(callback);
+    callback(current().as_ref());
+
+    let id = next_listener_id();
+    LISTENERS.with(|listeners| listeners.borrow_mut().push((id, callback)));
+    Subscription { id, status: false }
+}
+
+fn notify_listeners() {
+    let session = current();
+    write_state_file(session.as_ref());
+
+    let listeners = LISTENERS.with(|listeners| listeners.borrow().clone());
+    for (_, listener) in listeners {
+        listener(session.as_ref());
+    }
+}
+
+// Register a callback for human readable status messages
+pub fn subscribe_status<F: Fn(&str) + 'static>(callback: F) -> Subscription {
+    let id = next_listener_id();
+    STATUS_LISTENERS.with(|listeners| listeners.borrow_mut().push((id, Rc::new(callback))));
+    Subscription { id, status: true }
+}
+
+// Report a status message to the record page (and the log)
+pub fn report_status(message: &str) {
+    println!("Recording status: {}", message);
+
+    let listeners = STATUS_LISTENERS.with(|listeners| listeners.borrow().clone());
+    for (_, listener) in listeners {
+        listener(message);
+    }
+}
+
+// Track a freshly spawned recorder process
+pub fn begin(child: Child, file_path: &str, recording_dir: &str, backend: &str, preset: &str) {
+    let session = RecordingSession {
+        file_path: file_path.to_string(),
+        recording_dir: recording_dir.to_string(),
+        pid: child.id(),
+        backend: backend.to_string(),
+        preset: preset.to_string(),
+        started_unix: chrono::Local::now().timestamp(),
+        started_at: Instant::now(),
+        paused_since: None,
+        paused_total: Duration::ZERO,
+    };
+
+    CHILD.with(|slot| *slot.borrow_mut() = Some(child));
+    SESSION.with(|slot| *slot.borrow_mut() = Some(session));
+    notify_listeners();
+    report_status(&format!("Recording to: {}", file_path));
+}
+
+// Forget the active session and reap the recorder process
+pub fn end() -> Option<RecordingSession> {
+    let session = SESSION.with(|slot| slot.borrow_mut().take());
+
+    if let Some(mut child) = CHILD.with(|slot| slot.borrow_mut().take()) {
+        // The recorder finalises the file after SIGINT; reap it off the UI thread
+        std::thread::spawn(move || {
+            let _ = child.wait();
+        });
+    }
+
+    if session.is_some() {
+        notify_listeners();
+    }
+    session
+}
+
+// True if the recorder process we started has exited on its own
+pub fn has_exited() -> bool {
+    CHILD.with(|slot| {
+        slot.borrow_mut()
+            .as_mut()
+            .map(|child| matches!(child.try_wait(), Ok(Some(_))))
+            .unwrap_or(false)
+    })
+}
+
+// Suspend or resume the recorder process
+pub fn set_paused(paused: bool) -> bool {
+    let Some(session) = current() else {
+        return false;
+    };
+
+    if session.is_paused() == paused {
+        return true;
+    }
+
+    let Some(backend) = recorder::backend_by_name(&session.backend) else {
+        return false;
+    };
+    if !backend.capabilities().pause {
+        return false;
+    }
+
+    let signal = backend.pause_signal(paused);
+    let sent = Command::new("kill")
+        .arg(signal)
+        .arg(session.pid.to_string())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false);
+
+    if !sent {
+        println!("Failed to send {} to {} PID: {}", signal, session.backend, session.pid);
+        return false;
+    }
+
+    SESSION.with(|slot| {
+        if let Some(session) = slot.borrow_mut().as_mut() {
+            if paused {
+                session.paused_since = Some(Instant::now());
+            } else if let Some(since) = session.paused_since.take() {
+                session.paused_total += since.elapsed();
+            }
+        }
+    });
+
+    notify_listeners();
+
+    if let Some(session) = current() {
+        let state = if paused { "Paused" } else { "Recording to" };
+        report_status(&format!("{}: {}", state, session.file_path));
+    }
+    true
+}
+
+// Block until a process has exited (e.g. the recorder finalising the file)
+pub fn wait_for_exit(pid: u32, timeout: Duration) {
+    let proc_path = PathBuf::from(format!("/proc/{}", pid));
+    let started = Instant::now();
+
+    while proc_path.exists() && started.elapsed() < timeout {
+        std::thread::sleep(Duration::from_millis(200));
+    }
+}
+
+// Format a duration as HH:MM:SS
+pub fn format_elapsed(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{:02}:{:02}:{:02}", seconds / 3600, (seconds / 60) % 60, seconds % 60)
+}
+
+// Runtime state file read by `ummit-settings waybar` and other processes
+pub fn state_file() -> PathBuf {
+    glib::user_runtime_dir().join("ummit-settings").join("recording.state")
+}
+
+fn write_state_file(session: Option<&RecordingSession>) {
+    let path = state_file();
+
+    let Some(session) = session else {
+        let _ = fs::remove_file(&path);
+        return;
+    };
+
+    let key_file = glib::KeyFile::new();
+    let now = chrono::Local::now().timestamp();
+    let paused_secs = session
+        .paused_since
+        .map(|since| since.elapsed().as_secs() as i64)
+        .unwrap_or(0);
+
+    key_file.set_int64("Recording", "pid", session.pid as i64);
+    key_file.set_string("Recording", "file", &session.file_path);
+    key_file.set_string("Recording", "directory", &session.recording_dir);
+    key_file.set_string("Recording", "backend", &session.backend);
+    key_file.set_int64("Recording", "started", session.started_unix);
+    key_file.set_int64("Recording", "paused-total", session.paused_total.as_secs() as i64);
+    key_file.set_int64("Recording", "paused-since", if session.is_paused() { now - paused_secs } else { 0 });
+
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    if let Err(e) = key_file.save_to_file(&path) {
+        eprintln!("Failed to write recording state to {}: {}", path.display(), e);
+    }
+}
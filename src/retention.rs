@@ -0,0 +1,318 @@
+use gtk::gio;
+use gtk::prelude::*;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::config;
+use crate::history;
+use crate::markers;
+use crate::recorder;
+use crate::recording;
+
+// Storage usage of the recording directory and the optional retention
+// policy that trims it. Pinned and tagged recordings are never removed.
+
+const CONFIG_GROUP: &str = "Retention";
+
+#[derive(Debug, Clone)]
+pub struct RecordingFile {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: SystemTime,
+}
+
+// Recordings in a directory, oldest first
+pub fn scan(recording_dir: &str) -> Vec<RecordingFile> {
+    let Ok(entries) = fs::read_dir(recording_dir) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<RecordingFile> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| recorder::is_video_file(&entry.path()) && !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            Some(RecordingFile {
+                path: entry.path(),
+                size: metadata.len(),
+                modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            })
+        })
+        .collect();
+
+    files.sort_by_key(|file| file.modified);
+    files
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonthUsage {
+    pub month: String,
+    pub count: usize,
+    pub size: u64,
+}
+
+// Per-month totals, newest month first
+pub fn usage_by_month(files: &[RecordingFile]) -> Vec<MonthUsage> {
+    let mut months: BTreeMap<String, MonthUsage> = BTreeMap::new();
+
+    for file in files {
+        let month = chrono::DateTime::<chrono::Local>::from(file.modified)
+            .format("%Y-%m")
+            .to_string();
+        let usage = months.entry(month.clone()).or_insert(MonthUsage { month, count: 0, size: 0 });
+        usage.count += 1;
+        usage.size += file.size;
+    }
+
+    months.into_values().rev().collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RemovalAction {
+    Trash,
+    Delete,
+}
+
+impl RemovalAction {
+    pub const ALL: [RemovalAction; 2] = [RemovalAction::Trash, RemovalAction::Delete];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RemovalAction::Trash => "Move to trash",
+            RemovalAction::Delete => "Delete permanently",
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            RemovalAction::Trash => "trash",
+            RemovalAction::Delete => "delete",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Policy {
+    pub enabled: bool,
+    // 0 disables the age limit
+    pub max_age_days: i32,
+    // 0 disables the size quota
+    pub max_size_gb: i32,
+    pub action: RemovalAction,
+}
+
+impl Policy {
+    pub fn load() -> Self {
+        let action = config::get_string(CONFIG_GROUP, "action", RemovalAction::Trash.key());
+
+        Policy {
+            enabled: config::get_bool(CONFIG_GROUP, "enabled", false),
+            max_age_days: config::get_int(CONFIG_GROUP, "max-age-days", 30),
+            max_size_gb: config::get_int(CONFIG_GROUP, "max-size-gb", 0),
+            action: RemovalAction::ALL
+                .into_iter()
+                .find(|candidate| candidate.key() == action)
+                .unwrap_or(RemovalAction::Trash),
+        }
+    }
+
+    pub fn save(&self) {
+        let key_file = config::load();
+        key_file.set_boolean(CONFIG_GROUP, "enabled", self.enabled);
+        key_file.set_integer(CONFIG_GROUP, "max-age-days", self.max_age_days);
+        key_file.set_integer(CONFIG_GROUP, "max-size-gb", self.max_size_gb);
+        key_file.set_string(CONFIG_GROUP, "action", self.action.key());
+        config::save(&key_file);
+    }
+}
+
+// The first run of the policy is previewed and confirmed by the user
+pub fn is_confirmed() -> bool {
+    config::get_bool(CONFIG_GROUP, "confirmed", false)
+}
+
+pub fn set_confirmed(confirmed: bool) {
+    config::set_bool(CONFIG_GROUP, "confirmed", confirmed);
+}
+
+fn pinned_file() -> PathBuf {
+    config::data_dir().join("pinned-recordings")
+}
+
+fn pinned() -> Vec<String> {
+    fs::read_to_string(pinned_file())
+        .map(|contents| contents.lines().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+pub fn is_pinned(path: &Path) -> bool {
+    let path = path.to_string_lossy();
+    pinned().iter().any(|pinned| *pinned == path)
+}
+
+pub fn set_pinned(path: &Path, pin: bool) {
+    let path = path.to_string_lossy().to_string();
+    let mut paths: Vec<String> = pinned()
+        .into_iter()
+        .filter(|pinned| *pinned != path && Path::new(pinned).exists())
+        .collect();
+    if pin {
+        paths.push(path);
+    }
+
+    let file = pinned_file();
+    if let Some(dir) = file.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let contents: String = paths.iter().map(|path| format!("{}\n", path)).collect();
+    if let Err(e) = fs::write(&file, contents) {
+        eprintln!("Failed to save pinned recordings: {}", e);
+    }
+}
+
+fn tags_file() -> PathBuf {
+    config::data_dir().join("recording-tags")
+}
+
+// One "path<TAB>tag,tag" line per tagged recording
+fn all_tags() -> Vec<(String, Vec<String>)> {
+    fs::read_to_string(tags_file())
+        .map(|contents| {
+            contents
+                .lines()
+                .filter_map(|line| line.split_once('\t'))
+                .map(|(path, tags)| (path.to_string(), parse_tags(tags)))
+                .filter(|(_, tags)| !tags.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Comma-separated tags, trimmed, without empties or duplicates
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in text.split(',').map(str::trim).filter(|tag| !tag.is_empty()) {
+        if !tags.iter().any(|existing| existing == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+pub fn tags(path: &Path) -> Vec<String> {
+    let path = path.to_string_lossy();
+    all_tags()
+        .into_iter()
+        .find(|(tagged, _)| *tagged == path)
+        .map(|(_, tags)| tags)
+        .unwrap_or_default()
+}
+
+pub fn set_tags(path: &Path, tags: &[String]) {
+    let path = path.to_string_lossy().to_string();
+    let mut entries: Vec<(String, Vec<String>)> = all_tags()
+        .into_iter()
+        .filter(|(tagged, _)| *tagged != path && Path::new(tagged).exists())
+        .collect();
+    if !tags.is_empty() {
+        entries.push((path, tags.to_vec()));
+    }
+
+    let file = tags_file();
+    if let Some(dir) = file.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    let contents: String = entries
+        .iter()
+        .map(|(path, tags)| format!("{}\t{}\n", path, tags.join(",")))
+        .collect();
+    if let Err(e) = fs::write(&file, contents) {
+        eprintln!("Failed to save recording tags: {}", e);
+    }
+}
+
+// Recordings the policy would remove, oldest first
+pub fn plan(recording_dir: &str, policy: &Policy) -> Vec<RecordingFile> {
+    if !policy.enabled {
+        return Vec::new();
+    }
+
+    let files = scan(recording_dir);
+    let active = recording::current().map(|session| PathBuf::from(session.file_path));
+    let newest = files.last().map(|file| file.path.clone());
+    let pinned = pinned();
+    let tagged = all_tags();
+
+    // Never touch pinned or tagged files, the active recording or the latest one
+    let removable = |file: &RecordingFile| {
+        Some(&file.path) != active.as_ref()
+            && Some(&file.path) != newest.as_ref()
+            && !pinned.iter().any(|pinned| Path::new(pinned) == file.path)
+            && !tagged.iter().any(|(path, _)| Path::new(path) == file.path)
+    };
+
+    let mut doomed: Vec<RecordingFile> = Vec::new();
+
+    if policy.max_age_days > 0 {
+        let max_age = Duration::from_secs(policy.max_age_days as u64 * 24 * 60 * 60);
+        let now = SystemTime::now();
+        doomed.extend(
+            files
+                .iter()
+                .filter(|file| removable(file))
+                .filter(|file| now.duration_since(file.modified).unwrap_or_default() > max_age)
+                .cloned(),
+        );
+    }
+
+    if policy.max_size_gb > 0 {
+        let quota = policy.max_size_gb as u64 * 1024 * 1024 * 1024;
+        let mut total: u64 = files
+            .iter()
+            .filter(|file| !doomed.iter().any(|doomed| doomed.path == file.path))
+            .map(|file| file.size)
+            .sum();
+
+        for file in files.iter().filter(|file| removable(file)) {
+            if total <= quota {
+                break;
+            }
+            if !doomed.iter().any(|doomed| doomed.path == file.path) {
+                total -= file.size;
+                doomed.push(file.clone());
+            }
+        }
+    }
+
+    doomed.sort_by_key(|file| file.modified);
+    doomed
+}
+
+// Remove recordings (and their marker files); returns how many were removed
+pub fn remove(files: &[RecordingFile], action: RemovalAction) -> usize {
+    let mut removed = 0;
+
+    for file in files {
+        let sidecar = markers::sidecar_file(&file.path);
+        let result = match action {
+            RemovalAction::Trash => gio::File::for_path(&file.path)
+                .trash(gio::Cancellable::NONE)
+                .map_err(|e| e.to_string()),
+            RemovalAction::Delete => fs::remove_file(&file.path).map_err(|e| e.to_string()),
+        };
+
+        match result {
+            Ok(()) => {
+                println!("Retention: removed {}", file.path.display());
+                let _ = fs::remove_file(&sidecar);
+                history::record_removed(&file.path);
+                removed += 1;
+            }
+            Err(e) => eprintln!("Retention: failed to remove {}: {}", file.path.display(), e),
+        }
+    }
+
+    removed
+}
@@ -0,0 +1,247 @@
+use gtk::prelude::*;
+use gtk::{glib, Box, Button, Label, Orientation};
+use std::cell::Cell;
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::config;
+use crate::hyprland;
+use crate::hyprland_include;
+
+// Safety net for changes that can leave the user unable to see or click the
+// window (monitors, scale, input). The change is applied live only, the
+// previous values are written to disk, and unless the user confirms within
+// the countdown they are put back. Confirmed changes are then persisted in
+// the managed include file. A snapshot left behind by a crash is restored on
+// the next start.
+
+const COUNTDOWN_SECS: u32 = 15;
+
+// Lowest opacity that still leaves windows usable
+const MIN_SAFE_OPACITY: f64 = 0.3;
+
+thread_local! {
+    // Set while a confirmation dialog is open; a second one would replace
+    // the snapshot the first needs to revert
+    static CONFIRMING: Cell<bool> = const { Cell::new(false) };
+}
+
+pub fn is_confirming() -> bool {
+    CONFIRMING.with(Cell::get)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub option: String,
+    pub value: String,
+    // In keyword syntax, applied again on revert
+    pub previous: String,
+}
+
+impl Change {
+    // None if the current value cannot be read to revert to
+    pub fn new(option: &str, value: &str) -> Option<Change> {
+        Some(Change {
+            option: option.to_string(),
+            value: value.to_string(),
+            previous: hyprland::get_keyword_value(option)?,
+        })
+    }
+}
+
+// Settings that go through the confirmation flow
+pub fn is_risky(option: &str, value: &str) -> bool {
+    match option {
+        hyprland::ACTIVE_OPACITY | hyprland::INACTIVE_OPACITY => {
+            value.parse::<f64>().is_ok_and(|opacity| opacity < MIN_SAFE_OPACITY)
+        }
+        _ => option == "monitor" || option.starts_with("input:") || option.starts_with("device"),
+    }
+}
+
+fn snapshot_file() -> PathBuf {
+    config::data_dir().join("pending-revert")
+}
+
+fn save_snapshot(changes: &[Change]) -> Result<(), String> {
+    let contents: String = changes
+        .iter()
+        .map(|change| format!("{}\t{}\n", change.option, change.previous))
+        .collect();
+
+    let path = snapshot_file();
+    if let Some(dir) = path.parent() {
+        let _ = fs::create_dir_all(dir);
+    }
+    fs::write(&path, contents).map_err(|e| format!("Failed to save revert snapshot: {}", e))
+}
+
+fn revert(changes: &[(String, String)]) {
+    // Undo in reverse order, in case a later change depended on an earlier one
+    for (option, previous) in changes.iter().rev() {
+        match hyprland::keyword(option, previous) {
+            Ok(()) => println!("Reverted {} to {}", option, previous),
+            Err(e) => eprintln!("Failed to revert {}: {}", option, e),
+        }
+    }
+    let _ = fs::remove_file(snapshot_file());
+}
+
+// Put back values from a confirmation that never finished (crash, logout)
+pub fn restore_pending() {
+    let Ok(contents) = fs::read_to_string(snapshot_file()) else {
+        return;
+    };
+
+    let changes: Vec<(String, String)> = contents
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(option, previous)| (option.to_string(), previous.to_string()))
+        .collect();
+
+    println!("Restoring {} unconfirmed settings", changes.len());
+    revert(&changes);
+}
+
+// Apply live, then ask "Keep these settings?". `on_finished` gets true if
+// the changes were kept and persisted, false if they were reverted.
+pub fn apply_with_confirmation<F: Fn(bool) + 'static>(parent: Option<&gtk::Window>, changes: Vec<Change>, on_finished: F) {
+    if is_confirming() {
+        eprintln!("Another change is waiting for confirmation, not applying this one");
+        on_finished(false);
+        return;
+    }
+
+    if let Err(e) = save_snapshot(&changes) {
+        // Without a snapshot a crash could not undo the change
+        eprintln!("{}", e);
+        on_finished(false);
+        return;
+    }
+
+    let snapshot: Vec<(String, String)> = changes
+        .iter()
+        .map(|change| (change.option.clone(), change.previous.clone()))
+        .collect();
+
+    for change in &changes {
+        if let Err(e) = hyprland::keyword(&change.option, &change.value) {
+            eprintln!("{}", e);
+            revert(&snapshot);
+            on_finished(false);
+            return;
+        }
+    }
+
+    CONFIRMING.with(|confirming| confirming.set(true));
+
+    let dialog = gtk::Window::builder()
+        .title("Keep These Settings?")
+        .modal(true)
+        .resizable(false)
+        .deletable(false)
+        .build();
+    dialog.set_transient_for(parent);
+
+    let content = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(12)
+        .margin_top(24)
+        .margin_bottom(24)
+        .margin_start(24)
+        .margin_end(24)
+        .build();
+
+    let title = Label::builder()
+        .label("<span weight='bold'>Keep these settings?</span>")
+        .use_markup(true)
+        .halign(gtk::Align::Start)
+        .build();
+
+    let countdown_label = Label::builder()
+        .label(format!("Reverting in {} seconds…", COUNTDOWN_SECS))
+        .halign(gtk::Align::Start)
+        .build();
+
+    let buttons = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .halign(gtk::Align::End)
+        .build();
+
+    let revert_btn = Button::builder()
+        .label("Revert")
+        .build();
+
+    let keep_btn = Button::builder()
+        .label("Keep Changes")
+        .css_classes(["suggested-action"])
+        .build();
+
+    buttons.append(&revert_btn);
+    buttons.append(&keep_btn);
+    content.append(&title);
+    content.append(&countdown_label);
+    content.append(&buttons);
+    dialog.set_child(Some(&content));
+
+    // Enter must not keep a change the user cannot see
+    dialog.set_default_widget(Some(&revert_btn));
+    revert_btn.grab_focus();
+
+    let on_finished = Rc::new(on_finished);
+    let done = Rc::new(Cell::new(false));
+    let finish: Rc<dyn Fn(bool)> = {
+        let dialog = dialog.clone();
+        let done = done.clone();
+        Rc::new(move |keep: bool| {
+            if done.replace(true) {
+                return;
+            }
+
+            if keep {
+                let _ = fs::remove_file(snapshot_file());
+                for change in &changes {
+                    if let Err(e) = hyprland_include::persist(&change.option, &change.value) {
+                        eprintln!("{}", e);
+                    }
+                }
+            } else {
+                revert(&snapshot);
+            }
+
+            dialog.close();
+            CONFIRMING.with(|confirming| confirming.set(false));
+            on_finished(keep);
+        })
+    };
+
+    {
+        let finish = finish.clone();
+        keep_btn.connect_clicked(move |_| finish(true));
+    }
+    {
+        let finish = finish.clone();
+        revert_btn.connect_clicked(move |_| finish(false));
+    }
+
+    let remaining = Cell::new(COUNTDOWN_SECS);
+    glib::timeout_add_seconds_local(1, move || {
+        if done.get() {
+            return glib::ControlFlow::Break;
+        }
+
+        let left = remaining.get().saturating_sub(1);
+        remaining.set(left);
+        if left == 0 {
+            finish(false);
+            return glib::ControlFlow::Break;
+        }
+
+        countdown_label.set_text(&format!("Reverting in {} seconds…", left));
+        glib::ControlFlow::Continue
+    });
+
+    dialog.present();
+}
@@ -0,0 +1,766 @@
+use gtk::prelude::*;
+use gtk::{gdk, gdk_pixbuf, gio, glib, Box, Button, Label, Orientation};
+use std::cell::RefCell;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::rc::{Rc, Weak};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use chrono::format::{Item, StrftimeItems};
+
+use crate::apps;
+use crate::clipboard;
+use crate::config;
+use crate::hyprland;
+use crate::notifications::{self, NotifyAction, NotifyEvent};
+use crate::paths;
+
+// Screenshots with grim, using slurp to pick a region, window or output.
+// Files are named from a strftime template and shown in a thumbnail gallery
+// that can open, annotate (satty or swappy) and delete them.
+
+const CONFIG_GROUP: &str = "Screenshot";
+const DEFAULT_TEMPLATE: &str = "screenshot-%Y-%m-%d_%H-%M-%S";
+const GALLERY_LIMIT: usize = 48;
+// Give the compositor time to unmap our window before capturing
+const HIDE_DELAY: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureMode {
+    Screen,
+    Region,
+    Window,
+    Output,
+}
+
+impl CaptureMode {
+    pub const ALL: [CaptureMode; 4] = [CaptureMode::Screen, CaptureMode::Region, CaptureMode::Window, CaptureMode::Output];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            CaptureMode::Screen => "screen",
+            CaptureMode::Region => "region",
+            CaptureMode::Window => "window",
+            CaptureMode::Output => "output",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CaptureMode::Screen => "Full screen",
+            CaptureMode::Region => "Region",
+            CaptureMode::Window => "Window",
+            CaptureMode::Output => "Output",
+        }
+    }
+}
+
+pub fn selected_mode() -> CaptureMode {
+    let key = config::get_string(CONFIG_GROUP, "mode", CaptureMode::Screen.key());
+    CaptureMode::ALL
+        .into_iter()
+        .find(|mode| mode.key() == key)
+        .unwrap_or(CaptureMode::Screen)
+}
+
+pub fn set_selected_mode(mode: CaptureMode) {
+    config::set_string(CONFIG_GROUP, "mode", mode.key());
+}
+
+pub fn delay_secs() -> i32 {
+    config::get_int(CONFIG_GROUP, "delay", 0)
+}
+
+pub fn set_delay_secs(secs: i32) {
+    config::set_int(CONFIG_GROUP, "delay", secs);
+}
+
+pub fn copy_to_clipboard() -> bool {
+    config::get_bool(CONFIG_GROUP, "copy-to-clipboard", true)
+}
+
+pub fn set_copy_to_clipboard(enabled: bool) {
+    config::set_bool(CONFIG_GROUP, "copy-to-clipboard", enabled);
+}
+
+// XDG_PICTURES_DIR/Screenshots
+pub fn default_directory() -> String {
+    glib::user_special_dir(glib::UserDirectory::Pictures)
+        .unwrap_or_else(|| glib::home_dir().join("Pictures"))
+        .join("Screenshots")
+        .to_string_lossy()
+        .to_string()
+}
+
+// Save directory as entered, before `~` and variable expansion
+pub fn directory() -> String {
+    config::get_string(CONFIG_GROUP, "directory", &default_directory())
+}
+
+pub fn set_directory(directory: &str) {
+    config::set_string(CONFIG_GROUP, "directory", directory);
+}
+
+pub fn template() -> String {
+    config::get_string(CONFIG_GROUP, "template", DEFAULT_TEMPLATE)
+}
+
+pub fn set_template(template: &str) {
+    config::set_string(CONFIG_GROUP, "template", template);
+}
+
+// File name for a template: strftime fields plus {mode}, always a .png
+pub fn render_template<Tz>(template: &str, mode: CaptureMode, time: &chrono::DateTime<Tz>) -> Result<String, String>
+where
+    Tz: chrono::TimeZone,
+    Tz::Offset: std::fmt::Display,
+{
+    let template = template.trim().replace("{mode}", mode.key());
+    if template.is_empty() {
+        return Err("The file name template is empty".to_string());
+    }
+
+    // Formatting an invalid field panics, so reject the template up front
+    if StrftimeItems::new(&template).any(|item| matches!(item, Item::Error)) {
+        return Err(format!("Invalid % field in template \"{}\"", template));
+    }
+
+    // Keep everything in the save directory
+    let name = time.format(&template).to_string().replace('/', "-");
+    if name.ends_with(".png") {
+        Ok(name)
+    } else {
+        Ok(format!("{}.png", name))
+    }
+}
+
+// Append -2, -3, … when a screenshot with the same name exists
+fn unique_path(dir: &Path, file_name: &str) -> PathBuf {
+    let path = dir.join(file_name);
+    if !path.exists() {
+        return path;
+    }
+
+    let stem = file_name.trim_end_matches(".png");
+    (2..)
+        .map(|n| dir.join(format!("{}-{}.png", stem, n)))
+        .find(|candidate| !candidate.exists())
+        .unwrap_or(path)
+}
+
+// What grim captures
+enum Target {
+    AllOutputs,
+    Geometry(String),
+    Output(String),
+}
+
+// Run slurp, optionally offering predefined boxes; None if cancelled
+fn slurp(args: &[&str], boxes: Option<&[String]>) -> Result<Option<String>, String> {
+    let mut child = Command::new("slurp")
+        .args(args)
+        .stdin(if boxes.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to run slurp: {}", e))?;
+
+    if let (Some(boxes), Some(mut stdin)) = (boxes, child.stdin.take()) {
+        let _ = stdin.write_all(boxes.join("\n").as_bytes());
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to run slurp: {}", e))?;
+    let selection = String::from_utf8_lossy(&output.stdout).trim().to_string();
+
+    Ok((output.status.success() && !selection.is_empty()).then_some(selection))
+}
+
+fn select_target(mode: CaptureMode) -> Result<Option<Target>, String> {
+    match mode {
+        CaptureMode::Screen => Ok(Some(Target::AllOutputs)),
+        CaptureMode::Region => Ok(slurp(&[], None)?.map(Target::Geometry)),
+        CaptureMode::Window => {
+            let windows = hyprland::visible_window_geometries();
+            if windows.is_empty() {
+                return Err("No windows to capture".to_string());
+            }
+            // -r restricts the selection to the offered boxes
+            Ok(slurp(&["-r"], Some(&windows))?.map(Target::Geometry))
+        }
+        CaptureMode::Output => Ok(slurp(&["-o", "-r", "-f", "%o"], None)?.map(Target::Output)),
+    }
+}
+
+// Blocking: select, wait out the delay and capture. None if the selection
+// was cancelled.
+pub fn capture(mode: CaptureMode, delay: Duration, dir: &Path, template: &str) -> CaptureResult {
+    let Some(target) = select_target(mode)? else {
+        return Ok(None);
+    };
+
+    thread::sleep(delay);
+
+    fs::create_dir_all(dir).map_err(|e| format!("Cannot create {}: {}", dir.display(), e))?;
+    let path = unique_path(dir, &render_template(template, mode, &chrono::Local::now())?);
+
+    let mut command = Command::new("grim");
+    match &target {
+        Target::AllOutputs => {}
+        Target::Geometry(geometry) => {
+            command.arg("-g").arg(geometry);
+        }
+        Target::Output(output) => {
+            command.arg("-o").arg(output);
+        }
+    }
+
+    let output = command
+        .arg(&path)
+        .output()
+        .map_err(|e| format!("Failed to run grim: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!("grim failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    Ok(Some(path))
+}
+
+fn is_installed(program: &str) -> bool {
+    Command::new("which")
+        .arg(program)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+// satty if installed, else swappy
+fn annotator() -> Option<&'static str> {
+    ["satty", "swappy"].into_iter().find(|program| is_installed(program))
+}
+
+// Open the annotation tool, saving over the original
+fn annotate(path: &Path) -> Result<(), String> {
+    let program = annotator().ok_or("Neither satty nor swappy is installed")?;
+
+    let mut command = Command::new(program);
+    match program {
+        "satty" => command.arg("--filename").arg(path).arg("--output-filename").arg(path),
+        _ => command.arg("-f").arg(path).arg("-o").arg(path),
+    };
+
+    command
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to run {}: {}", program, e))
+}
+
+// Screenshots and their modification times
+type Listing = Vec<(PathBuf, SystemTime)>;
+
+// Screenshots in a directory, newest first
+fn list_screenshots(dir: &Path) -> Listing {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut files: Listing = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            matches!(
+                entry.path().extension().and_then(|ext| ext.to_str()),
+                Some("png") | Some("jpg") | Some("jpeg")
+            )
+        })
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((entry.path(), modified))
+        })
+        .collect();
+
+    files.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+    files.truncate(GALLERY_LIMIT);
+    files
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn setting_row<W: IsA<gtk::Widget>>(label_text: &str, widget: &W) -> Box {
+    let row = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(12)
+        .build();
+
+    let label = Label::builder()
+        .label(label_text)
+        .halign(gtk::Align::Start)
+        .hexpand(true)
+        .build();
+
+    row.append(&label);
+    row.append(widget);
+    row
+}
+
+type Refresh = Rc<dyn Fn()>;
+type WeakRefresh = Weak<dyn Fn()>;
+// Saved path, None if the selection was cancelled
+type CaptureResult = Result<Option<PathBuf>, String>;
+
+pub fn create_section() -> Box {
+    let section = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(8)
+        .build();
+
+    // Capture mode
+    let mode_labels: Vec<&str> = CaptureMode::ALL.iter().map(|mode| mode.label()).collect();
+    let mode_dropdown = gtk::DropDown::from_strings(&mode_labels);
+    let current_mode = selected_mode();
+    mode_dropdown.set_selected(CaptureMode::ALL.iter().position(|mode| *mode == current_mode).unwrap_or(0) as u32);
+    mode_dropdown.connect_selected_notify(|dropdown| {
+        if let Some(mode) = CaptureMode::ALL.get(dropdown.selected() as usize) {
+            set_selected_mode(*mode);
+        }
+    });
+    section.append(&setting_row("Capture", &mode_dropdown));
+
+    // Delay
+    let delay_spin = gtk::SpinButton::with_range(0.0, 30.0, 1.0);
+    delay_spin.set_value(delay_secs() as f64);
+    delay_spin.set_tooltip_text(Some("Seconds to wait before capturing"));
+    delay_spin.connect_value_changed(|spin| set_delay_secs(spin.value_as_int()));
+    section.append(&setting_row("Delay (seconds)", &delay_spin));
+
+    // Clipboard
+    let clipboard_switch = gtk::Switch::builder()
+        .active(copy_to_clipboard())
+        .valign(gtk::Align::Center)
+        .build();
+    clipboard_switch.connect_active_notify(|switch| set_copy_to_clipboard(switch.is_active()));
+    section.append(&setting_row("Copy to clipboard", &clipboard_switch));
+
+    // Save directory
+    let dir_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(6)
+        .build();
+
+    let dir_entry = gtk::Entry::builder()
+        .text(directory())
+        .width_chars(32)
+        .tooltip_text("~ and $VARIABLES are expanded")
+        .build();
+
+    let browse_btn = Button::builder()
+        .icon_name("folder-open-symbolic")
+        .tooltip_text("Choose a folder")
+        .build();
+
+    dir_box.append(&dir_entry);
+    dir_box.append(&browse_btn);
+    section.append(&setting_row("Save to", &dir_box));
+
+    dir_entry.connect_changed(|entry| set_directory(&entry.text()));
+
+    {
+        let dir_entry_clone = dir_entry.clone();
+        browse_btn.connect_clicked(move |button| {
+            let parent = button
+                .root()
+                .and_then(|root| root.downcast::<gtk::Window>().ok());
+
+            let dialog = gtk::FileDialog::builder()
+                .title("Choose Screenshot Directory")
+                .modal(true)
+                .build();
+
+            let current = PathBuf::from(paths::expand(&dir_entry_clone.text()));
+            if current.is_dir() {
+                dialog.set_initial_folder(Some(&gio::File::for_path(&current)));
+            }
+
+            let dir_entry_clone = dir_entry_clone.clone();
+            dialog.select_folder(parent.as_ref(), gio::Cancellable::NONE, move |result| {
+                if let Ok(folder) = result
+                    && let Some(path) = folder.path()
+                {
+                    dir_entry_clone.set_text(&path.to_string_lossy());
+                }
+            });
+        });
+    }
+
+    // File name template with a live preview
+    let template_entry = gtk::Entry::builder()
+        .text(template())
+        .width_chars(32)
+        .tooltip_text("strftime fields such as %Y-%m-%d, plus {mode}")
+        .build();
+    section.append(&setting_row("File name", &template_entry));
+
+    let template_preview = Label::builder()
+        .halign(gtk::Align::End)
+        .css_classes(["dim-label"])
+        .build();
+    section.append(&template_preview);
+
+    let update_preview = {
+        let template_preview = template_preview.clone();
+        move |template: &str| match render_template(template, selected_mode(), &chrono::Local::now()) {
+            Ok(name) => {
+                template_preview.set_text(&name);
+                template_preview.remove_css_class("error");
+            }
+            Err(e) => {
+                template_preview.set_text(&e);
+                template_preview.add_css_class("error");
+            }
+        }
+    };
+    update_preview(&template_entry.text());
+
+    template_entry.connect_changed(move |entry| {
+        update_preview(&entry.text());
+        // Only keep templates that produce a file name
+        if render_template(&entry.text(), selected_mode(), &chrono::Local::now()).is_ok() {
+            set_template(&entry.text());
+        }
+    });
+
+    // Capture buttons
+    let button_box = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .margin_top(8)
+        .build();
+
+    let capture_btn = Button::builder()
+        .label("Take Screenshot")
+        .css_classes(["suggested-action"])
+        .build();
+
+    let open_folder_btn = Button::builder()
+        .label("Open Folder")
+        .build();
+
+    button_box.append(&capture_btn);
+    button_box.append(&open_folder_btn);
+    section.append(&button_box);
+
+    let status_label = Label::builder()
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .css_classes(["dim-label"])
+        .build();
+    section.append(&status_label);
+
+    open_folder_btn.connect_clicked(|_| {
+        let dir = paths::expand(&directory());
+        if let Err(e) = fs::create_dir_all(&dir) {
+            eprintln!("Error creating directory {}: {}", dir, e);
+            return;
+        }
+        apps::open(Path::new(&dir));
+    });
+
+    // Gallery
+    let gallery_header = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(8)
+        .margin_top(16)
+        .build();
+
+    let gallery_label = Label::builder()
+        .label("Recent screenshots:")
+        .halign(gtk::Align::Start)
+        .hexpand(true)
+        .build();
+
+    let refresh_btn = Button::builder()
+        .icon_name("view-refresh-symbolic")
+        .tooltip_text("Refresh gallery")
+        .build();
+
+    gallery_header.append(&gallery_label);
+    gallery_header.append(&refresh_btn);
+    section.append(&gallery_header);
+
+    let gallery = gtk::FlowBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .homogeneous(true)
+        .max_children_per_line(6)
+        .column_spacing(8)
+        .row_spacing(8)
+        .build();
+
+    let gallery_scroll = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Never)
+        .min_content_height(280)
+        .vexpand(true)
+        .child(&gallery)
+        .build();
+    section.append(&gallery_scroll);
+
+    // Rebuild only when the directory listing changed
+    let shown: Rc<RefCell<Option<Listing>>> = Rc::new(RefCell::new(None));
+    // Thumbnails refresh the gallery after a delete; they hold it weakly
+    // because the gallery owns them
+    let refresh_slot: Rc<RefCell<Option<WeakRefresh>>> = Rc::new(RefCell::new(None));
+    let refresh_gallery: Refresh = {
+        let gallery = gallery.clone();
+        let shown = shown.clone();
+        let refresh_slot = refresh_slot.clone();
+        Rc::new(move || {
+            let files = list_screenshots(Path::new(&paths::expand(&directory())));
+            if shown.borrow().as_ref() == Some(&files) {
+                return;
+            }
+
+            while let Some(child) = gallery.first_child() {
+                gallery.remove(&child);
+            }
+
+            if files.is_empty() {
+                gallery.append(
+                    &Label::builder()
+                        .label("No screenshots yet")
+                        .css_classes(["dim-label"])
+                        .build(),
+                );
+            }
+
+            let refresh = refresh_slot.borrow().clone();
+            for (path, _) in &files {
+                gallery.append(&create_thumbnail(path, refresh.clone()));
+            }
+
+            *shown.borrow_mut() = Some(files);
+        })
+    };
+    *refresh_slot.borrow_mut() = Some(Rc::downgrade(&refresh_gallery));
+
+    {
+        let refresh_gallery = refresh_gallery.clone();
+        refresh_btn.connect_clicked(move |_| refresh_gallery());
+    }
+
+    // Pick up edits from the annotation tool and files added elsewhere, but
+    // only poll while the gallery is on screen
+    let poll: Rc<RefCell<Option<glib::SourceId>>> = Rc::new(RefCell::new(None));
+    {
+        let refresh_gallery = Rc::downgrade(&refresh_gallery);
+        let poll = poll.clone();
+        gallery.connect_map(move |_| {
+            let refresh_gallery = refresh_gallery.clone();
+            let source = glib::timeout_add_seconds_local(2, move || match refresh_gallery.upgrade() {
+                Some(refresh_gallery) => {
+                    refresh_gallery();
+                    glib::ControlFlow::Continue
+                }
+                None => glib::ControlFlow::Break,
+            });
+            if let Some(previous) = poll.borrow_mut().replace(source) {
+                previous.remove();
+            }
+        });
+    }
+    gallery.connect_unmap(move |_| {
+        if let Some(source) = poll.borrow_mut().take() {
+            source.remove();
+        }
+    });
+
+    {
+        let refresh_gallery = refresh_gallery.clone();
+        let status_label = status_label.clone();
+        capture_btn.connect_clicked(move |button| {
+            let mode = selected_mode();
+            let delay = Duration::from_secs(delay_secs().max(0) as u64);
+            let dir = PathBuf::from(paths::expand(&directory()));
+            let template = template();
+            let copy = copy_to_clipboard();
+
+            // Keep this window out of the picture
+            let window = button
+                .root()
+                .and_then(|root| root.downcast::<gtk::Window>().ok());
+            if let Some(window) = &window {
+                window.set_visible(false);
+            }
+            button.set_sensitive(false);
+            status_label.set_text("Capturing…");
+
+            let result: Arc<Mutex<Option<CaptureResult>>> = Arc::new(Mutex::new(None));
+            {
+                let result = result.clone();
+                thread::spawn(move || {
+                    thread::sleep(HIDE_DELAY);
+                    let captured = capture(mode, delay, &dir, &template).and_then(|path| {
+                        if copy && let Some(path) = &path {
+                            clipboard::copy_image(path)?;
+                        }
+                        Ok(path)
+                    });
+                    *result.lock().unwrap() = Some(captured);
+                });
+            }
+
+            let button = button.clone();
+            let status_label = status_label.clone();
+            let refresh_gallery = refresh_gallery.clone();
+            glib::timeout_add_local(Duration::from_millis(100), move || {
+                let Some(captured) = result.lock().unwrap().take() else {
+                    return glib::ControlFlow::Continue;
+                };
+
+                if let Some(window) = &window {
+                    window.present();
+                }
+                button.set_sensitive(true);
+
+                match captured {
+                    Ok(Some(path)) => {
+                        let path = path.to_string_lossy().to_string();
+                        println!("Screenshot saved: {}", path);
+                        status_label.set_text(&format!("Saved {}", path));
+                        notifications::notify(
+                            NotifyEvent::ScreenshotSaved,
+                            "Screenshot saved",
+                            &path,
+                            &[NotifyAction::OpenFile(path.clone())],
+                        );
+                        refresh_gallery();
+                    }
+                    Ok(None) => status_label.set_text("Screenshot cancelled"),
+                    Err(e) => {
+                        eprintln!("Screenshot failed: {}", e);
+                        status_label.set_text(&format!("Screenshot failed: {}", e));
+                        notifications::notify(NotifyEvent::ScreenshotFailed, "Screenshot failed", &e, &[]);
+                    }
+                }
+                glib::ControlFlow::Break
+            });
+        });
+    }
+
+    refresh_gallery();
+    section
+}
+
+fn create_thumbnail(path: &Path, refresh: Option<WeakRefresh>) -> Box {
+    let card = Box::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(4)
+        .build();
+
+    // Scale while decoding so large screenshots stay cheap
+    let picture = gtk::Picture::builder()
+        .content_fit(gtk::ContentFit::Cover)
+        .can_shrink(true)
+        .width_request(176)
+        .height_request(110)
+        .build();
+    match gdk_pixbuf::Pixbuf::from_file_at_scale(path, 352, 220, true) {
+        Ok(pixbuf) => picture.set_paintable(Some(&gdk::Texture::for_pixbuf(&pixbuf))),
+        Err(e) => eprintln!("Failed to load thumbnail for {}: {}", path.display(), e),
+    }
+
+    let name_label = Label::builder()
+        .label(file_name(path))
+        .ellipsize(gtk::pango::EllipsizeMode::Middle)
+        .max_width_chars(20)
+        .tooltip_text(path.to_string_lossy())
+        .build();
+
+    let actions = Box::builder()
+        .orientation(Orientation::Horizontal)
+        .spacing(4)
+        .halign(gtk::Align::Center)
+        .build();
+
+    let open_btn = Button::builder()
+        .icon_name("document-open-symbolic")
+        .tooltip_text("Open")
+        .build();
+
+    let annotate_btn = Button::builder()
+        .icon_name("document-edit-symbolic")
+        .tooltip_text("Annotate with satty or swappy")
+        .build();
+
+    let delete_btn = Button::builder()
+        .icon_name("user-trash-symbolic")
+        .tooltip_text("Delete")
+        .css_classes(["destructive-action"])
+        .build();
+
+    actions.append(&open_btn);
+    actions.append(&annotate_btn);
+    actions.append(&delete_btn);
+    card.append(&picture);
+    card.append(&name_label);
+    card.append(&actions);
+
+    {
+        let path = path.to_path_buf();
+        open_btn.connect_clicked(move |_| apps::open(&path));
+    }
+
+    {
+        let path = path.to_path_buf();
+        annotate_btn.connect_clicked(move |_| {
+            if let Err(e) = annotate(&path) {
+                eprintln!("{}", e);
+                notifications::notify(NotifyEvent::ScreenshotFailed, "Cannot annotate screenshot", &e, &[]);
+            }
+        });
+    }
+
+    {
+        let path = path.to_path_buf();
+        delete_btn.connect_clicked(move |button| {
+            let parent = button
+                .root()
+                .and_then(|root| root.downcast::<gtk::Window>().ok());
+
+            let dialog = gtk::AlertDialog::builder()
+                .message(format!("Delete {}?", file_name(&path)))
+                .detail("The screenshot will be moved to the trash.")
+                .buttons(["Cancel", "Delete"])
+                .cancel_button(0)
+                .default_button(0)
+                .modal(true)
+                .build();
+
+            let path = path.clone();
+            let refresh = refresh.clone();
+            dialog.choose(parent.as_ref(), gio::Cancellable::NONE, move |response| {
+                if response != Ok(1) {
+                    return;
+                }
+                match gio::File::for_path(&path).trash(gio::Cancellable::NONE) {
+                    Ok(()) => println!("Deleted screenshot: {}", path.display()),
+                    Err(e) => eprintln!("Failed to delete {}: {}", path.display(), e),
+                }
+                if let Some(refresh) = refresh.as_ref().and_then(Weak::upgrade) {
+                    refresh();
+                }
+            });
+        });
+    }
+
+    card
+}
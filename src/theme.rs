@@ -0,0 +1,304 @@
+use gtk::prelude::*;
+use gtk::{gio, glib};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Light/dark colour scheme: org.gnome.desktop.interface in GSettings for
+// GTK 4 and portal-aware apps, plus gtk-application-prefer-dark-theme in the
+// GTK 3/4 settings.ini files for everything else.
+
+const INTERFACE_SCHEMA: &str = "org.gnome.desktop.interface";
+const DARK_SUFFIX: &str = "-dark";
+// GTK only reads the [Settings] group, so our own group can live alongside
+const THEME_GROUP: &str = "ummit-settings";
+
+// None when the schema is not installed (gio::Settings::new would abort)
+fn interface_settings() -> Option<gio::Settings> {
+    let schema = gio::SettingsSchemaSource::default()?.lookup(INTERFACE_SCHEMA, true)?;
+    Some(gio::Settings::new_full(&schema, None::<&gio::SettingsBackend>, None))
+}
+
+fn has_key(settings: &gio::Settings, key: &str) -> bool {
+    settings
+        .settings_schema()
+        .is_some_and(|schema| schema.has_key(key))
+}
+
+fn settings_ini_files(config_dir: &Path) -> Vec<PathBuf> {
+    vec![
+        config_dir.join("gtk-3.0").join("settings.ini"),
+        config_dir.join("gtk-4.0").join("settings.ini"),
+    ]
+}
+
+fn theme_exists(name: &str) -> bool {
+    let mut dirs = vec![glib::home_dir().join(".themes"), glib::user_data_dir().join("themes")];
+    dirs.extend(glib::system_data_dirs().into_iter().map(|dir| dir.join("themes")));
+    dirs.iter().any(|dir| dir.join(name).is_dir())
+}
+
+// Dark or light variant of a GTK theme name. Adwaita-dark is built into
+// GTK 3; other themes need an installed "-dark" variant.
+fn theme_variant(current: &str, dark: bool) -> String {
+    let base = current.strip_suffix(DARK_SUFFIX).unwrap_or(current);
+    if !dark {
+        return base.to_string();
+    }
+
+    let candidate = format!("{}{}", base, DARK_SUFFIX);
+    if base == "Adwaita" || theme_exists(&candidate) {
+        candidate
+    } else {
+        "Adwaita-dark".to_string()
+    }
+}
+
+// Theme to go back to when switching to light: the one that was in use
+// before we switched to dark, as long as the dark theme is still ours
+fn light_theme(config_dir: &Path, current: &str) -> String {
+    settings_ini_files(config_dir)
+        .iter()
+        .find_map(|path| {
+            let key_file = glib::KeyFile::new();
+            key_file.load_from_file(path, glib::KeyFileFlags::NONE).ok()?;
+            key_file.string(THEME_GROUP, "light-theme").ok().map(|theme| theme.to_string())
+        })
+        .filter(|light| theme_variant(light, true) == current)
+        .unwrap_or_else(|| theme_variant(current, false))
+}
+
+// Current state, for the switch on load
+pub fn is_dark() -> bool {
+    is_dark_in(interface_settings().as_ref(), &glib::user_config_dir())
+}
+
+fn is_dark_in(settings: Option<&gio::Settings>, config_dir: &Path) -> bool {
+    if let Some(settings) = settings {
+        if has_key(settings, "color-scheme") {
+            return settings.string("color-scheme") == "prefer-dark";
+        }
+        if has_key(settings, "gtk-theme") {
+            return settings.string("gtk-theme").ends_with(DARK_SUFFIX);
+        }
+    }
+
+    settings_ini_files(config_dir).iter().rev().any(|path| {
+        let key_file = glib::KeyFile::new();
+        key_file.load_from_file(path, glib::KeyFileFlags::NONE).is_ok()
+            && key_file
+                .boolean("Settings", "gtk-application-prefer-dark-theme")
+                .unwrap_or(false)
+    })
+}
+
+fn write_settings_ini(path: &Path, dark: bool, light_theme: Option<&str>) -> Result<(), String> {
+    let key_file = glib::KeyFile::new();
+    if path.exists() {
+        key_file
+            .load_from_file(path, glib::KeyFileFlags::KEEP_COMMENTS)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    }
+
+    key_file.set_boolean("Settings", "gtk-application-prefer-dark-theme", dark);
+    if let Some(theme) = light_theme {
+        key_file.set_string(THEME_GROUP, "light-theme", theme);
+    }
+
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    key_file
+        .save_to_file(path)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+// Switch every place we know of; errors are collected so one read-only file
+// does not stop the rest
+pub fn set_dark(dark: bool) -> Result<(), String> {
+    println!("Setting colour scheme: {}", if dark { "dark" } else { "light" });
+    let result = set_dark_in(interface_settings().as_ref(), &glib::user_config_dir(), dark);
+
+    // Follow the change in our own window right away
+    if let Some(settings) = gtk::Settings::default() {
+        settings.set_gtk_application_prefer_dark_theme(dark);
+    }
+
+    result
+}
+
+fn set_dark_in(settings: Option<&gio::Settings>, config_dir: &Path, dark: bool) -> Result<(), String> {
+    let mut errors = Vec::new();
+    // Remembered when leaving a light theme, so its name survives the switch
+    let mut light = None;
+
+    if let Some(settings) = settings {
+        if has_key(settings, "color-scheme")
+            && let Err(e) = settings.set_string("color-scheme", if dark { "prefer-dark" } else { "default" })
+        {
+            errors.push(format!("Failed to set color-scheme: {}", e));
+        }
+        if has_key(settings, "gtk-theme") {
+            let current = settings.string("gtk-theme");
+            let theme = if dark {
+                if !current.ends_with(DARK_SUFFIX) {
+                    light = Some(current.to_string());
+                }
+                theme_variant(&current, true)
+            } else {
+                light_theme(config_dir, &current)
+            };
+            if let Err(e) = settings.set_string("gtk-theme", &theme) {
+                errors.push(format!("Failed to set gtk-theme: {}", e));
+            }
+        }
+        gio::Settings::sync();
+    }
+
+    for path in settings_ini_files(config_dir) {
+        if let Err(e) = write_settings_ini(&path, dark, light.as_deref()) {
+            errors.push(e);
+        }
+    }
+
+    if errors.is_empty() { Ok(()) } else { Err(errors.join("\n")) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    // The keys we touch, plus a variant without color-scheme like older
+    // gsettings-desktop-schemas
+    const SCHEMAS: &str = r#"<schemalist>
+  <schema id="org.gnome.desktop.interface" path="/org/gnome/desktop/interface/">
+    <key name="color-scheme" type="s"><default>'default'</default></key>
+    <key name="gtk-theme" type="s"><default>'Adwaita'</default></key>
+  </schema>
+  <schema id="org.gnome.desktop.interface.legacy" path="/org/gnome/desktop/interface-legacy/">
+    <key name="gtk-theme" type="s"><default>'Adwaita'</default></key>
+  </schema>
+</schemalist>
+"#;
+
+    // Stands in for $XDG_CONFIG_HOME
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ummit-theme-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn memory_settings(dir: &Path, schema_id: &str) -> gio::Settings {
+        let schema_dir = dir.join("schemas");
+        fs::create_dir_all(&schema_dir).unwrap();
+        fs::write(schema_dir.join("ummit-test.gschema.xml"), SCHEMAS).unwrap();
+        let status = Command::new("glib-compile-schemas")
+            .arg(&schema_dir)
+            .status()
+            .expect("glib-compile-schemas is needed to run these tests");
+        assert!(status.success());
+
+        let source = gio::SettingsSchemaSource::from_directory(&schema_dir, None, false).unwrap();
+        let schema = source.lookup(schema_id, false).unwrap();
+        gio::Settings::new_full(&schema, Some(&gio::memory_settings_backend_new()), None)
+    }
+
+    fn ini_value(config_dir: &Path, gtk: &str, group: &str, key: &str) -> Option<String> {
+        let key_file = glib::KeyFile::new();
+        key_file
+            .load_from_file(config_dir.join(gtk).join("settings.ini"), glib::KeyFileFlags::NONE)
+            .ok()?;
+        key_file.value(group, key).ok().map(|value| value.to_string())
+    }
+
+    #[test]
+    fn switches_color_scheme_and_settings_ini() {
+        let dir = scratch_dir("color-scheme");
+        let settings = memory_settings(&dir, INTERFACE_SCHEMA);
+        assert!(!is_dark_in(Some(&settings), &dir));
+
+        set_dark_in(Some(&settings), &dir, true).unwrap();
+        assert!(is_dark_in(Some(&settings), &dir));
+        assert_eq!(settings.string("color-scheme"), "prefer-dark");
+        assert_eq!(settings.string("gtk-theme"), "Adwaita-dark");
+        for gtk in ["gtk-3.0", "gtk-4.0"] {
+            assert_eq!(
+                ini_value(&dir, gtk, "Settings", "gtk-application-prefer-dark-theme").as_deref(),
+                Some("true")
+            );
+        }
+
+        set_dark_in(Some(&settings), &dir, false).unwrap();
+        assert!(!is_dark_in(Some(&settings), &dir));
+        assert_eq!(settings.string("color-scheme"), "default");
+        assert_eq!(settings.string("gtk-theme"), "Adwaita");
+        assert_eq!(
+            ini_value(&dir, "gtk-4.0", "Settings", "gtk-application-prefer-dark-theme").as_deref(),
+            Some("false")
+        );
+    }
+
+    #[test]
+    fn reads_gtk_theme_without_color_scheme() {
+        let dir = scratch_dir("legacy");
+        let settings = memory_settings(&dir, "org.gnome.desktop.interface.legacy");
+
+        set_dark_in(Some(&settings), &dir, true).unwrap();
+        assert_eq!(settings.string("gtk-theme"), "Adwaita-dark");
+        assert!(is_dark_in(Some(&settings), &dir));
+
+        set_dark_in(Some(&settings), &dir, false).unwrap();
+        assert_eq!(settings.string("gtk-theme"), "Adwaita");
+        assert!(!is_dark_in(Some(&settings), &dir));
+    }
+
+    #[test]
+    fn restores_theme_without_dark_variant() {
+        let dir = scratch_dir("restore");
+        let settings = memory_settings(&dir, INTERFACE_SCHEMA);
+        settings.set_string("gtk-theme", "UmmitTestTheme").unwrap();
+
+        set_dark_in(Some(&settings), &dir, true).unwrap();
+        assert_eq!(settings.string("gtk-theme"), "Adwaita-dark");
+        assert_eq!(
+            ini_value(&dir, "gtk-4.0", THEME_GROUP, "light-theme").as_deref(),
+            Some("UmmitTestTheme")
+        );
+
+        set_dark_in(Some(&settings), &dir, false).unwrap();
+        assert_eq!(settings.string("gtk-theme"), "UmmitTestTheme");
+    }
+
+    #[test]
+    fn ignores_remembered_theme_after_outside_change() {
+        let dir = scratch_dir("outside-change");
+        let settings = memory_settings(&dir, INTERFACE_SCHEMA);
+        settings.set_string("gtk-theme", "UmmitTestTheme").unwrap();
+        set_dark_in(Some(&settings), &dir, true).unwrap();
+
+        // Another tool picked a different dark theme in the meantime
+        settings.set_string("gtk-theme", "Other-dark").unwrap();
+        set_dark_in(Some(&settings), &dir, false).unwrap();
+        assert_eq!(settings.string("gtk-theme"), "Other");
+    }
+
+    #[test]
+    fn falls_back_to_settings_ini_and_keeps_other_keys() {
+        let dir = scratch_dir("ini-only");
+        fs::create_dir_all(dir.join("gtk-3.0")).unwrap();
+        fs::write(
+            dir.join("gtk-3.0").join("settings.ini"),
+            "# mine\n[Settings]\ngtk-font-name=Sans 11\n",
+        )
+        .unwrap();
+        assert!(!is_dark_in(None, &dir));
+
+        set_dark_in(None, &dir, true).unwrap();
+        assert!(is_dark_in(None, &dir));
+        assert_eq!(ini_value(&dir, "gtk-3.0", "Settings", "gtk-font-name").as_deref(), Some("Sans 11"));
+        assert!(fs::read_to_string(dir.join("gtk-3.0").join("settings.ini")).unwrap().contains("# mine"));
+        // No GSettings theme was replaced, so there is nothing to remember
+        assert_eq!(ini_value(&dir, "gtk-4.0", THEME_GROUP, "light-theme"), None);
+    }
+}
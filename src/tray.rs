@@ -0,0 +1,455 @@
+use gtk::prelude::*;
+use gtk::{gio, glib};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use crate::recorder;
+use crate::recording::{self, RecordingSession};
+
+// StatusNotifierItem tray icon shown while a recording is running, with a
+// com.canonical.dbusmenu menu (Stop, Pause/Resume, Open folder)
+
+const ITEM_PATH: &str = "/StatusNotifierItem";
+const MENU_PATH: &str = "/MenuBar";
+const ITEM_INTERFACE: &str = "org.kde.StatusNotifierItem";
+const MENU_INTERFACE: &str = "com.canonical.dbusmenu";
+
+const INTROSPECTION_XML: &str = r#"
+<node>
+  <interface name="org.kde.StatusNotifierItem">
+    <property name="Category" type="s" access="read"/>
+    <property name="Id" type="s" access="read"/>
+    <property name="Title" type="s" access="read"/>
+    <property name="Status" type="s" access="read"/>
+    <property name="WindowId" type="i" access="read"/>
+    <property name="IconName" type="s" access="read"/>
+    <property name="IconThemePath" type="s" access="read"/>
+    <property name="ToolTip" type="(sa(iiay)ss)" access="read"/>
+    <property name="ItemIsMenu" type="b" access="read"/>
+    <property name="Menu" type="o" access="read"/>
+    <method name="ContextMenu"><arg name="x" type="i" direction="in"/><arg name="y" type="i" direction="in"/></method>
+    <method name="Activate"><arg name="x" type="i" direction="in"/><arg name="y" type="i" direction="in"/></method>
+    <method name="SecondaryActivate"><arg name="x" type="i" direction="in"/><arg name="y" type="i" direction="in"/></method>
+    <method name="Scroll"><arg name="delta" type="i" direction="in"/><arg name="orientation" type="s" direction="in"/></method>
+    <signal name="NewTitle"/>
+    <signal name="NewIcon"/>
+    <signal name="NewToolTip"/>
+    <signal name="NewStatus"><arg name="status" type="s"/></signal>
+  </interface>
+  <interface name="com.canonical.dbusmenu">
+    <property name="Version" type="u" access="read"/>
+    <property name="TextDirection" type="s" access="read"/>
+    <property name="Status" type="s" access="read"/>
+    <property name="IconThemePath" type="as" access="read"/>
+    <method name="GetLayout">
+      <arg name="parentId" type="i" direction="in"/>
+      <arg name="recursionDepth" type="i" direction="in"/>
+      <arg name="propertyNames" type="as" direction="in"/>
+      <arg name="revision" type="u" direction="out"/>
+      <arg name="layout" type="(ia{sv}av)" direction="out"/>
+    </method>
+    <method name="GetGroupProperties">
+      <arg name="ids" type="ai" direction="in"/>
+      <arg name="propertyNames" type="as" direction="in"/>
+      <arg name="properties" type="a(ia{sv})" direction="out"/>
+    </method>
+    <method name="GetProperty">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="name" type="s" direction="in"/>
+      <arg name="value" type="v" direction="out"/>
+    </method>
+    <method name="Event">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="eventId" type="s" direction="in"/>
+      <arg name="data" type="v" direction="in"/>
+      <arg name="timestamp" type="u" direction="in"/>
+    </method>
+    <method name="EventGroup">
+      <arg name="events" type="a(isvu)" direction="in"/>
+      <arg name="idErrors" type="ai" direction="out"/>
+    </method>
+    <method name="AboutToShow">
+      <arg name="id" type="i" direction="in"/>
+      <arg name="needUpdate" type="b" direction="out"/>
+    </method>
+    <method name="AboutToShowGroup">
+      <arg name="ids" type="ai" direction="in"/>
+      <arg name="updatesNeeded" type="ai" direction="out"/>
+      <arg name="idErrors" type="ai" direction="out"/>
+    </method>
+    <signal name="ItemsPropertiesUpdated">
+      <arg name="updatedProps" type="a(ia{sv})"/>
+      <arg name="removedProps" type="a(ias)"/>
+    </signal>
+    <signal name="LayoutUpdated">
+      <arg name="revision" type="u"/>
+      <arg name="parent" type="i"/>
+    </signal>
+  </interface>
+</node>
+"#;
+
+// Menu item ids
+const MENU_STOP: i32 = 1;
+const MENU_PAUSE: i32 = 2;
+const MENU_SEPARATOR: i32 = 3;
+const MENU_OPEN_FOLDER: i32 = 4;
+const MENU_SHOW_SETTINGS: i32 = 5;
+
+struct TrayHandle {
+    connection: gio::DBusConnection,
+    registrations: Vec<gio::RegistrationId>,
+    owner_id: gio::OwnerId,
+    timer: glib::SourceId,
+}
+
+thread_local! {
+    static TRAY: RefCell<Option<TrayHandle>> = const { RefCell::new(None) };
+    static MENU_REVISION: Cell<u32> = const { Cell::new(1) };
+    static HOLD_GUARD: RefCell<Option<gio::ApplicationHoldGuard>> = const { RefCell::new(None) };
+}
+
+// Show the tray icon and keep the application alive while recording
+pub fn install(app: &gtk::Application) {
+    let app = app.clone();
+
+    recording::subscribe(move |session| match session {
+        Some(_) => {
+            HOLD_GUARD.with(|guard| {
+                if guard.borrow().is_none() {
+                    *guard.borrow_mut() = Some(app.hold());
+                }
+            });
+
+            if TRAY.with(|tray| tray.borrow().is_none()) {
+                show();
+            } else {
+                refresh();
+            }
+        }
+        None => {
+            hide();
+            HOLD_GUARD.with(|guard| guard.borrow_mut().take());
+        }
+    })
+    .keep();
+}
+
+fn show() {
+    let connection = match gio::bus_get_sync(gio::BusType::Session, None::<&gio::Cancellable>) {
+        Ok(connection) => connection,
+        Err(e) => {
+            eprintln!("Cannot show tray icon, session bus unavailable: {}", e);
+            return;
+        }
+    };
+
+    let node_info = gio::DBusNodeInfo::for_xml(INTROSPECTION_XML).expect("Invalid tray introspection XML");
+    let item_interface = node_info.lookup_interface(ITEM_INTERFACE).expect("Missing StatusNotifierItem interface");
+    let menu_interface = node_info.lookup_interface(MENU_INTERFACE).expect("Missing dbusmenu interface");
+
+    let mut registrations = Vec::new();
+
+    match connection
+        .register_object(ITEM_PATH, &item_interface)
+        .method_call(|_, _, _, _, method, _, invocation| {
+            match method {
+                "Activate" | "SecondaryActivate" => show_settings(),
+                _ => {}
+            }
+            invocation.return_value(None);
+        })
+        .property(|_, _, _, _, property| item_property(property))
+        .build()
+    {
+        Ok(id) => registrations.push(id),
+        Err(e) => {
+            eprintln!("Failed to export tray icon: {}", e);
+            return;
+        }
+    }
+
+    match connection
+        .register_object(MENU_PATH, &menu_interface)
+        .method_call(|_, _, _, _, method, parameters, invocation| {
+            handle_menu_call(method, &parameters, invocation);
+        })
+        .property(|_, _, _, _, property| menu_property(property))
+        .build()
+    {
+        Ok(id) => registrations.push(id),
+        Err(e) => eprintln!("Failed to export tray menu: {}", e),
+    }
+
+    let bus_name = format!("org.kde.StatusNotifierItem-{}-1", std::process::id());
+    let owner_id = gio::bus_own_name_on_connection(
+        &connection,
+        &bus_name,
+        gio::BusNameOwnerFlags::NONE,
+        |connection, name| register_with_watcher(&connection, name),
+        |_, name| eprintln!("Lost tray bus name {}", name),
+    );
+
+    // Keep the elapsed time in the tooltip current
+    let timer = {
+        let connection = connection.clone();
+        glib::timeout_add_seconds_local(1, move || {
+            emit_item_signal(&connection, "NewToolTip", None);
+            glib::ControlFlow::Continue
+        })
+    };
+
+    TRAY.with(|tray| {
+        *tray.borrow_mut() = Some(TrayHandle {
+            connection,
+            registrations,
+            owner_id,
+            timer,
+        });
+    });
+
+    println!("Recording tray icon shown");
+}
+
+fn hide() {
+    let Some(handle) = TRAY.with(|tray| tray.borrow_mut().take()) else {
+        return;
+    };
+
+    handle.timer.remove();
+    gio::bus_unown_name(handle.owner_id);
+    for id in handle.registrations {
+        let _ = handle.connection.unregister_object(id);
+    }
+
+    println!("Recording tray icon removed");
+}
+
+// Tell hosts that the icon, tooltip and menu changed (e.g. after pausing)
+fn refresh() {
+    TRAY.with(|tray| {
+        if let Some(handle) = tray.borrow().as_ref() {
+            emit_item_signal(&handle.connection, "NewIcon", None);
+            emit_item_signal(&handle.connection, "NewToolTip", None);
+            emit_item_signal(&handle.connection, "NewStatus", Some(&(item_status(),).to_variant()));
+
+            let revision = MENU_REVISION.with(|revision| {
+                revision.set(revision.get() + 1);
+                revision.get()
+            });
+            let _ = handle.connection.emit_signal(
+                None,
+                MENU_PATH,
+                MENU_INTERFACE,
+                "LayoutUpdated",
+                Some(&(revision, 0i32).to_variant()),
+            );
+        }
+    });
+}
+
+fn register_with_watcher(connection: &gio::DBusConnection, bus_name: &str) {
+    connection.call(
+        Some("org.kde.StatusNotifierWatcher"),
+        "/StatusNotifierWatcher",
+        "org.kde.StatusNotifierWatcher",
+        "RegisterStatusNotifierItem",
+        Some(&(bus_name,).to_variant()),
+        None,
+        gio::DBusCallFlags::NONE,
+        -1,
+        None::<&gio::Cancellable>,
+        |result| {
+            if let Err(e) = result {
+                eprintln!("No StatusNotifierWatcher available, tray icon not shown: {}", e);
+            }
+        },
+    );
+}
+
+fn emit_item_signal(connection: &gio::DBusConnection, signal: &str, parameters: Option<&glib::Variant>) {
+    let _ = connection.emit_signal(None, ITEM_PATH, ITEM_INTERFACE, signal, parameters);
+}
+
+fn item_status() -> &'static str {
+    match recording::current() {
+        Some(session) if session.is_paused() => "Active",
+        Some(_) => "NeedsAttention",
+        None => "Passive",
+    }
+}
+
+fn item_icon(session: Option<&RecordingSession>) -> &'static str {
+    match session {
+        Some(session) if session.is_paused() => "media-playback-pause",
+        _ => "media-record",
+    }
+}
+
+fn item_property(property: &str) -> glib::Variant {
+    let session = recording::current();
+
+    match property {
+        "Category" => "ApplicationStatus".to_variant(),
+        "Id" => "ummit-settings".to_variant(),
+        "Title" => "UmmItOS Screen Recording".to_variant(),
+        "Status" => item_status().to_variant(),
+        "WindowId" => 0i32.to_variant(),
+        "IconName" => item_icon(session.as_ref()).to_variant(),
+        "IconThemePath" => "".to_variant(),
+        "ItemIsMenu" => false.to_variant(),
+        "Menu" => glib::variant::ObjectPath::try_from(MENU_PATH.to_string())
+            .expect("Invalid menu object path")
+            .to_variant(),
+        "ToolTip" => {
+            let (title, description) = match session.as_ref() {
+                Some(session) => {
+                    let state = if session.is_paused() { "Paused" } else { "Recording" };
+                    (
+                        format!("{} {}", state, recording::format_elapsed(session.elapsed())),
+                        session.file_path.clone(),
+                    )
+                }
+                None => ("Not recording".to_string(), String::new()),
+            };
+
+            (
+                item_icon(session.as_ref()),
+                Vec::<(i32, i32, Vec<u8>)>::new(),
+                title,
+                description,
+            )
+                .to_variant()
+        }
+        _ => "".to_variant(),
+    }
+}
+
+fn menu_property(property: &str) -> glib::Variant {
+    match property {
+        "Version" => 3u32.to_variant(),
+        "TextDirection" => "ltr".to_variant(),
+        "Status" => "normal".to_variant(),
+        "IconThemePath" => Vec::<String>::new().to_variant(),
+        _ => "".to_variant(),
+    }
+}
+
+// Properties of a single dbusmenu item
+fn menu_item_properties(id: i32) -> HashMap<String, glib::Variant> {
+    let session = recording::current();
+    let paused = session.as_ref().map(|session| session.is_paused()).unwrap_or(false);
+    let can_pause = session
+        .as_ref()
+        .and_then(|session| recorder::backend_by_name(&session.backend))
+        .is_some_and(|backend| backend.capabilities().pause);
+    let mut properties = HashMap::new();
+
+    let (label, icon) = match id {
+        MENU_STOP => ("Stop recording", "media-playback-stop"),
+        MENU_PAUSE if paused => ("Resume recording", "media-playback-start"),
+        MENU_PAUSE => ("Pause recording", "media-playback-pause"),
+        MENU_OPEN_FOLDER => ("Open recordings folder", "folder-videos"),
+        MENU_SHOW_SETTINGS => ("Show UmmItOS Settings", "preferences-system"),
+        _ => {
+            properties.insert("type".to_string(), "separator".to_variant());
+            return properties;
+        }
+    };
+
+    properties.insert("label".to_string(), label.to_variant());
+    properties.insert("icon-name".to_string(), icon.to_variant());
+    let enabled = match id {
+        MENU_SHOW_SETTINGS => true,
+        MENU_PAUSE => can_pause,
+        _ => session.is_some(),
+    };
+    properties.insert("enabled".to_string(), enabled.to_variant());
+    properties
+}
+
+fn menu_layout() -> glib::Variant {
+    let children: Vec<glib::Variant> = [MENU_STOP, MENU_PAUSE, MENU_SEPARATOR, MENU_OPEN_FOLDER, MENU_SHOW_SETTINGS]
+        .into_iter()
+        .map(|id| (id, menu_item_properties(id), Vec::<glib::Variant>::new()).to_variant())
+        .collect();
+
+    let mut root_properties: HashMap<String, glib::Variant> = HashMap::new();
+    root_properties.insert("children-display".to_string(), "submenu".to_variant());
+
+    (0i32, root_properties, children).to_variant()
+}
+
+fn handle_menu_call(method: &str, parameters: &glib::Variant, invocation: gio::DBusMethodInvocation) {
+    match method {
+        "GetLayout" => {
+            let revision = MENU_REVISION.with(|revision| revision.get());
+            let reply = glib::Variant::tuple_from_iter([revision.to_variant(), menu_layout()]);
+            invocation.return_value(Some(&reply));
+        }
+        "GetGroupProperties" => {
+            let ids = parameters
+                .get::<(Vec<i32>, Vec<String>)>()
+                .map(|(ids, _)| ids)
+                .unwrap_or_default();
+            let properties: Vec<(i32, HashMap<String, glib::Variant>)> =
+                ids.into_iter().map(|id| (id, menu_item_properties(id))).collect();
+            invocation.return_value(Some(&(properties,).to_variant()));
+        }
+        "GetProperty" => {
+            let value = parameters
+                .get::<(i32, String)>()
+                .and_then(|(id, name)| menu_item_properties(id).remove(&name))
+                .unwrap_or_else(|| "".to_variant());
+            invocation.return_value(Some(&glib::Variant::tuple_from_iter([value.to_variant()])));
+        }
+        "Event" => {
+            if let Some((id, event_id, _, _)) = parameters.get::<(i32, String, glib::Variant, u32)>()
+                && event_id == "clicked"
+            {
+                activate_menu_item(id);
+            }
+            invocation.return_value(None);
+        }
+        "EventGroup" => {
+            if let Some((events,)) = parameters.get::<(Vec<(i32, String, glib::Variant, u32)>,)>() {
+                for (id, event_id, _, _) in events {
+                    if event_id == "clicked" {
+                        activate_menu_item(id);
+                    }
+                }
+            }
+            invocation.return_value(Some(&(Vec::<i32>::new(),).to_variant()));
+        }
+        "AboutToShow" => {
+            invocation.return_value(Some(&(false,).to_variant()));
+        }
+        "AboutToShowGroup" => {
+            invocation.return_value(Some(&(Vec::<i32>::new(), Vec::<i32>::new()).to_variant()));
+        }
+        _ => invocation.return_dbus_error("org.freedesktop.DBus.Error.UnknownMethod", method),
+    }
+}
+
+fn activate_menu_item(id: i32) {
+    // Defer so the D-Bus reply goes out before the (possibly slow) action runs
+    glib::idle_add_local_once(move || match id {
+        MENU_STOP => activate_app_action("stop-recording"),
+        MENU_PAUSE => activate_app_action("toggle-pause"),
+        MENU_OPEN_FOLDER => activate_app_action("open-recordings-folder"),
+        MENU_SHOW_SETTINGS => show_settings(),
+        _ => {}
+    });
+}
+
+fn activate_app_action(action: &str) {
+    if let Some(app) = gio::Application::default() {
+        app.activate_action(action, None);
+    }
+}
+
+fn show_settings() {
+    if let Some(app) = gio::Application::default() {
+        app.activate();
+    }
+}
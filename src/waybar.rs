@@ -0,0 +1,132 @@
+use gtk::glib;
+use std::io::Write;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use crate::json;
+use crate::recorder;
+use crate::recording;
+
+// `ummit-settings waybar`: streams the recorder state as JSON lines for a
+// Waybar custom module (return-type json)
+
+#[derive(Debug, Clone, PartialEq)]
+enum RecorderState {
+    Idle,
+    Recording { file: String, elapsed: Duration, paused: bool },
+    // A recorder running, but not started by ummit-settings
+    External(&'static str),
+}
+
+pub fn run() -> glib::ExitCode {
+    let stdout = std::io::stdout();
+    let mut last_line = String::new();
+
+    loop {
+        let line = status_json(&read_state());
+
+        // Only emit when something changed (the elapsed time changes every second)
+        if line != last_line {
+            let mut out = stdout.lock();
+            if writeln!(out, "{}", line).and_then(|_| out.flush()).is_err() {
+                // Waybar closed the pipe
+                return glib::ExitCode::SUCCESS;
+            }
+            last_line = line;
+        }
+
+        thread::sleep(Duration::from_secs(1));
+    }
+}
+
+fn read_state() -> RecorderState {
+    let key_file = glib::KeyFile::new();
+    let loaded = key_file
+        .load_from_file(recording::state_file(), glib::KeyFileFlags::NONE)
+        .is_ok();
+
+    if loaded {
+        let pid = key_file.int64("Recording", "pid").unwrap_or(0);
+
+        // Ignore stale state left behind by a crashed instance
+        if pid > 0 && Path::new(&format!("/proc/{}", pid)).exists() {
+            let now = chrono::Local::now().timestamp();
+            let started = key_file.int64("Recording", "started").unwrap_or(now);
+            let paused_total = key_file.int64("Recording", "paused-total").unwrap_or(0);
+            let paused_since = key_file.int64("Recording", "paused-since").unwrap_or(0);
+            let paused_now = if paused_since > 0 { now - paused_since } else { 0 };
+            let elapsed = (now - started - paused_total - paused_now).max(0);
+
+            return RecorderState::Recording {
+                file: key_file.string("Recording", "file").map(|file| file.to_string()).unwrap_or_default(),
+                elapsed: Duration::from_secs(elapsed as u64),
+                paused: paused_since > 0,
+            };
+        }
+    }
+
+    match recorder::any_running() {
+        Some(name) => RecorderState::External(name),
+        None => RecorderState::Idle,
+    }
+}
+
+fn status_json(state: &RecorderState) -> String {
+    let (text, tooltip, class) = match state {
+        RecorderState::Idle => (
+            "Record".to_string(),
+            "Not recording\nClick to start recording".to_string(),
+            "idle",
+        ),
+        RecorderState::Recording { file, elapsed, paused } => {
+            let elapsed = recording::format_elapsed(*elapsed);
+            if *paused {
+                (elapsed, format!("Recording paused\n{}\nRight-click to resume", file), "paused")
+            } else {
+                (elapsed, format!("Recording\n{}\nClick to stop, right-click to pause", file), "recording")
+            }
+        }
+        RecorderState::External(name) => (
+            "REC".to_string(),
+            format!("{} is running (not started by UmmItOS Settings)", name),
+            "recording",
+        ),
+    };
+
+    format!(
+        "{{\"text\": {}, \"tooltip\": {}, \"class\": {}, \"alt\": {}}}",
+        json::escape_string(&text),
+        json::escape_string(&tooltip),
+        json::escape_string(class),
+        json::escape_string(class)
+    )
+}
+
+// Ready-to-paste Waybar module configuration and style
+pub fn module_snippet() -> String {
+    r#"// ~/.config/waybar/config: add "custom/recording" to a modules-* list
+"custom/recording": {
+    "exec": "ummit-settings waybar",
+    "return-type": "json",
+    "format": "{icon} {}",
+    "format-icons": {
+        "idle": "○",
+        "recording": "●",
+        "paused": "⏸"
+    },
+    "on-click": "ummit-settings record-toggle",
+    "on-click-right": "ummit-settings record-pause",
+    "on-click-middle": "ummit-settings open-folder"
+},
+
+/* ~/.config/waybar/style.css */
+#custom-recording.recording {
+    color: #ff5555;
+}
+
+#custom-recording.paused {
+    color: #f1fa8c;
+}"#
+        .to_string()
+}